@@ -1,27 +1,42 @@
+use std::collections::HashMap;
 use std::io::{Result as IoResult, Write};
 use std::sync::atomic::{AtomicBool, Ordering};
 
+use chrono::{Local, NaiveDate, TimeDelta};
 use flexi_logger::{
     Age, Cleanup, Criterion, DeferredNow, FileSpec, LogSpecification, Logger,
     Naming,
 };
-use log::{error, warn, Level, Record};
+use log::{error, info, warn, Level, Record};
 use sticky_models::error::Error;
-use sticky_models::models::{Note, NoteSearchHit};
+use sticky_models::geometry::Arrangement;
+use sticky_models::models::{
+    Backlink, DailyNote, ExpiredNote, LinkGraph, Note, NoteDiagnostic,
+    NoteSearchHit, NoteSummary, NoteTemplate, TagCount, Task, TaskFilter,
+    TaskRef,
+};
 use sticky_models::queries::{
-    delete_note, get_note, list_notes, note_path, notes_dir, search_notes,
-    upsert_note,
+    convert_note_to_utf8, create_from_template, daily_note, delete_note,
+    get_note, get_note_summary, link_graph, list_backlinks, list_daily_notes,
+    list_note_summaries, list_notes, list_tags, list_tasks, list_templates,
+    move_pin, note_diagnostics, note_path, notes_dir, rename_tag,
+    rewrite_links, search_notes, take_stale_links, templates_dir,
+    toggle_archived, toggle_pinned, toggle_task, upsert_note,
 };
+use sticky_models::store::note_title;
+use sticky_models::sweeper::NOTES_EXPIRED;
 use sticky_models::watcher::NOTES_CHANGED;
 use tauri::webview::PageLoadEvent;
 use tauri::{
-    App, AppHandle, Emitter, Manager, RunEvent, Runtime, WebviewWindow,
-    WindowEvent,
+    App, AppHandle, Emitter, Listener, Manager, RunEvent, Runtime,
+    WebviewWindow, WindowEvent,
 };
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons};
 use tauri_plugin_window_state::{AppHandleExt, StateFlags, WindowExt};
 
 #[cfg(target_os = "macos")]
 mod mac_window;
+mod reminders;
 mod tray;
 mod update;
 mod window;
@@ -189,6 +204,12 @@ async fn cmd_open_command_window(
     auto_size: bool,
 ) -> Result<(), String> {
     let label = window::command_window_label(window.label());
+    let note = match &note_id {
+        Some(id) => get_note_summary(window.app_handle(), id).await.ok(),
+        None => None,
+    };
+    let archived = note.as_ref().is_some_and(|note| note.archived);
+    let pinned = note.as_ref().is_some_and(|note| note.pinned);
     if let Some(w) = window.app_handle().webview_windows().get(&label) {
         if w.is_visible().unwrap_or(false) {
             let _ = w.hide();
@@ -196,6 +217,8 @@ async fn cmd_open_command_window(
             let context = serde_json::json!({
                 "noteId": note_id,
                 "autoSize": auto_size,
+                "archived": archived,
+                "pinned": pinned,
             });
             let _ = w.emit_to(label.as_str(), "command:reset", context);
             window::present_command_window(&window, w);
@@ -205,8 +228,11 @@ async fn cmd_open_command_window(
 
     // First open without a pre-warmed panel: the context travels in the
     // query string; the webview is not ready to receive events yet.
-    let mut url =
-        format!("/commands?parent={}&autoSize={auto_size}", window.label());
+    let mut url = format!(
+        "/commands?parent={}&autoSize={auto_size}&archived={archived}\
+         &pinned={pinned}",
+        window.label()
+    );
     if let Some(id) = note_id {
         url.push_str(&format!("&noteId={id}"));
     }
@@ -237,6 +263,16 @@ async fn cmd_note_path<R: Runtime>(
     Ok(path.to_string_lossy().into_owned())
 }
 
+// Rewrites the note's file as plain UTF-8 with `\n` line endings;
+// saves otherwise keep whatever encoding the file came in.
+#[tauri::command]
+async fn cmd_convert_note_to_utf8<R: Runtime>(
+    note_id: String,
+    app_handle: AppHandle<R>,
+) -> Result<(), Error> {
+    convert_note_to_utf8(&app_handle, &note_id).await
+}
+
 // Opens the folder holding the note files in Finder.
 #[tauri::command]
 async fn cmd_open_notes_dir<R: Runtime>(
@@ -289,22 +325,7 @@ async fn cmd_show_toast(
     window: WebviewWindow,
     message: String,
 ) -> Result<(), String> {
-    let label = window::toast_window_label(window.label());
-    if let Some(w) = window.app_handle().webview_windows().get(&label) {
-        let _ = w.emit_to(label.as_str(), "toast:show", message);
-        return Ok(());
-    }
-
-    // The first message travels percent-encoded in the query string;
-    // the webview is not ready to receive events yet.
-    let mut url = tauri::Url::parse("tauri://localhost/toast")
-        .expect("Toast panel base URL should parse");
-    url.query_pairs_mut()
-        .append_pair("parent", window.label())
-        .append_pair("message", &message);
-    let url = format!("{}?{}", url.path(), url.query().unwrap_or_default());
-
-    window::create_toast_window(&window, &url);
+    window::show_toast(&window, &message);
     Ok(())
 }
 
@@ -354,11 +375,45 @@ async fn cmd_list_notes<R: Runtime>(
 }
 
 #[tauri::command]
-async fn cmd_get_note<R: Runtime>(
-    id: String,
+async fn cmd_list_note_summaries<R: Runtime>(
     app_handle: AppHandle<R>,
+) -> Result<Vec<NoteSummary>, Error> {
+    list_note_summaries(&app_handle).await
+}
+
+// Note windows load their own note through here on opening, on every
+// navigation and after every change, which makes it the place to keep
+// their tint in step with the note's color label.
+#[tauri::command]
+async fn cmd_get_note(
+    id: String,
+    window: WebviewWindow,
 ) -> Result<Note, Error> {
-    get_note(&app_handle, &id).await
+    let note = get_note(window.app_handle(), &id).await?;
+    if window.label().starts_with(MAIN_WINDOW_PREFIX) {
+        window::tint_note_window(&window, note.color);
+        window::track_note(&window, &note.id, &note_title(&note.content));
+    }
+    Ok(note)
+}
+
+// Records whether the calling note window was resized by hand, which
+// its note's remembered frame keeps.
+#[tauri::command]
+async fn cmd_set_manually_sized(
+    window: WebviewWindow,
+    manually_sized: bool,
+) -> Result<(), String> {
+    window::set_manually_sized(&window, manually_sized);
+    Ok(())
+}
+
+#[tauri::command]
+async fn cmd_note_diagnostics<R: Runtime>(
+    id: String,
+    app_handle: AppHandle<R>,
+) -> Result<Vec<NoteDiagnostic>, Error> {
+    note_diagnostics(&app_handle, &id).await
 }
 
 #[tauri::command]
@@ -387,10 +442,303 @@ async fn cmd_delete_note<R: Runtime>(
     app_handle: AppHandle<R>,
 ) -> Result<(), Error> {
     delete_note(&app_handle, &note_id).await?;
+    // Deleting from a note window or the search panel moves the window
+    // on to another note; the registry must not close it first.
+    window::release_note(&app_handle, &note_id);
     let _ = app_handle.emit(NOTES_CHANGED, Some(note_id));
     Ok(())
 }
 
+#[tauri::command]
+async fn cmd_list_tags<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<Vec<TagCount>, Error> {
+    list_tags(&app_handle).await
+}
+
+#[tauri::command]
+async fn cmd_rename_tag<R: Runtime>(
+    from: String,
+    to: String,
+    app_handle: AppHandle<R>,
+) -> Result<Vec<String>, Error> {
+    let renamed = rename_tag(&app_handle, &from, &to).await?;
+    if !renamed.is_empty() {
+        // Open notes reload their rewritten content.
+        let _ = app_handle.emit(NOTES_CHANGED, None::<String>);
+    }
+    Ok(renamed)
+}
+
+#[tauri::command]
+async fn cmd_list_backlinks<R: Runtime>(
+    id: String,
+    app_handle: AppHandle<R>,
+) -> Result<Vec<Backlink>, Error> {
+    list_backlinks(&app_handle, &id).await
+}
+
+#[tauri::command]
+async fn cmd_link_graph<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<LinkGraph, Error> {
+    link_graph(&app_handle).await
+}
+
+// Called when a note's editor settles (its window loses focus or
+// closes): if the note's title changed and other notes still link to
+// the old one, asks whether to update those links. Asking per save
+// would interrupt every keystroke of a title. Returns the ids of the
+// notes rewritten.
+#[tauri::command]
+async fn cmd_offer_link_rewrite<R: Runtime>(
+    note_id: String,
+    app_handle: AppHandle<R>,
+) -> Result<Vec<String>, Error> {
+    let Some(stale) = take_stale_links(&app_handle, &note_id).await? else {
+        return Ok(Vec::new());
+    };
+
+    let count = match stale.note_ids.len() {
+        1 => "1 note links".to_string(),
+        n => format!("{n} notes link"),
+    };
+    let message = format!(
+        "{count} to “{}”. Update the links to “{}”?",
+        stale.old_title, stale.new_title
+    );
+    // Blocking is fine here: this runs on an async worker thread while
+    // the dialog itself is presented on the main thread.
+    let confirmed = app_handle
+        .dialog()
+        .message(message)
+        .title("Note Renamed")
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Update Links".into(),
+            "Keep".into(),
+        ))
+        .blocking_show();
+    if !confirmed {
+        return Ok(Vec::new());
+    }
+
+    let rewritten =
+        rewrite_links(&app_handle, &stale.old_title, &stale.new_title).await?;
+    if !rewritten.is_empty() {
+        let _ = app_handle.emit(NOTES_CHANGED, None::<String>);
+    }
+    Ok(rewritten)
+}
+
+#[tauri::command]
+async fn cmd_list_tasks<R: Runtime>(
+    filter: Option<TaskFilter>,
+    app_handle: AppHandle<R>,
+) -> Result<Vec<Task>, Error> {
+    list_tasks(&app_handle, &filter.unwrap_or_default()).await
+}
+
+#[tauri::command]
+async fn cmd_toggle_task<R: Runtime>(
+    note_id: String,
+    task_ref: TaskRef,
+    app_handle: AppHandle<R>,
+) -> Result<Task, Error> {
+    let task = toggle_task(&app_handle, &note_id, &task_ref).await?;
+    // The note's window reloads the ticked checkbox.
+    let _ = app_handle.emit(NOTES_CHANGED, Some(note_id));
+    Ok(task)
+}
+
+// Archives the note, or unarchives an archived one, returning whether
+// it is now archived.
+#[tauri::command]
+async fn cmd_toggle_archived<R: Runtime>(
+    note_id: String,
+    app_handle: AppHandle<R>,
+) -> Result<bool, Error> {
+    let archived = toggle_archived(&app_handle, &note_id).await?;
+    // The tray and the open lists drop or regain the note.
+    let _ = app_handle.emit(NOTES_CHANGED, Some(note_id));
+    Ok(archived)
+}
+
+// Pins the note, or unpins a pinned one, returning whether it is now
+// pinned.
+#[tauri::command]
+async fn cmd_toggle_pinned<R: Runtime>(
+    note_id: String,
+    app_handle: AppHandle<R>,
+) -> Result<bool, Error> {
+    let pinned = toggle_pinned(&app_handle, &note_id).await?;
+    let _ = app_handle.emit(NOTES_CHANGED, Some(note_id));
+    Ok(pinned)
+}
+
+// Moves a pinned note to `index` among the pins.
+#[tauri::command]
+async fn cmd_move_pin<R: Runtime>(
+    note_id: String,
+    index: usize,
+    app_handle: AppHandle<R>,
+) -> Result<(), Error> {
+    if move_pin(&app_handle, &note_id, index).await? {
+        // Pins live outside the notes, so the watcher won't notice.
+        let _ = app_handle.emit(NOTES_CHANGED, None::<String>);
+    }
+    Ok(())
+}
+
+// Lists the saved window layouts by name.
+#[tauri::command]
+async fn cmd_list_layouts(app_handle: AppHandle) -> Vec<String> {
+    window::layout_names(&app_handle)
+}
+
+// Saves the visible note windows as the layout `name`.
+#[tauri::command]
+async fn cmd_save_layout(
+    name: String,
+    app_handle: AppHandle,
+) -> Result<(), Error> {
+    window::save_layout(&app_handle, &name)
+}
+
+// Puts the windows of the layout `name` on screen.
+#[tauri::command]
+async fn cmd_apply_layout(
+    name: String,
+    app_handle: AppHandle,
+) -> Result<(), Error> {
+    window::apply_layout(&app_handle, &name)
+}
+
+#[tauri::command]
+async fn cmd_delete_layout(
+    name: String,
+    app_handle: AppHandle,
+) -> Result<bool, Error> {
+    window::delete_layout(&app_handle, &name)
+}
+
+// Cascades, tiles, stacks, or gathers the visible note windows.
+#[tauri::command]
+async fn cmd_arrange_windows(arrangement: Arrangement, app_handle: AppHandle) {
+    window::arrange_windows(&app_handle, arrangement);
+}
+
+#[tauri::command]
+async fn cmd_list_templates<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<Vec<NoteTemplate>, Error> {
+    list_templates(&app_handle).await
+}
+
+// Opens the vault's templates folder in Finder, creating it first so
+// there is somewhere to drop the first template.
+#[tauri::command]
+async fn cmd_open_templates_dir<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<(), String> {
+    let dir = templates_dir(&app_handle).await.map_err(|e| e.to_string())?;
+    tauri_plugin_opener::open_path(dir, None::<&str>).map_err(|e| e.to_string())
+}
+
+// Creates a note from a template. `values` holds the title and the
+// answers to the template's prompts; `{{clipboard}}` is read here, as
+// the webview can't read the clipboard without asking.
+#[tauri::command]
+async fn cmd_create_from_template<R: Runtime>(
+    template: String,
+    mut values: HashMap<String, String>,
+    app_handle: AppHandle<R>,
+) -> Result<Note, Error> {
+    if let Some(text) = clipboard_text() {
+        values.entry("clipboard".to_string()).or_insert(text);
+    }
+
+    let note = create_from_template(&app_handle, &template, &values).await?;
+    let _ = app_handle.emit(NOTES_CHANGED, Some(note.id.clone()));
+    Ok(note)
+}
+
+// Opens the daily note `offset` days from today, creating it if needed,
+// in the window already showing it or a new one.
+#[tauri::command]
+async fn cmd_open_daily_note(
+    offset: i64,
+    app_handle: AppHandle,
+) -> Result<Note, Error> {
+    let date = Local::now().date_naive() + TimeDelta::days(offset);
+    let note = daily_note(&app_handle, date).await?;
+    let _ = app_handle.emit(NOTES_CHANGED, Some(note.id.clone()));
+    window::show_note_window(&app_handle, &note.id);
+    Ok(note)
+}
+
+// Lists the days between `from` and `to`, both included, that have a
+// daily note, for a calendar.
+#[tauri::command]
+async fn cmd_list_daily_notes<R: Runtime>(
+    from: NaiveDate,
+    to: NaiveDate,
+    app_handle: AppHandle<R>,
+) -> Result<Vec<DailyNote>, Error> {
+    list_daily_notes(&app_handle, from, to).await
+}
+
+// Closes the windows of the notes the sweeper expires, and says what
+// expired in a toast over a note window still showing.
+fn listen_for_expired_notes(app: &App) {
+    let handle = app.handle().clone();
+    app.listen(NOTES_EXPIRED, move |event| {
+        let notes: Vec<ExpiredNote> =
+            match serde_json::from_str(event.payload()) {
+                Ok(notes) => notes,
+                Err(e) => {
+                    warn!("Unreadable {NOTES_EXPIRED} payload: {e}");
+                    return;
+                }
+            };
+        let app = handle.clone();
+        let _ = handle.run_on_main_thread(move || close_expired(&app, &notes));
+    });
+}
+
+fn close_expired(app: &AppHandle, notes: &[ExpiredNote]) {
+    let mut shown: Option<WebviewWindow> = None;
+    for (note_id, w) in window::note_windows(app) {
+        if notes.iter().any(|note| note.id == note_id) {
+            // Released first, so closing doesn't remember a frame for
+            // a note that is gone; its editor's last save is refused.
+            window::release_note(app, &note_id);
+            let _ = w.close();
+        } else if w.is_visible().unwrap_or(false)
+            && (shown.is_none() || w.is_focused().unwrap_or(false))
+        {
+            shown = Some(w);
+        }
+    }
+
+    let message = match notes {
+        [note] => format!("“{}” expired", note.title),
+        _ => format!("{} notes expired", notes.len()),
+    };
+    match shown {
+        Some(w) => window::show_toast(&w, &message),
+        None => info!("{message}"),
+    }
+}
+
+// The plain text on the clipboard, if there is any.
+fn clipboard_text() -> Option<String> {
+    #[cfg(target_os = "macos")]
+    return mac_window::clipboard_text();
+
+    #[cfg(not(target_os = "macos"))]
+    None
+}
+
 pub fn custom_colored_format(
     w: &mut dyn Write,
     now: &mut DeferredNow,
@@ -462,16 +810,17 @@ pub fn run() {
             tauri_plugin_window_state::Builder::new()
                 .with_state_flags(StateFlags::all() - StateFlags::VISIBLE)
                 .skip_initial_state(&format!("{MAIN_WINDOW_PREFIX}0"))
-                // Only the primary window persists its geometry. Every
-                // other note window is transient: its `main_N` label is a
-                // recycled slot, not a note identity, so restoring by it
-                // would open new notes at some previous window's size.
-                // They open at their creation size and let autosize grow
-                // to fit the content instead.
+                // Only the primary window persists its geometry here.
+                // Every other note window's `main_N` label is a recycled
+                // slot, not a note identity, so restoring by it would
+                // open notes at some previous window's size; their
+                // frames are remembered per note instead (see
+                // `window::frames`).
                 .with_filter(|label| label == format!("{MAIN_WINDOW_PREFIX}0"))
                 .build(),
         )
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
         .plugin(sticky_models::plugin::init())
@@ -503,9 +852,14 @@ pub fn run() {
             }
 
             tray::init(app_handle)?;
+            reminders::init(app_handle);
+            listen_for_expired_notes(app_handle);
+            window::init_registry(app_handle);
+            window::watch_monitors(app_handle);
             app_handle.manage(AppState::default());
             app_handle.manage(window::PanelState::default());
             app_handle.manage(window::ToastState::default());
+            app_handle.manage(window::WindowNotes::default());
 
             Ok(())
         });
@@ -518,20 +872,44 @@ pub fn run() {
             cmd_new_child_window,
             cmd_new_main_window,
             cmd_snap_window_to_corner,
+            cmd_set_manually_sized,
             cmd_open_command_window,
             cmd_open_link_window,
             cmd_open_notes_dir,
             cmd_open_search_window,
             cmd_reveal_note,
             cmd_note_path,
+            cmd_convert_note_to_utf8,
             cmd_popup_format_menu,
             cmd_show_toast,
             cmd_present_toast,
             cmd_list_notes,
+            cmd_list_note_summaries,
             cmd_get_note,
+            cmd_note_diagnostics,
             cmd_search_notes,
             cmd_upsert_note,
             cmd_delete_note,
+            cmd_list_tags,
+            cmd_rename_tag,
+            cmd_list_backlinks,
+            cmd_link_graph,
+            cmd_offer_link_rewrite,
+            cmd_list_tasks,
+            cmd_toggle_task,
+            cmd_toggle_archived,
+            cmd_toggle_pinned,
+            cmd_move_pin,
+            cmd_list_layouts,
+            cmd_save_layout,
+            cmd_apply_layout,
+            cmd_delete_layout,
+            cmd_arrange_windows,
+            cmd_list_templates,
+            cmd_open_templates_dir,
+            cmd_create_from_template,
+            cmd_open_daily_note,
+            cmd_list_daily_notes,
         ])
         .build(tauri::generate_context!())
         .expect("error while running tauri application")
@@ -552,8 +930,13 @@ pub fn run() {
                 }
 
                 RunEvent::Ready => {
-                    debug_log!("Application is ready, creating main window");
                     update::check_in_background(app_handle);
+                    if window::restore_session(app_handle) {
+                        debug_log!("Application is ready, restoring session");
+                        return;
+                    }
+
+                    debug_log!("Application is ready, creating main window");
                     let handle = app_handle.clone();
                     let window =
                         window::create_main_window(&handle, "/", None, None);
@@ -564,6 +947,9 @@ pub fn run() {
                         ) {
                             Ok(_) => {
                                 debug_log!("Restored window size successfully");
+                                // Saved on a monitor since disconnected,
+                                // it would open out of reach.
+                                window::rescue_window(&window);
                             }
                             Err(e) => {
                                 error!("Failed to restore window size: {:?}", e)
@@ -578,6 +964,10 @@ pub fn run() {
                     ..
                 } => {
                     debug_log!("Window focused: {}", label);
+                    if let Some(window) = app_handle.get_webview_window(&label)
+                    {
+                        window::mark_focused(&window);
+                    }
                 }
 
                 RunEvent::WindowEvent {
@@ -586,6 +976,13 @@ pub fn run() {
                     ..
                 } => {
                     debug_log!("Window close requested: {}", label);
+                    if label.starts_with(MAIN_WINDOW_PREFIX) {
+                        if let Some(window) =
+                            app_handle.get_webview_window(&label)
+                        {
+                            window::remember_frame(&window);
+                        }
+                    }
                     let is_first_main_window =
                         label == format!("{MAIN_WINDOW_PREFIX}0");
                     // Utility windows (like the search panel) don't count
@@ -613,6 +1010,14 @@ pub fn run() {
                         );
                     }
                 }
+
+                RunEvent::WindowEvent {
+                    event: WindowEvent::Destroyed,
+                    label,
+                    ..
+                } => {
+                    window::forget_window(app_handle, &label);
+                }
                 _ => {}
             };
         })
@@ -1,6 +1,8 @@
 #![allow(deprecated)]
 
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{LazyLock, Mutex};
 
 use objc::{class, msg_send, sel, sel_impl};
 use tauri::{Emitter, Runtime, WebviewWindow};
@@ -55,6 +57,11 @@ const DIMMED_GREY: (f64, f64, f64) = (0.722, 0.722, 0.741);
 // (#4F4F53).
 const DIMMED_GREY_DARK: (f64, f64, f64) = (0.31, 0.31, 0.325);
 
+// The color labels of the note windows, by label, which tint their
+// decorative minimize/zoom circles in place of the dimmed grey.
+static WINDOW_TINTS: LazyLock<Mutex<HashMap<String, (f64, f64, f64)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
 // The glyphs (x, -, +) shown inside the circles on hover.
 const GLYPH_INSET: f64 = 4.5;
 const GLYPH_LINE_WIDTH: f64 = 1.5;
@@ -249,6 +256,25 @@ pub fn is_left_mouse_down() -> bool {
     buttons & 1 != 0
 }
 
+// The plain text on the general pasteboard, if there is any.
+pub fn clipboard_text() -> Option<String> {
+    use cocoa::base::{id, nil};
+    use cocoa::foundation::NSString;
+
+    #[allow(unexpected_cfgs)]
+    unsafe {
+        let pasteboard: id = msg_send![class!(NSPasteboard), generalPasteboard];
+        let kind = NSString::alloc(nil).init_str("public.utf8-plain-text");
+        let text: id = msg_send![pasteboard, stringForType: kind];
+        let _: () = msg_send![kind, release];
+        if text == nil {
+            return None;
+        }
+        let bytes = text.UTF8String();
+        Some(std::ffi::CStr::from_ptr(bytes).to_string_lossy().into_owned())
+    }
+}
+
 // Records the click count of every left mousedown before it is
 // dispatched. WebKit's own counter (e.detail) resets once the native
 // drag session started by a press on a drag region swallows the
@@ -521,6 +547,7 @@ fn position_traffic_lights(
         };
         let close_rgb =
             if is_key_window == YES { CLOSE_RED } else { dimmed_grey };
+        let tint = WINDOW_TINTS.lock().unwrap().get(&label).copied();
 
         let window_buttons = vec![close, miniaturize, zoom];
         let space_between = TRAFFIC_LIGHT_SPACING;
@@ -658,7 +685,8 @@ fn position_traffic_lights(
                 let _: () = msg_send![circle, setFrame: circle_rect];
             }
 
-            let (r, g, b) = if i == 0 { close_rgb } else { dimmed_grey };
+            let (r, g, b) =
+                if i == 0 { close_rgb } else { tint.unwrap_or(dimmed_grey) };
             let color: id = msg_send![
                 class!(NSColor),
                 colorWithSRGBRed: r green: g blue: b alpha: 1.0f64
@@ -674,6 +702,35 @@ fn position_traffic_lights(
     }
 }
 
+/// Tints the note window's minimize/zoom circles with an RGB color, or
+/// restores their grey. Must run on the main thread.
+pub fn set_traffic_light_tint<R: Runtime>(
+    window: &WebviewWindow<R>,
+    tint: Option<(u8, u8, u8)>,
+) {
+    let label = window.label().to_string();
+    {
+        let mut tints = WINDOW_TINTS.lock().unwrap();
+        match tint {
+            Some((r, g, b)) => {
+                let rgb = |c: u8| f64::from(c) / 255.0;
+                tints.insert(label.clone(), (rgb(r), rgb(g), rgb(b)))
+            }
+            None => tints.remove(&label),
+        };
+    }
+
+    let Ok(ns_window) = window.ns_window() else {
+        return;
+    };
+    position_traffic_lights(
+        UnsafeWindowHandle(ns_window),
+        WINDOW_CONTROL_PAD_X,
+        WINDOW_CONTROL_PAD_Y,
+        label,
+    );
+}
+
 #[derive(Debug)]
 struct WindowState<R: Runtime> {
     window: WebviewWindow<R>,
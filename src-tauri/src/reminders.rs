@@ -0,0 +1,131 @@
+//! Fires note reminders (see [`sticky_models::reminders`]).
+//!
+//! A background thread sleeps until the next reminder is due, waking
+//! early whenever the notes change. A due reminder posts a system
+//! notification, brings its note up, and asks whether to dismiss or
+//! snooze it; the answer is written back to the note's header.
+
+use std::collections::HashSet;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use chrono::{Local, NaiveDateTime, TimeDelta};
+use log::warn;
+use sticky_models::models::NoteSummary;
+use sticky_models::store::NotesStore;
+use sticky_models::watcher::NOTES_CHANGED;
+use tauri::{App, AppHandle, Emitter, Listener, Manager};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::window;
+
+/// How long a snoozed reminder waits before firing again.
+const SNOOZE_MINUTES: i64 = 10;
+
+/// The longest the scheduler sleeps between checks, so reminders still
+/// fire on time after the clock jumps, as on waking from sleep.
+const MAX_SLEEP: Duration = Duration::from_secs(60);
+
+/// Start the reminder scheduler. Call once at setup.
+pub fn init(app: &App) {
+    let (wake, woken) = mpsc::channel();
+    app.listen(NOTES_CHANGED, move |_| {
+        let _ = wake.send(());
+    });
+
+    let handle = app.handle().clone();
+    thread::spawn(move || run(handle, woken));
+}
+
+/// Fire reminders as they come due, until the app exits.
+fn run(app: AppHandle, woken: Receiver<()>) {
+    // The reminders fired and not yet dismissed or snoozed, by note id
+    // and due time, so each fires once.
+    let mut fired: HashSet<(String, NaiveDateTime)> = HashSet::new();
+
+    loop {
+        // Archiving a note sets it aside, but keeps its reminder.
+        let notes = app.state::<NotesStore>().all_summaries();
+        let notes = notes.unwrap_or_else(|e| {
+            warn!("Reminders could not list notes: {e}");
+            Vec::new()
+        });
+        let now = Local::now().naive_local();
+
+        let mut due = HashSet::new();
+        let mut next: Option<NaiveDateTime> = None;
+        for note in &notes {
+            let Some(at) = note.reminder.as_ref().map(|r| r.due()) else {
+                continue;
+            };
+            if at > now {
+                next = Some(next.map_or(at, |next| next.min(at)));
+                continue;
+            }
+            let key = (note.id.clone(), at);
+            if !fired.contains(&key) {
+                fire(&app, note);
+            }
+            due.insert(key);
+        }
+        fired = due;
+
+        let sleep = next
+            .and_then(|next| (next - now).to_std().ok())
+            .map_or(MAX_SLEEP, |until| until.min(MAX_SLEEP));
+        if let Err(RecvTimeoutError::Disconnected) = woken.recv_timeout(sleep) {
+            return;
+        }
+    }
+}
+
+/// Announce a due reminder and bring its note up, then ask what to do
+/// with it on a thread of its own.
+fn fire(app: &AppHandle, note: &NoteSummary) {
+    if let Err(e) =
+        app.notification().builder().title("Reminder").body(&note.title).show()
+    {
+        warn!("Could not post the reminder for {}: {e}", note.id);
+    }
+
+    let handle = app.clone();
+    let note_id = note.id.clone();
+    let _ = app.run_on_main_thread(move || {
+        window::show_note_window(&handle, &note_id);
+    });
+
+    let app = app.clone();
+    let note = note.clone();
+    thread::spawn(move || ask(&app, &note));
+}
+
+/// Ask whether to dismiss or snooze a fired reminder, and write the
+/// answer to the note. Closing the dialog snoozes, so a reminder is
+/// never lost to a stray keypress.
+fn ask(app: &AppHandle, note: &NoteSummary) {
+    let snooze = format!("Snooze {SNOOZE_MINUTES} Minutes");
+    let dismissed = app
+        .dialog()
+        .message(&note.title)
+        .title("Reminder")
+        .buttons(MessageDialogButtons::OkCancelCustom("Dismiss".into(), snooze))
+        .blocking_show();
+
+    let store = app.state::<NotesStore>();
+    let now = Local::now().naive_local();
+    let answered = if dismissed {
+        store.dismiss_reminder(&note.id, now)
+    } else {
+        let until = now + TimeDelta::minutes(SNOOZE_MINUTES);
+        store.snooze_reminder(&note.id, until)
+    };
+    match answered {
+        Ok(true) => {
+            let _ = app.emit(NOTES_CHANGED, Some(note.id.clone()));
+        }
+        Ok(false) => {}
+        Err(e) => warn!("Could not update the reminder of {}: {e}", note.id),
+    }
+}
@@ -1,19 +1,27 @@
 //! The menu bar tray icon.
 //!
 //! The app runs as an accessory with no Dock icon, so the tray is its
-//! only persistent entry point: it opens and lists notes, toggles
-//! their visibility, and quits — even while no window is open.
+//! only persistent entry point: it opens and lists notes, opens daily
+//! notes, creates notes from templates, applies window layouts,
+//! arranges the windows, toggles the notes' visibility, and quits —
+//! even while no window is open.
 
+use std::collections::HashSet;
+
+use chrono::{Local, TimeDelta};
 use log::warn;
-use sticky_models::store::{note_title, NotesStore};
+use sticky_models::colors;
+use sticky_models::geometry::Arrangement;
+use sticky_models::store::NotesStore;
 use sticky_models::watcher::NOTES_CHANGED;
-use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem, Submenu};
 use tauri::tray::TrayIconBuilder;
-use tauri::{include_image, App, AppHandle, Listener, Manager, Wry};
+use tauri::{include_image, App, AppHandle, Emitter, Listener, Manager, Wry};
 use tauri_plugin_window_state::{AppHandleExt, StateFlags};
 
 use crate::window::{
-    self, MAIN_WINDOW_PREFIX, MIN_WINDOW_HEIGHT, MIN_WINDOW_WIDTH,
+    self, LAYOUTS_CHANGED, MAIN_WINDOW_PREFIX, MIN_WINDOW_HEIGHT,
+    MIN_WINDOW_WIDTH,
 };
 
 /// The tray icon's registration id.
@@ -27,12 +35,35 @@ const MAX_MENU_TITLE_LEN: usize = 40;
 
 /// The fixed menu items' ids.
 const NEW_NOTE: &str = "tray_new_note";
+const OPEN_TEMPLATES: &str = "tray_open_templates";
+const SAVE_LAYOUT: &str = "tray_save_layout";
 const TOGGLE_NOTES: &str = "tray_toggle_notes";
 const QUIT: &str = "tray_quit";
 
 /// Id prefix of the recent-note items; the note id follows.
 const NOTE_PREFIX: &str = "tray_note:";
 
+/// Id prefix of the template items; the template name follows.
+const TEMPLATE_PREFIX: &str = "tray_template:";
+
+/// Id prefix of the daily note items; the days from today follow.
+const DAILY_PREFIX: &str = "tray_daily:";
+
+/// Id prefix of the layout items; the layout name follows.
+const LAYOUT_PREFIX: &str = "tray_layout:";
+
+/// Id prefix of the arrangement items; the index into
+/// [`ARRANGEMENTS`] follows.
+const ARRANGE_PREFIX: &str = "tray_arrange:";
+
+/// The Arrange Windows submenu's items.
+const ARRANGEMENTS: [(Arrangement, &str); 4] = [
+    (Arrangement::Cascade, "Cascade"),
+    (Arrangement::Tile, "Tile"),
+    (Arrangement::StackRight, "Stack Along Right Edge"),
+    (Arrangement::Gather, "Gather from All Monitors"),
+];
+
 /// Build the tray icon with its menu and keep the menu's recent-notes
 /// section following the store. Call once at setup.
 pub fn init(app: &App) -> tauri::Result<()> {
@@ -44,14 +75,17 @@ pub fn init(app: &App) -> tauri::Result<()> {
         .build(app.handle())?;
 
     // Saves, deletes, and external file edits all surface as this
-    // event; rebuild the recent-notes section on each.
-    let handle = app.handle().clone();
-    app.listen(NOTES_CHANGED, move |_| {
-        let app = handle.clone();
-        // Menus are AppKit objects; they must be touched on the main
-        // thread.
-        let _ = handle.run_on_main_thread(move || refresh(&app));
-    });
+    // event; rebuild the recent-notes section on each. Layouts are
+    // listed too.
+    for event in [NOTES_CHANGED, LAYOUTS_CHANGED] {
+        let handle = app.handle().clone();
+        app.listen(event, move |_| {
+            let app = handle.clone();
+            // Menus are AppKit objects; they must be touched on the
+            // main thread.
+            let _ = handle.run_on_main_thread(move || refresh(&app));
+        });
+    }
 
     Ok(())
 }
@@ -72,8 +106,8 @@ fn refresh(app: &AppHandle) {
     }
 }
 
-/// The tray menu: New Note, the most recent notes, Show/Hide All,
-/// Quit.
+/// The tray menu: New Note, New from Template, Daily Note, the most
+/// recent notes, Layouts, Show/Hide All, Quit.
 fn build_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
     let menu = Menu::new(app)?;
     let item = |id: &str, text: &str| {
@@ -81,8 +115,10 @@ fn build_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
     };
 
     menu.append(&item(NEW_NOTE, "New Note")?)?;
+    menu.append(&templates_menu(app)?)?;
+    menu.append(&daily_menu(app)?)?;
 
-    let notes = app.state::<NotesStore>().list().unwrap_or_else(|e| {
+    let notes = app.state::<NotesStore>().summaries().unwrap_or_else(|e| {
         warn!("Tray menu could not list notes: {e}");
         Vec::new()
     });
@@ -90,12 +126,31 @@ fn build_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
     if !notes.is_empty() {
         menu.append(&PredefinedMenuItem::separator(app)?)?;
     }
-    for note in notes.iter().take(MAX_RECENT_NOTES) {
+    // Pinned notes come first and are always listed; only the recent
+    // ones after them are capped.
+    let (pinned, recent): (Vec<_>, Vec<_>) =
+        notes.iter().partition(|note| note.pinned);
+    for note in
+        pinned.into_iter().chain(recent.into_iter().take(MAX_RECENT_NOTES))
+    {
         let id = format!("{NOTE_PREFIX}{}", note.id);
-        menu.append(&item(&id, &menu_title(&note.content))?)?;
+        let mut text = menu_title(&note.title);
+        // Ephemeral notes carry an hourglass: they'll be gone soon.
+        if note.expires_at.is_some() {
+            text = format!("⏳ {text}");
+        }
+        if let Some(color) = note.color {
+            text = format!("{} {text}", colors::marker(color));
+        }
+        if note.pinned {
+            text = format!("📌 {text}");
+        }
+        menu.append(&item(&id, &text)?)?;
     }
 
     menu.append(&PredefinedMenuItem::separator(app)?)?;
+    menu.append(&layouts_menu(app)?)?;
+    menu.append(&arrange_menu(app)?)?;
     menu.append(&item(TOGGLE_NOTES, "Show/Hide All Notes")?)?;
     menu.append(&PredefinedMenuItem::separator(app)?)?;
     menu.append(&item(QUIT, "Quit Sticky")?)?;
@@ -103,9 +158,105 @@ fn build_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
     Ok(menu)
 }
 
+/// The New from Template submenu: a template per item, and a way to
+/// the folder holding them.
+fn templates_menu(app: &AppHandle) -> tauri::Result<Submenu<Wry>> {
+    let submenu = Submenu::new(app, "New from Template", true)?;
+    let store = app.state::<NotesStore>();
+    let templates = store.templates().unwrap_or_else(|e| {
+        warn!("Tray menu could not list templates: {e}");
+        Vec::new()
+    });
+
+    for template in &templates {
+        let id = format!("{TEMPLATE_PREFIX}{}", template.name);
+        let text = menu_title(&template.name);
+        submenu.append(&MenuItem::with_id(
+            app,
+            id,
+            text,
+            true,
+            None::<&str>,
+        )?)?;
+    }
+    if !templates.is_empty() {
+        submenu.append(&PredefinedMenuItem::separator(app)?)?;
+    }
+    submenu.append(&MenuItem::with_id(
+        app,
+        OPEN_TEMPLATES,
+        "Open Templates Folder",
+        true,
+        None::<&str>,
+    )?)?;
+
+    Ok(submenu)
+}
+
+/// The Daily Note submenu: yesterday's, today's and tomorrow's.
+fn daily_menu(app: &AppHandle) -> tauri::Result<Submenu<Wry>> {
+    let submenu = Submenu::new(app, "Daily Note", true)?;
+    for (offset, text) in [(0, "Today"), (-1, "Yesterday"), (1, "Tomorrow")] {
+        let id = format!("{DAILY_PREFIX}{offset}");
+        submenu.append(&MenuItem::with_id(
+            app,
+            id,
+            text,
+            true,
+            None::<&str>,
+        )?)?;
+    }
+    Ok(submenu)
+}
+
+/// The Layouts submenu: a layout per item, and a way to save the
+/// windows on screen as a new one.
+fn layouts_menu(app: &AppHandle) -> tauri::Result<Submenu<Wry>> {
+    let submenu = Submenu::new(app, "Layouts", true)?;
+    let names = window::layout_names(app);
+    for name in &names {
+        let id = format!("{LAYOUT_PREFIX}{name}");
+        submenu.append(&MenuItem::with_id(
+            app,
+            id,
+            menu_title(name),
+            true,
+            None::<&str>,
+        )?)?;
+    }
+    if !names.is_empty() {
+        submenu.append(&PredefinedMenuItem::separator(app)?)?;
+    }
+    submenu.append(&MenuItem::with_id(
+        app,
+        SAVE_LAYOUT,
+        "Save Current Layout",
+        true,
+        None::<&str>,
+    )?)?;
+
+    Ok(submenu)
+}
+
+/// The Arrange Windows submenu: the ways to arrange the visible note
+/// windows.
+fn arrange_menu(app: &AppHandle) -> tauri::Result<Submenu<Wry>> {
+    let submenu = Submenu::new(app, "Arrange Windows", true)?;
+    for (index, (_, text)) in ARRANGEMENTS.iter().enumerate() {
+        let id = format!("{ARRANGE_PREFIX}{index}");
+        submenu.append(&MenuItem::with_id(
+            app,
+            id,
+            text,
+            true,
+            None::<&str>,
+        )?)?;
+    }
+    Ok(submenu)
+}
+
 /// A note title clipped to fit the menu.
-fn menu_title(content: &str) -> String {
-    let title = note_title(content);
+fn menu_title(title: &str) -> String {
     let mut clipped: String = title.chars().take(MAX_MENU_TITLE_LEN).collect();
     if clipped.len() < title.len() {
         clipped.push('…');
@@ -117,11 +268,31 @@ fn menu_title(content: &str) -> String {
 fn handle_selection(app: &AppHandle, id: &str) {
     match id {
         NEW_NOTE => new_note(app),
+        OPEN_TEMPLATES => open_templates(app),
+        SAVE_LAYOUT => save_layout(app),
         TOGGLE_NOTES => toggle_notes(app),
         QUIT => quit(app),
         _ => {
             if let Some(note_id) = id.strip_prefix(NOTE_PREFIX) {
-                open_note(app, note_id);
+                window::show_note_window(app, note_id);
+            } else if let Some(name) = id.strip_prefix(TEMPLATE_PREFIX) {
+                new_note_from_template(app, name);
+            } else if let Some(offset) = id.strip_prefix(DAILY_PREFIX) {
+                if let Ok(offset) = offset.parse() {
+                    open_daily_note(app, offset);
+                }
+            } else if let Some(name) = id.strip_prefix(LAYOUT_PREFIX) {
+                if let Err(e) = window::apply_layout(app, name) {
+                    warn!("Could not apply the layout {name:?}: {e}");
+                }
+            } else if let Some(index) = id.strip_prefix(ARRANGE_PREFIX) {
+                let arrangement = index
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| ARRANGEMENTS.get(index));
+                if let Some((arrangement, _)) = arrangement {
+                    window::arrange_windows(app, *arrangement);
+                }
             }
         }
     }
@@ -138,31 +309,83 @@ fn new_note(app: &AppHandle) {
     );
 }
 
-/// Focus the window already showing the note, or open one for it.
-fn open_note(app: &AppHandle, note_id: &str) {
-    let path = format!("/{note_id}");
-    let existing = app.webview_windows().into_iter().find(|(label, w)| {
-        label.starts_with(MAIN_WINDOW_PREFIX)
-            && w.url().is_ok_and(|url| url.path() == path)
-    });
+/// Open a note window for a new note from a template; the `/new`
+/// route asks for the template's prompts, if it has any, then creates
+/// the note.
+fn new_note_from_template(app: &AppHandle, name: &str) {
+    let mut url = tauri::Url::parse("tauri://localhost/new")
+        .expect("New note base URL should parse");
+    url.query_pairs_mut().append_pair("template", name);
+    let url = format!("{}?{}", url.path(), url.query().unwrap_or_default());
 
-    if let Some((_, w)) = existing {
-        let _ = w.show();
-        let _ = w.set_focus();
-        return;
+    window::create_main_window(
+        app,
+        &url,
+        Some((MIN_WINDOW_WIDTH, MIN_WINDOW_HEIGHT)),
+        None,
+    );
+}
+
+/// Open the templates folder in Finder, creating it if needed.
+fn open_templates(app: &AppHandle) {
+    let dir = match app.state::<NotesStore>().templates_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            warn!("Could not create the templates folder: {e}");
+            return;
+        }
+    };
+    if let Err(e) = tauri_plugin_opener::open_path(dir, None::<&str>) {
+        warn!("Could not open the templates folder: {e}");
+    }
+}
+
+/// Save the note windows on screen as a new, numbered layout, which
+/// the rebuilt menu lists; the command palette saves them under a name
+/// of the user's choosing.
+fn save_layout(app: &AppHandle) {
+    if let Err(e) = window::save_new_layout(app) {
+        warn!("Could not save the layout: {e}");
     }
+}
 
-    window::create_main_window(app, &path, None, None);
+/// Open the daily note `offset` days from today, creating it if
+/// needed.
+fn open_daily_note(app: &AppHandle, offset: i64) {
+    let date = Local::now().date_naive() + TimeDelta::days(offset);
+    match app.state::<NotesStore>().daily_note(date) {
+        Ok(note) => {
+            let _ = app.emit(NOTES_CHANGED, Some(note.id.clone()));
+            window::show_note_window(app, &note.id);
+        }
+        Err(e) => warn!("Could not open the daily note of {date}: {e}"),
+    }
 }
 
 /// Hide every note window when any is visible; show them all
-/// otherwise. With no window open, open the most recent note.
+/// otherwise. Windows of archived notes are left out. With no window
+/// open, open the most recent note.
 fn toggle_notes(app: &AppHandle) {
+    let archived: HashSet<String> =
+        match app.state::<NotesStore>().all_summaries() {
+            Ok(notes) => notes
+                .into_iter()
+                .filter(|note| note.archived)
+                .map(|note| note.id)
+                .collect(),
+            Err(e) => {
+                warn!("Tray could not list archived notes: {e}");
+                HashSet::new()
+            }
+        };
     let windows: Vec<_> = app
         .webview_windows()
         .into_iter()
         .filter(|(label, _)| label.starts_with(MAIN_WINDOW_PREFIX))
         .map(|(_, w)| w)
+        .filter(|w| {
+            !window::shown_note(w).is_some_and(|id| archived.contains(&id))
+        })
         .collect();
 
     if windows.is_empty() {
@@ -185,6 +408,8 @@ fn toggle_notes(app: &AppHandle) {
 /// Quit the app. Saves window state like the Cmd+Q path, and the
 /// explicit exit code bypasses the keep-alive in `run`.
 fn quit(app: &AppHandle) {
+    window::remember_all_frames(app);
+    window::save_session(app);
     if let Err(e) = app.save_window_state(StateFlags::all()) {
         warn!("Failed to save window state on quit: {e:?}");
     }
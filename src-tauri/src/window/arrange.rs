@@ -0,0 +1,55 @@
+//! Arranging the note windows: cascading, tiling, or stacking those on
+//! the current monitor, or gathering them there from all monitors. The
+//! placement itself is [`sticky_models::geometry::arrange`].
+
+use sticky_models::geometry::{arrange, screen_of, Arrangement};
+use tauri::{LogicalPosition, LogicalSize};
+
+use super::main::MAX_WINDOW_WIDTH;
+use super::*;
+
+/// Arranges the visible note windows on the current monitor: the one
+/// holding the frontmost note window. Gathering takes the windows on
+/// every monitor there.
+pub fn arrange_windows(handle: &AppHandle, arrangement: Arrangement) {
+    let screens = frames::screens(handle);
+    let mut windows: Vec<_> = registry::note_windows(handle)
+        .into_iter()
+        .map(|(_, w)| w)
+        .filter(|w| w.is_visible().unwrap_or(false))
+        // Minimized windows have no current frame and stay put.
+        .filter_map(|w| Some((frames::current_frame(&w)?.bounds, w)))
+        .collect();
+    windows.sort_by_key(|(_, w)| registry::focused_at(w));
+
+    let Some(screen) = windows
+        .last()
+        .and_then(|(frame, _)| screen_of(frame, &screens))
+        .cloned()
+    else {
+        return;
+    };
+    if arrangement != Arrangement::Gather {
+        windows.retain(|(frame, _)| {
+            screen_of(frame, &screens).is_some_and(|s| *s == screen)
+        });
+    }
+
+    debug_log!("Arranging {} windows: {arrangement:?}", windows.len());
+    let current: Vec<_> = windows.iter().map(|(frame, _)| *frame).collect();
+    let placed = arrange(
+        arrangement,
+        &current,
+        &screen.area,
+        &screens,
+        MIN_WINDOW_WIDTH,
+        MAX_WINDOW_WIDTH,
+    );
+    // Focusing back to front keeps them stacked as they were, which a
+    // cascade relies on.
+    for ((_, w), bounds) in windows.iter().zip(placed) {
+        let _ = w.set_position(LogicalPosition::new(bounds.x, bounds.y));
+        let _ = w.set_size(LogicalSize::new(bounds.width, bounds.height));
+        let _ = w.set_focus();
+    }
+}
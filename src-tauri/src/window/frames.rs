@@ -0,0 +1,115 @@
+//! Per-note window frames: each note's window reopens where it was
+//! last closed (see [`sticky_models::state::Frames`]).
+//!
+//! Note windows live in recycled `main_N` slots, so the frame is
+//! remembered under the note the window showed: when the window
+//! closes, and when it switches to another note.
+
+use log::warn;
+use sticky_models::geometry::{clamp_to_work_areas, Rect, Screen};
+use sticky_models::state::WindowFrame;
+use sticky_models::store::NotesStore;
+use tauri::Monitor;
+
+use super::*;
+
+/// Remembers the frame of `window` for the note it shows. Minimized
+/// windows keep the frame they had before.
+pub fn remember_frame<R: Runtime>(window: &WebviewWindow<R>) {
+    let Some(note_id) = registry::shown_note(window) else {
+        return;
+    };
+    let Some(frame) = current_frame(window) else {
+        return;
+    };
+
+    let store = window.state::<NotesStore>();
+    if let Err(e) = store.set_window_frame(&note_id, frame) {
+        // A note deleted while open has no frame to keep.
+        debug_log!("Not remembering frame of {note_id}: {e}");
+    }
+}
+
+/// The frame `window` has now, `None` while it is minimized.
+pub fn current_frame<R: Runtime>(
+    window: &WebviewWindow<R>,
+) -> Option<WindowFrame> {
+    if window.is_minimized().unwrap_or(false) {
+        return None;
+    }
+    let bounds = window_bounds(window)?;
+    let manually_sized = registry::is_manually_sized(window);
+    Some(WindowFrame { bounds, manually_sized })
+}
+
+/// Remembers the frames of every note window, for quitting.
+pub fn remember_all_frames<R: Runtime>(handle: &AppHandle<R>) {
+    handle
+        .webview_windows()
+        .iter()
+        .filter(|(label, _)| label.starts_with(MAIN_WINDOW_PREFIX))
+        .for_each(|(_, window)| remember_frame(window));
+}
+
+/// The remembered frame of `note_id`'s window, moved onto the monitors
+/// connected now.
+pub fn restored_frame<R: Runtime>(
+    handle: &AppHandle<R>,
+    note_id: &str,
+) -> Option<WindowFrame> {
+    let frame = handle.state::<NotesStore>().window_frame(note_id)?;
+    let bounds = clamp_to_work_areas(frame.bounds, &work_areas(handle));
+    Some(WindowFrame { bounds, ..frame })
+}
+
+/// The work area of every connected monitor, in logical pixels.
+pub fn work_areas<R: Runtime>(handle: &AppHandle<R>) -> Vec<Rect> {
+    screens(handle).into_iter().map(|screen| screen.area).collect()
+}
+
+/// Every connected monitor, with its work area in logical pixels.
+pub fn screens<R: Runtime>(handle: &AppHandle<R>) -> Vec<Screen> {
+    let monitors = handle.available_monitors().unwrap_or_else(|e| {
+        warn!("Failed to list monitors: {e}");
+        Vec::new()
+    });
+    monitors.iter().map(monitor_screen).collect()
+}
+
+/// The monitor under the mouse pointer, if it can be told.
+pub fn cursor_screen<R: Runtime>(handle: &AppHandle<R>) -> Option<Screen> {
+    let cursor = handle.cursor_position().ok()?;
+    let monitor = handle.monitor_from_point(cursor.x, cursor.y).ok()??;
+    Some(monitor_screen(&monitor))
+}
+
+/// `monitor` with its work area in logical pixels.
+fn monitor_screen(monitor: &Monitor) -> Screen {
+    let area = monitor.work_area();
+    let scale = monitor.scale_factor();
+    let position = area.position.to_logical::<f64>(scale);
+    let size = area.size.to_logical::<f64>(scale);
+    Screen {
+        name: monitor.name().cloned(),
+        area: Rect {
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+        },
+    }
+}
+
+/// The outer position and inner size of `window`, in logical pixels:
+/// what window creation takes.
+fn window_bounds<R: Runtime>(window: &WebviewWindow<R>) -> Option<Rect> {
+    let scale = window.scale_factor().ok()?;
+    let position = window.outer_position().ok()?.to_logical::<f64>(scale);
+    let size = window.inner_size().ok()?.to_logical::<f64>(scale);
+    Some(Rect {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    })
+}
@@ -0,0 +1,123 @@
+//! Named window layouts: sets of note windows put on screen together,
+//! like one for coding and one for meetings (see
+//! [`sticky_models::state::Layouts`]).
+//!
+//! Each window is saved with the monitor it was on, so applying a
+//! layout under another monitor arrangement still lands it somewhere
+//! sensible; see [`sticky_models::geometry::relocate`].
+
+use sticky_models::error::{Error, Result};
+use sticky_models::geometry::{relocate, screen_of};
+use sticky_models::state::{Layout, LayoutWindow, Layouts, WindowFrame};
+use sticky_models::store::NotesStore;
+use tauri::{LogicalPosition, LogicalSize};
+
+use super::main::restore_note_window;
+use super::*;
+
+/// Emitted whenever a layout is saved or deleted.
+pub const LAYOUTS_CHANGED: &str = "layouts:changed";
+
+/// The names of the saved layouts, in the order they were first saved.
+pub fn layout_names(handle: &AppHandle) -> Vec<String> {
+    let store = handle.state::<NotesStore>();
+    Layouts::load(store.dir()).names()
+}
+
+/// Saves the visible note windows as the layout `name`, replacing any
+/// of that name.
+pub fn save_layout(handle: &AppHandle, name: &str) -> Result<()> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(Error::GenericError("A layout needs a name".into()));
+    }
+
+    let screens = frames::screens(handle);
+    let mut windows: Vec<_> = registry::note_windows(handle)
+        .into_iter()
+        .filter(|(_, w)| w.is_visible().unwrap_or(false))
+        .collect();
+    windows.sort_by_key(|(_, w)| registry::focused_at(w));
+    let windows = windows
+        .into_iter()
+        .filter_map(|(note_id, w)| {
+            let frame = frames::current_frame(&w)?;
+            let screen = screen_of(&frame.bounds, &screens)?.clone();
+            Some(LayoutWindow { note_id, frame, screen })
+        })
+        .collect();
+
+    let store = handle.state::<NotesStore>();
+    let mut layouts = Layouts::load(store.dir());
+    layouts.insert(Layout { name: name.to_string(), windows });
+    layouts.save(store.dir())?;
+    let _ = handle.emit(LAYOUTS_CHANGED, ());
+    Ok(())
+}
+
+/// Saves the visible note windows as a new layout with a numbered
+/// name, returning the name.
+pub fn save_new_layout(handle: &AppHandle) -> Result<String> {
+    let store = handle.state::<NotesStore>();
+    let name = Layouts::load(store.dir()).next_name();
+    save_layout(handle, &name)?;
+    Ok(name)
+}
+
+/// Puts the windows of the layout `name` on screen: windows of notes
+/// outside it close, those of notes in it move into place, and the
+/// rest open. Notes deleted since it was saved are skipped.
+pub fn apply_layout(handle: &AppHandle, name: &str) -> Result<()> {
+    let store = handle.state::<NotesStore>();
+    let layouts = Layouts::load(store.dir());
+    let layout = layouts.get(name).ok_or_else(|| {
+        Error::GenericError(format!("No layout is called {name:?}"))
+    })?;
+    let entries: Vec<&LayoutWindow> = layout
+        .windows
+        .iter()
+        .filter(|entry| store.path(&entry.note_id).is_ok())
+        .collect();
+
+    let mut open: HashMap<String, WebviewWindow> = HashMap::new();
+    for (note_id, w) in registry::note_windows(handle) {
+        if entries.iter().any(|entry| entry.note_id == note_id) {
+            open.insert(note_id, w);
+        } else {
+            let _ = w.close();
+        }
+    }
+
+    let screens = frames::screens(handle);
+    for entry in entries {
+        let bounds = relocate(entry.frame.bounds, &entry.screen, &screens);
+        match open.remove(&entry.note_id) {
+            // Focusing back to front restacks them as they were.
+            Some(w) => {
+                let _ =
+                    w.set_position(LogicalPosition::new(bounds.x, bounds.y));
+                let _ =
+                    w.set_size(LogicalSize::new(bounds.width, bounds.height));
+                let _ = w.show();
+                let _ = w.set_focus();
+            }
+            None => {
+                let frame = WindowFrame { bounds, ..entry.frame };
+                restore_note_window(handle, &entry.note_id, frame, true);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Deletes the layout `name`, returning whether there was one.
+pub fn delete_layout(handle: &AppHandle, name: &str) -> Result<bool> {
+    let store = handle.state::<NotesStore>();
+    let mut layouts = Layouts::load(store.dir());
+    if !layouts.remove(name) {
+        return Ok(false);
+    }
+    layouts.save(store.dir())?;
+    let _ = handle.emit(LAYOUTS_CHANGED, ());
+    Ok(true)
+}
@@ -1,5 +1,14 @@
 //! The note windows themselves.
 
+use log::warn;
+use sticky_models::colors;
+use sticky_models::geometry::{clamp_to_work_areas, Rect};
+use sticky_models::models::NoteColor;
+use sticky_models::state::WindowFrame;
+use sticky_models::store::NotesStore;
+use tauri::window::Color;
+use tauri::Theme;
+
 use super::*;
 
 pub const DEFAULT_FIRST_MAIN_WINDOW_HEIGHT: f64 = 190.0;
@@ -8,37 +17,101 @@ pub const DEFAULT_WINDOW_HEIGHT: f64 = 700.0;
 
 pub const MAX_WINDOW_WIDTH: f64 = 700.0;
 
-/// Creates a note window under the first free `main_N` label.
+/// The title of note windows until they show a note, which titles them
+/// after it; see [`registry`].
+const DEFAULT_WINDOW_TITLE: &str = "Sticky";
+
+/// The local storage key prefix of a window's sizing mode; keep in
+/// sync with `src-web/src/lib/autosize.ts`.
+const MANUALLY_RESIZED_KEY: &str = "__is_manually_resized__";
+
+/// Creates a note window under the first free `main_N` label. Without
+/// a `position` or a remembered frame, it opens where [`placement`]
+/// finds room.
 pub fn create_main_window(
     handle: &AppHandle,
     url: &str,
     size: Option<(f64, f64)>,
     position: Option<(f64, f64)>,
 ) -> WebviewWindow {
-    let mut counter = 0;
-    let label = loop {
-        let label = format!("{MAIN_WINDOW_PREFIX}{counter}");
-        match handle.webview_windows().get(label.as_str()) {
-            None => break Some(label),
-            Some(_) => counter += 1,
-        }
-    }
-    .expect("Failed to generate label for new window");
+    let (label, counter) = free_main_label(handle);
+
+    // A note opened before comes back where its window last was.
+    let note_id = url.strip_prefix('/').filter(|id| !id.is_empty());
+    let frame = note_id.and_then(|id| frames::restored_frame(handle, id));
+    let saved_position = frame.map(|f| (f.bounds.x, f.bounds.y));
+    let saved_size = frame.map(|f| (f.bounds.width, f.bounds.height));
 
-    let position = position.unwrap_or((100.0, 100.0));
     let default_size = if counter == 0 {
         (DEFAULT_WINDOW_WIDTH, DEFAULT_FIRST_MAIN_WINDOW_HEIGHT)
     } else {
         (DEFAULT_WINDOW_WIDTH, DEFAULT_WINDOW_HEIGHT)
     };
-    let inner_size = size.unwrap_or(default_size);
+    let (width, height) = size.or(saved_size).unwrap_or(default_size);
+    let (x, y) = position.or(saved_position).unwrap_or_else(|| {
+        let bounds = placement::new_window_bounds(handle, width, height);
+        (bounds.x, bounds.y)
+    });
+
+    let frame = WindowFrame {
+        bounds: Rect { x, y, width, height },
+        manually_sized: frame.is_some_and(|f| f.manually_sized),
+    };
+    open_main_window(handle, url, &label, frame, true)
+}
+
+/// Opens a note window at a recorded `frame`, as a session or layout
+/// has it, moved onto the monitors connected now. Unless `visible`,
+/// the window is left hidden.
+pub fn restore_note_window(
+    handle: &AppHandle,
+    note_id: &str,
+    frame: WindowFrame,
+    visible: bool,
+) -> WebviewWindow {
+    let (label, _) = free_main_label(handle);
+    let areas = frames::work_areas(handle);
+    let bounds = clamp_to_work_areas(frame.bounds, &areas);
+    let frame = WindowFrame { bounds, ..frame };
+    open_main_window(handle, &format!("/{note_id}"), &label, frame, visible)
+}
+
+/// The first free `main_N` label, and its `N`.
+fn free_main_label(handle: &AppHandle) -> (String, usize) {
+    let windows = handle.webview_windows();
+    (0..)
+        .map(|n| (format!("{MAIN_WINDOW_PREFIX}{n}"), n))
+        .find(|(label, _)| !windows.contains_key(label))
+        .expect("Failed to generate label for new window")
+}
+
+fn open_main_window(
+    handle: &AppHandle,
+    url: &str,
+    label: &str,
+    frame: WindowFrame,
+    reveal: bool,
+) -> WebviewWindow {
+    let bounds = frame.bounds;
+    let manually_sized = frame.manually_sized;
+    // The sizing mode is kept per window label in local storage (see
+    // `lib/autosize`), and a recycled label may hold another window's;
+    // seed it with the note's own before the page reads it.
+    let mut script = format!(
+        "localStorage.setItem('{MANUALLY_RESIZED_KEY}_{label}', \
+         '{manually_sized}');"
+    );
+    if !reveal {
+        // See `hooks/use-window-reveal`.
+        script.push_str("window.__STICKY_KEEP_HIDDEN__ = true;");
+    }
 
     let config = CreateWindowConfig {
         url,
-        label: label.as_str(),
-        title: "Sticky",
-        inner_size: Some(inner_size),
-        position: Some(position),
+        label,
+        title: DEFAULT_WINDOW_TITLE,
+        inner_size: Some((bounds.width, bounds.height)),
+        position: Some((bounds.x, bounds.y)),
         hide_titlebar: true,
         always_on_top: true,
         max_size: Some((Some(MAX_WINDOW_WIDTH), None)),
@@ -46,10 +119,57 @@ pub fn create_main_window(
         // editor, which reveals the window; showing any earlier would
         // flash the webview's white default background.
         start_hidden: true,
+        initialization_script: Some(script),
         ..Default::default()
     };
 
-    create_window(handle, config)
+    let window = create_window(handle, config);
+    registry::set_manually_sized(&window, manually_sized);
+    // A summary, not `get`: the editor's own read is what its saves go
+    // by.
+    let note = url
+        .strip_prefix('/')
+        .and_then(|id| handle.state::<NotesStore>().summary(id).ok());
+    if let Some(note) = &note {
+        registry::track_note(&window, &note.id, &note.title);
+    }
+    // A reused label may still carry the tint of the window that had it.
+    tint_note_window(&window, note.and_then(|note| note.color));
+    window
+}
+
+/// Tints a note window with its note's color label (see
+/// [`sticky_models::colors`]): the window background, behind the
+/// webview's own, and on macOS the traffic lights. `None` clears it.
+pub fn tint_note_window(window: &WebviewWindow, color: Option<NoteColor>) {
+    let dark = window.theme().is_ok_and(|theme| theme == Theme::Dark);
+    let background = color.map(|color| {
+        let (r, g, b) = colors::tint(color, dark);
+        Color(r, g, b, 255)
+    });
+    if let Err(e) = window.set_background_color(background) {
+        warn!("Failed to tint window {}: {e}", window.label());
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let tinted = window.clone();
+        let accent = color.map(colors::accent);
+        let _ = window.run_on_main_thread(move || {
+            crate::mac_window::set_traffic_light_tint(&tinted, accent)
+        });
+    }
+}
+
+/// Focuses the window already showing the note, or opens one for it.
+pub fn show_note_window(handle: &AppHandle, note_id: &str) {
+    if let Some(w) = registry::note_window(handle, note_id) {
+        let _ = w.show();
+        let _ = w.set_focus();
+        return;
+    }
+
+    create_main_window(handle, &format!("/{note_id}"), None, None);
 }
 
 /// Creates a window centered on its parent that keeps focus while both
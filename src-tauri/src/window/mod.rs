@@ -1,26 +1,44 @@
 //! Note windows and the utility panels floated over them.
 
+mod arrange;
 mod command;
+mod frames;
+mod layouts;
 mod link;
 mod main;
 mod panel;
+mod placement;
+mod registry;
 mod search;
+mod session;
 mod toast;
 
+pub use arrange::arrange_windows;
 pub use command::{
     command_window_label, create_command_window, present_command_window,
     prewarm_command_window,
 };
+pub use frames::{remember_all_frames, remember_frame};
+pub use layouts::{
+    apply_layout, delete_layout, layout_names, save_layout, save_new_layout,
+    LAYOUTS_CHANGED,
+};
 pub use link::{create_link_window, link_window_label, present_link_window};
-pub use main::{create_child_window, create_main_window};
+pub use main::{
+    create_child_window, create_main_window, show_note_window, tint_note_window,
+};
 pub use panel::{panel_recently_hidden, PanelState};
+pub use placement::{rescue_window, watch_monitors};
+pub use registry::{
+    forget_window, init_registry, mark_focused, note_window, note_windows,
+    release_note, set_manually_sized, shown_note, track_note, WindowNotes,
+};
 pub use search::{
     create_search_window, present_search_window, prewarm_search_window,
     search_window_label,
 };
-pub use toast::{
-    create_toast_window, present_toast_window, toast_window_label, ToastState,
-};
+pub use session::{restore_session, save_session};
+pub use toast::{present_toast_window, show_toast, ToastState};
 
 use std::collections::HashMap;
 use std::sync::Mutex;
@@ -64,6 +82,8 @@ pub(crate) struct CreateWindowConfig<'s> {
     /// Rejects minimizing, for utility panels: their controls are
     /// hidden, but Cmd+M would still miniaturize them into the Dock.
     pub no_minimize: bool,
+    /// Script run in the webview before the page's own, on every load.
+    pub initialization_script: Option<String>,
 }
 
 /// Creates a window according to `config`, wiring up the app menu, the
@@ -111,6 +131,10 @@ pub(crate) fn create_window<R: Runtime>(
         win_builder = win_builder.minimizable(false);
     }
 
+    if let Some(script) = config.initialization_script {
+        win_builder = win_builder.initialization_script(script);
+    }
+
     if let Some((w, h)) = config.inner_size {
         win_builder = win_builder.inner_size(w, h);
     } else {
@@ -224,6 +248,7 @@ pub(crate) fn create_window<R: Runtime>(
                     // window is gone; without it the app would stay
                     // alive behind the tray icon.
                     use std::sync::atomic::Ordering;
+                    save_session(w.app_handle());
                     w.state::<crate::AppState>()
                         .quitting
                         .store(true, Ordering::Relaxed);
@@ -0,0 +1,103 @@
+//! Where note windows go across monitors: new ones open on the monitor
+//! in use, clear of the windows already there, and windows a monitor
+//! change leaves off screen come back onto one.
+
+use std::thread;
+
+use sticky_models::geometry::{
+    clamp_to_work_areas, is_off_screen, place_new, screen_of, Rect,
+};
+use tauri::{App, LogicalPosition, LogicalSize};
+
+use super::*;
+
+/// How often the connected monitors are checked for changes; there is
+/// no event for it.
+const MONITOR_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Where a new note window of `width` by `height` opens: on the focused
+/// note window's monitor, else the one under the mouse pointer, and
+/// stepped clear of the visible note windows.
+pub fn new_window_bounds<R: Runtime>(
+    handle: &AppHandle<R>,
+    width: f64,
+    height: f64,
+) -> Rect {
+    let screens = frames::screens(handle);
+    let windows = main_windows(handle);
+    let focused = windows
+        .iter()
+        .find(|w| w.is_focused().unwrap_or(false))
+        .and_then(|w| frames::current_frame(w))
+        .and_then(|frame| screen_of(&frame.bounds, &screens).cloned());
+    let screen = focused
+        .or_else(|| frames::cursor_screen(handle))
+        .or_else(|| screens.first().cloned());
+    let Some(screen) = screen else {
+        // No monitor to go by.
+        return Rect { x: 100.0, y: 100.0, width, height };
+    };
+
+    let taken: Vec<Rect> = windows
+        .iter()
+        .filter(|w| w.is_visible().unwrap_or(false))
+        .filter_map(|w| frames::current_frame(w))
+        .map(|frame| frame.bounds)
+        .collect();
+    place_new(width, height, &screen.area, &taken)
+}
+
+/// Moves `window` back onto the nearest work area if it is off screen,
+/// as geometry restored for a monitor no longer connected leaves it.
+pub fn rescue_window<R: Runtime>(window: &WebviewWindow<R>) {
+    rescue(window, &frames::work_areas(window.app_handle()));
+}
+
+/// Watches the connected monitors and, when they change, moves the
+/// note windows left off screen back onto them. Call once at setup.
+pub fn watch_monitors(app: &App) {
+    let handle = app.handle().clone();
+    thread::spawn(move || {
+        let mut known = frames::screens(&handle);
+        loop {
+            thread::sleep(MONITOR_POLL_INTERVAL);
+            let screens = frames::screens(&handle);
+            if screens == known {
+                continue;
+            }
+
+            debug_log!("Monitors changed, rescuing off-screen windows");
+            let areas: Vec<Rect> =
+                screens.iter().map(|screen| screen.area).collect();
+            for window in main_windows(&handle) {
+                rescue(&window, &areas);
+            }
+            known = screens;
+        }
+    });
+}
+
+fn rescue<R: Runtime>(window: &WebviewWindow<R>, areas: &[Rect]) {
+    // Minimized windows have no frame to go by; they come back wherever
+    // the system puts them.
+    let Some(frame) = frames::current_frame(window) else {
+        return;
+    };
+    if !is_off_screen(&frame.bounds, areas) {
+        return;
+    }
+
+    debug_log!("Moving {} back on screen", window.label());
+    let bounds = clamp_to_work_areas(frame.bounds, areas);
+    let _ = window.set_position(LogicalPosition::new(bounds.x, bounds.y));
+    let _ = window.set_size(LogicalSize::new(bounds.width, bounds.height));
+}
+
+fn main_windows<R: Runtime>(handle: &AppHandle<R>) -> Vec<WebviewWindow<R>> {
+    handle
+        .webview_windows()
+        .into_iter()
+        .filter(|(label, _)| label.starts_with(MAIN_WINDOW_PREFIX))
+        .map(|(_, w)| w)
+        .collect()
+}
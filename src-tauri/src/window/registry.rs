@@ -0,0 +1,172 @@
+//! Which note each note window shows.
+//!
+//! Note windows live in recycled `main_N` slots, so a label says
+//! nothing about the note behind it. The registry maps them: a window
+//! is registered with its note on creation and whenever it loads
+//! another one, and dropped once destroyed. It titles each window
+//! after its note and tells windows whose note was deleted from
+//! outside the app, which close unless they have edits to keep.
+
+use log::warn;
+use sticky_models::store::NotesStore;
+use sticky_models::watcher::NOTES_CHANGED;
+use tauri::{App, Listener};
+
+use super::*;
+
+/// What each note window shows, keyed by the window's label.
+#[derive(Default)]
+pub struct WindowNotes(pub Mutex<HashMap<String, WindowNote>>);
+
+#[derive(Default)]
+pub struct WindowNote {
+    /// The note the window last loaded, if any.
+    note_id: Option<String>,
+    /// Whether the window was resized by hand; see `lib/autosize`.
+    manually_sized: bool,
+    /// When the window last gained focus, which orders the windows
+    /// front to back.
+    focused_at: Option<Instant>,
+}
+
+/// Keeps the registered windows following their notes: retitled when
+/// a note is edited, closed when it is deleted. Call once at setup.
+pub fn init_registry(app: &App) {
+    let handle = app.handle().clone();
+    app.listen(NOTES_CHANGED, move |event| {
+        let changed: Option<String> =
+            serde_json::from_str(event.payload()).unwrap_or_default();
+        sync_windows(&handle, changed.as_deref());
+    });
+}
+
+/// Registers `window` as showing `note_id`, titled `title`. When it was
+/// showing another note, that note's frame is remembered first: the
+/// window still has it until the new note's editor refits it.
+pub fn track_note<R: Runtime>(
+    window: &WebviewWindow<R>,
+    note_id: &str,
+    title: &str,
+) {
+    let previous = shown_note(window);
+    if previous.as_deref() != Some(note_id) {
+        if previous.is_some() {
+            frames::remember_frame(window);
+        }
+        window_note(window, |slot| slot.note_id = Some(note_id.to_string()));
+    }
+    set_title(window, title);
+}
+
+/// The note `window` shows, if any.
+pub fn shown_note<R: Runtime>(window: &WebviewWindow<R>) -> Option<String> {
+    window_note(window, |slot| slot.note_id.clone())
+}
+
+/// The windows showing a note, with its id.
+pub fn note_windows<R: Runtime>(
+    handle: &AppHandle<R>,
+) -> Vec<(String, WebviewWindow<R>)> {
+    let state = handle.state::<WindowNotes>();
+    let notes = state.0.lock().expect("Window notes poisoned");
+    notes
+        .iter()
+        .filter_map(|(label, slot)| {
+            let note_id = slot.note_id.clone()?;
+            Some((note_id, handle.get_webview_window(label)?))
+        })
+        .collect()
+}
+
+/// The window showing `note_id`, if one does. With several, the one
+/// focused last.
+pub fn note_window<R: Runtime>(
+    handle: &AppHandle<R>,
+    note_id: &str,
+) -> Option<WebviewWindow<R>> {
+    note_windows(handle)
+        .into_iter()
+        .filter(|(id, _)| id == note_id)
+        .map(|(_, window)| window)
+        .max_by_key(|window| focused_at(window))
+}
+
+/// Unregisters the windows showing `note_id`, for deletions made in
+/// the app: the window asking moves on to another note by itself.
+pub fn release_note<R: Runtime>(handle: &AppHandle<R>, note_id: &str) {
+    let state = handle.state::<WindowNotes>();
+    let mut notes = state.0.lock().expect("Window notes poisoned");
+    for slot in notes.values_mut() {
+        if slot.note_id.as_deref() == Some(note_id) {
+            slot.note_id = None;
+        }
+    }
+}
+
+/// Records whether `window` was resized by hand, to remember with its
+/// frame.
+pub fn set_manually_sized<R: Runtime>(
+    window: &WebviewWindow<R>,
+    manually_sized: bool,
+) {
+    window_note(window, |slot| slot.manually_sized = manually_sized);
+}
+
+/// Whether `window` was resized by hand.
+pub fn is_manually_sized<R: Runtime>(window: &WebviewWindow<R>) -> bool {
+    window_note(window, |slot| slot.manually_sized)
+}
+
+/// Records that `window` gained focus, which brought it to the front.
+pub fn mark_focused<R: Runtime>(window: &WebviewWindow<R>) {
+    window_note(window, |slot| slot.focused_at = Some(Instant::now()));
+}
+
+/// When `window` last gained focus, if ever.
+pub fn focused_at<R: Runtime>(window: &WebviewWindow<R>) -> Option<Instant> {
+    window_note(window, |slot| slot.focused_at)
+}
+
+/// Unregisters the window `label`, once it is gone.
+pub fn forget_window<R: Runtime>(handle: &AppHandle<R>, label: &str) {
+    let state = handle.state::<WindowNotes>();
+    state.0.lock().expect("Window notes poisoned").remove(label);
+}
+
+/// Retitles the windows of `changed`, or of every note when `None`,
+/// and tells those whose note is gone. Closing them here would drop
+/// edits their editors haven't saved yet.
+fn sync_windows<R: Runtime>(handle: &AppHandle<R>, changed: Option<&str>) {
+    let store = handle.state::<NotesStore>();
+    for (note_id, window) in note_windows(handle) {
+        if changed.is_some_and(|id| id != note_id) {
+            continue;
+        }
+        // Not `get`: that would take the disk's content as what the
+        // window's editor works from, hiding external edits from its
+        // next save.
+        match store.summary(&note_id) {
+            Ok(summary) => set_title(&window, &summary.title),
+            Err(_) if store.path(&note_id).is_err() => {
+                debug_log!("Flagging {} of deleted {note_id}", window.label());
+                let _ = window.emit_to(window.label(), "note:deleted", ());
+            }
+            Err(e) => warn!("Could not retitle {}: {e}", window.label()),
+        }
+    }
+}
+
+fn set_title<R: Runtime>(window: &WebviewWindow<R>, title: &str) {
+    if let Err(e) = window.set_title(title) {
+        warn!("Failed to title window {}: {e}", window.label());
+    }
+}
+
+fn window_note<R: Runtime, T>(
+    window: &WebviewWindow<R>,
+    f: impl FnOnce(&mut WindowNote) -> T,
+) -> T {
+    let state = window.state::<WindowNotes>();
+    let mut notes = state.0.lock().expect("Window notes poisoned");
+    f(notes.entry(window.label().to_string()).or_default())
+}
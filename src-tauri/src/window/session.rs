@@ -0,0 +1,88 @@
+//! Session restore: the note windows open at quit come back on the
+//! next launch (see [`sticky_models::state::Session`]), where they were
+//! and stacked as they were.
+
+use log::warn;
+use sticky_models::state::{Session, SessionWindow};
+use sticky_models::store::NotesStore;
+
+use super::main::restore_note_window;
+use super::*;
+
+/// How long restoring waits for a window to reveal itself before
+/// opening the next one over it regardless.
+const REVEAL_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How often restoring checks whether a window has revealed itself.
+const REVEAL_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Records the open note windows as the session to restore, back to
+/// front by when they last had focus. Windows not showing a note are
+/// left out.
+pub fn save_session<R: Runtime>(handle: &AppHandle<R>) {
+    let store = handle.state::<NotesStore>();
+    let mut windows = registry::note_windows(handle);
+    windows.sort_by_key(|(_, w)| registry::focused_at(w));
+
+    let windows = windows
+        .into_iter()
+        .filter_map(|(note_id, w)| {
+            // A minimized window keeps the frame it had before.
+            let frame = frames::current_frame(&w)
+                .or_else(|| store.window_frame(&note_id))?;
+            Some(SessionWindow {
+                note_id,
+                frame,
+                visible: w.is_visible().unwrap_or(false),
+            })
+        })
+        .collect();
+
+    debug_log!("Saving session");
+    if let Err(e) = (Session { windows }).save(store.dir()) {
+        warn!("Failed to save the session: {e}");
+    }
+}
+
+/// Reopens the windows of the last session, back to front, skipping
+/// notes deleted or expired since. Returns whether there were any.
+pub fn restore_session(handle: &AppHandle) -> bool {
+    let store = handle.state::<NotesStore>();
+    let windows: Vec<SessionWindow> = Session::load(store.dir())
+        .windows
+        .into_iter()
+        .filter(|w| store.path(&w.note_id).is_ok())
+        .collect();
+    if windows.is_empty() {
+        return false;
+    }
+
+    // Every window comes to the front as it reveals itself, once its
+    // editor has painted; opening the next only after that keeps the
+    // stacking order.
+    let handle = handle.clone();
+    tauri::async_runtime::spawn(async move {
+        for entry in windows {
+            debug_log!("Restoring window of {}", entry.note_id);
+            let window = restore_note_window(
+                &handle,
+                &entry.note_id,
+                entry.frame,
+                entry.visible,
+            );
+            if entry.visible {
+                wait_for_reveal(&window).await;
+            }
+        }
+    });
+    true
+}
+
+async fn wait_for_reveal(window: &WebviewWindow) {
+    let started = Instant::now();
+    while !window.is_visible().unwrap_or(true)
+        && started.elapsed() < REVEAL_TIMEOUT
+    {
+        tokio::time::sleep(REVEAL_POLL_INTERVAL).await;
+    }
+}
@@ -19,6 +19,27 @@ pub fn toast_window_label(parent_label: &str) -> String {
     format!("{OTHER_WINDOW_PREFIX}toast_{parent_label}")
 }
 
+/// Shows `message` in the toast over `parent_window`, creating the
+/// panel on first use.
+pub fn show_toast(parent_window: &WebviewWindow, message: &str) {
+    let label = toast_window_label(parent_window.label());
+    if let Some(w) = parent_window.app_handle().webview_windows().get(&label) {
+        let _ = w.emit_to(label.as_str(), "toast:show", message);
+        return;
+    }
+
+    // The first message travels percent-encoded in the query string;
+    // the webview is not ready to receive events yet.
+    let mut url = tauri::Url::parse("tauri://localhost/toast")
+        .expect("Toast panel base URL should parse");
+    url.query_pairs_mut()
+        .append_pair("parent", parent_window.label())
+        .append_pair("message", message);
+    let url = format!("{}?{}", url.path(), url.query().unwrap_or_default());
+
+    create_toast_window(parent_window, &url);
+}
+
 /// Creates the transient notification pill floated over the bottom of
 /// its parent window.
 ///
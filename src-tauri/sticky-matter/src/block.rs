@@ -0,0 +1,454 @@
+//! Block-style YAML: the indented lines under a key.
+//!
+//! Block content is kept as the original lines. Reading parses them on
+//! demand; writing splices in freshly rendered lines for just the node
+//! being changed, so every other line keeps its exact bytes.
+
+use std::ops::Range;
+
+use crate::parse_value;
+use crate::value::{
+    flow, render_flow, render_key, render_scalar, scalar, strip_comment, Value,
+};
+
+/// The indentation of a line, in spaces.
+pub(crate) fn indent(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+/// Whether a line carries no content: blank, or only a comment.
+pub(crate) fn is_filler(line: &str) -> bool {
+    let line = line.trim();
+    line.is_empty() || line.starts_with('#')
+}
+
+/// Whether text starts a sequence item: a `-` followed by a space or
+/// nothing.
+pub(crate) fn is_item(text: &str) -> bool {
+    text == "-" || text.starts_with("- ")
+}
+
+/// Whether block lines hold a mapping, as opposed to a sequence or
+/// nothing at all.
+pub(crate) fn is_map(lines: &[&str]) -> bool {
+    lines
+        .iter()
+        .find(|l| !is_filler(l))
+        .is_some_and(|l| !is_item(l.trim_start()))
+}
+
+/// Split a mapping line's text into its key and the text after the
+/// colon. The key may be quoted; a plain one ends at the first `:`
+/// followed by a space or the end of the line.
+pub(crate) fn split_key(text: &str) -> Option<(String, &str)> {
+    if is_item(text) || text.starts_with(['#', '[', '{']) {
+        return None;
+    }
+
+    let end = match text.chars().next()? {
+        q @ ('"' | '\'') => closing_quote(text, q)? + 1,
+        _ => {
+            let colon = text.match_indices(':').map(|(i, _)| i).find(|&i| {
+                text[i + 1..].is_empty() || text[i + 1..].starts_with(' ')
+            })?;
+            text[..colon].trim_end().len()
+        }
+    };
+
+    let rest = text[end..].trim_start_matches(' ').strip_prefix(':')?;
+    if !(rest.is_empty() || rest.starts_with(' ')) {
+        return None;
+    }
+
+    let key = parse_value(&text[..end]);
+    (!key.is_empty()).then_some((key, rest))
+}
+
+/// The byte offset of the quote closing the one that opens `text`.
+fn closing_quote(text: &str, quote: char) -> Option<usize> {
+    let mut chars = text.char_indices().skip(1).peekable();
+    while let Some((i, c)) = chars.next() {
+        if quote == '"' && c == '\\' {
+            chars.next();
+        } else if c == quote {
+            if quote == '\'' && chars.peek().is_some_and(|&(_, c)| c == '\'') {
+                chars.next();
+            } else {
+                return Some(i);
+            }
+        }
+    }
+    None
+}
+
+/// Parse a node: the text after its `key:` or `- `, and the block
+/// lines under it.
+pub(crate) fn parse_node(inline: &str, block: &[&str]) -> Value {
+    let inline = strip_comment(inline).trim();
+    match inline.chars().next() {
+        None => parse_block(block),
+        Some('|' | '>') => block_scalar(inline, block),
+        Some(first) => {
+            // Flow collections and plain scalars may continue on more
+            // indented lines; YAML folds the line breaks into spaces.
+            let mut text = inline.to_string();
+            for line in block.iter().filter(|l| !is_filler(l)) {
+                text.push(' ');
+                text.push_str(strip_comment(line).trim());
+            }
+            if matches!(first, '[' | '{') {
+                flow(&text)
+            } else {
+                scalar(&text)
+            }
+        }
+    }
+}
+
+/// Parse block content at whatever indentation it uses.
+pub(crate) fn parse_block(lines: &[&str]) -> Value {
+    let Some(first) = lines.iter().find(|l| !is_filler(l)) else {
+        return Value::Null;
+    };
+
+    let indent = indent(first);
+    if is_item(first.trim_start()) {
+        let items = spans(lines, indent, true)
+            .into_iter()
+            .map(|span| parse_item(&lines[span], indent));
+        Value::List(items.collect())
+    } else {
+        let entries =
+            spans(lines, indent, false).into_iter().filter_map(|span| {
+                let (key, rest) = split_key(lines[span.start].trim_start())?;
+                Some((key, parse_node(rest, &lines[span.start + 1..span.end])))
+            });
+        Value::Map(entries.collect())
+    }
+}
+
+/// Parse a sequence item whose `-` sits at `indent`.
+fn parse_item(lines: &[&str], indent: usize) -> Value {
+    let rest = &lines[0][indent + 1..];
+    let content = rest.trim_start();
+    if content.is_empty() {
+        return parse_block(&lines[1..]);
+    }
+
+    // `- key: value` starts a mapping whose further keys line up with
+    // the first one; reparse it as block content at that column.
+    if split_key(content).is_some() {
+        let column = indent + 1 + rest.len() - content.len();
+        let first = format!("{}{content}", " ".repeat(column));
+        let mut item = vec![first.as_str()];
+        item.extend_from_slice(&lines[1..]);
+        return parse_block(&item);
+    }
+
+    parse_node(content, &lines[1..])
+}
+
+/// The line ranges of the entries (or `items`) at `indent`: each
+/// starts at its key or `-` line and runs to its last more indented
+/// content line. Trailing blanks and comments belong to no entry.
+fn spans(lines: &[&str], indent: usize, items: bool) -> Vec<Range<usize>> {
+    let mut spans: Vec<Range<usize>> = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        if is_filler(line) {
+            continue;
+        }
+
+        let column = self::indent(line);
+        let item = is_item(line.trim_start());
+        if column == indent && item == items {
+            spans.push(i..i + 1);
+        } else if let Some(span) = spans.last_mut() {
+            // Deeper lines belong to the current entry, and so do
+            // sequence items at a key's own column (`key:\n- a`).
+            if column > indent || (column == indent && item) {
+                span.end = i + 1;
+            }
+        }
+    }
+    spans
+}
+
+/// Parse a `|` literal or `>` folded block scalar.
+fn block_scalar(header: &str, lines: &[&str]) -> Value {
+    let indent =
+        lines.iter().find(|l| !l.trim().is_empty()).map_or(0, |l| indent(l));
+    let content: Vec<&str> = lines
+        .iter()
+        .map(|l| {
+            if l.trim().is_empty() {
+                ""
+            } else {
+                l.get(indent..).unwrap_or(l)
+            }
+        })
+        .collect();
+
+    let mut text = if header.starts_with('>') {
+        let mut out = String::new();
+        let mut blank = true;
+        for line in content {
+            if line.is_empty() {
+                out.push('\n');
+                blank = true;
+            } else {
+                if !blank {
+                    out.push(' ');
+                }
+                out.push_str(line);
+                blank = false;
+            }
+        }
+        out
+    } else {
+        content.join("\n")
+    };
+
+    // Chomping: `-` strips the final line break, `+` keeps trailing
+    // blank lines, and the default clips to a single break.
+    if header.contains('+') {
+        text.push('\n');
+    } else {
+        text.truncate(text.trim_end_matches('\n').len());
+        if !header.contains('-') && !text.is_empty() {
+            text.push('\n');
+        }
+    }
+    Value::String(text)
+}
+
+/// Render a mapping entry: its `key:` line at `indent`, then its block
+/// lines at `child_indent`. The key is used as given; nested callers
+/// quote it with [`render_key`] first.
+pub(crate) fn render_entry(
+    key: &str,
+    value: &Value,
+    indent: usize,
+    child_indent: usize,
+    flow: bool,
+) -> Vec<String> {
+    let (inline, block) = render_node(value, child_indent, flow);
+    let mut line = format!("{}{key}:", " ".repeat(indent));
+    if !inline.is_empty() {
+        line.push(' ');
+        line.push_str(&inline);
+    }
+    std::iter::once(line).chain(block).collect()
+}
+
+/// Render a node's value: the text after its `key:`, empty for null
+/// and block collections, and the block lines under it at `indent`.
+/// `flow` keeps collections on the key's line instead.
+pub(crate) fn render_node(
+    value: &Value,
+    indent: usize,
+    flow: bool,
+) -> (String, Vec<String>) {
+    match value {
+        Value::Null => (String::new(), vec![]),
+        _ if value.is_inline() => {
+            (render_scalar(value, false).into_owned(), vec![])
+        }
+        _ if flow => (render_flow(value), vec![]),
+        Value::List(items) => (String::new(), render_items(items, indent)),
+        Value::Map(entries) => {
+            let lines = entries.iter().flat_map(|(k, v)| {
+                render_entry(&render_key(k), v, indent, indent + 2, false)
+            });
+            (String::new(), lines.collect())
+        }
+        _ => unreachable!("scalars render inline"),
+    }
+}
+
+/// Render sequence items, their `-` at `indent`.
+fn render_items(items: &[Value], indent: usize) -> Vec<String> {
+    let pad = " ".repeat(indent);
+    let mut lines = Vec::new();
+    for item in items {
+        match item {
+            Value::Map(_) if !item.is_inline() => {
+                // The first key shares the `- ` line; the rest line up
+                // under it.
+                let (_, mut entry) = render_node(item, indent + 2, false);
+                entry[0].replace_range(..indent + 2, &format!("{pad}- "));
+                lines.extend(entry);
+            }
+            Value::List(_) => {
+                lines.push(format!("{pad}- {}", render_flow(item)))
+            }
+            _ => lines.push(format!("{pad}- {}", render_scalar(item, false))),
+        }
+    }
+    lines
+}
+
+/// The indentation for a node's block lines: whatever its existing
+/// block uses, or two more than its key. Sequences may sit at the
+/// key's own column; mappings must be deeper.
+pub(crate) fn child_indent(
+    block: &[&str],
+    parent: usize,
+    value: &Value,
+) -> usize {
+    match block.iter().find(|l| !is_filler(l)).map(|l| indent(l)) {
+        Some(n) if n > parent => n,
+        Some(n) if n == parent && matches!(value, Value::List(_)) => n,
+        _ => parent + 2,
+    }
+}
+
+/// Set the value at `path` inside the block mapping under a key at
+/// column `parent`.
+///
+/// Only the lines of the node the path ends at are rewritten; a
+/// missing key is appended after the mapping's last entry.
+pub(crate) fn set_in_block(
+    lines: &mut Vec<String>,
+    parent: usize,
+    path: &[&str],
+    value: Value,
+) {
+    let Some((key, rest)) = path.split_first() else {
+        return;
+    };
+
+    let refs: Vec<&str> = lines.iter().map(String::as_str).collect();
+    let indent =
+        refs.iter().find(|l| !is_filler(l)).map_or(parent + 2, |l| indent(l));
+    let spans = spans(&refs, indent, false);
+    let found = spans.iter().find(|span| {
+        split_key(refs[span.start].trim_start()).is_some_and(|(k, _)| k == *key)
+    });
+
+    let Some(span) = found.cloned() else {
+        let mut node = Value::Null;
+        node.set_path(rest, value);
+        let at = spans.last().map_or(lines.len(), |span| span.end);
+        let entry =
+            render_entry(&render_key(key), &node, indent, indent + 2, false);
+        lines.splice(at..at, entry);
+        return;
+    };
+
+    let (_, inline) = split_key(refs[span.start].trim_start())
+        .expect("Spans start at key lines");
+    let block = &refs[span.start + 1..span.end];
+
+    // Deeper paths through a block mapping recurse, leaving the
+    // mapping's other entries alone.
+    if !rest.is_empty()
+        && strip_comment(inline).trim().is_empty()
+        && is_map(block)
+    {
+        let mut sub: Vec<String> =
+            block.iter().map(|l| l.to_string()).collect();
+        set_in_block(&mut sub, indent, rest, value);
+        lines.splice(span.start + 1..span.end, sub);
+        return;
+    }
+
+    let mut node = parse_node(inline, block);
+    node.set_path(rest, value);
+    let flow = strip_comment(inline).trim().starts_with(['[', '{']);
+    let child_indent = child_indent(block, indent, &node);
+    let entry =
+        render_entry(&render_key(key), &node, indent, child_indent, flow);
+    lines.splice(span, entry);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(text: &str) -> Value {
+        let lines: Vec<&str> = text.lines().collect();
+        parse_block(&lines)
+    }
+
+    #[test]
+    fn test_block_sequences_and_mappings() {
+        assert_eq!(
+            block("  - a\n  - 2"),
+            Value::from(vec![Value::from("a"), 2.into()])
+        );
+        assert_eq!(block("- compact"), Value::from(vec!["compact"]));
+        assert_eq!(
+            block("  x: 1\n  inner:\n    deep: true\n  list:\n  - a"),
+            Value::Map(vec![
+                ("x".into(), 1.into()),
+                (
+                    "inner".into(),
+                    Value::Map(vec![("deep".into(), true.into())])
+                ),
+                ("list".into(), vec!["a"].into()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_items_holding_mappings() {
+        assert_eq!(
+            block("  - name: a\n    size: 1\n  - name: b"),
+            Value::List(vec![
+                Value::Map(vec![
+                    ("name".into(), "a".into()),
+                    ("size".into(), 1.into())
+                ]),
+                Value::Map(vec![("name".into(), "b".into())]),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_block_scalars() {
+        assert_eq!(parse_node("|", &["  a", "  b"]), Value::from("a\nb\n"));
+        assert_eq!(
+            parse_node(">-", &["  a", "  b", "", "  c"]),
+            Value::from("a b\nc")
+        );
+        assert_eq!(
+            parse_node("plain", &["  continued"]),
+            Value::from("plain continued")
+        );
+    }
+
+    #[test]
+    fn test_split_key() {
+        assert_eq!(split_key("a: 1"), Some(("a".into(), " 1")));
+        assert_eq!(split_key("first name:"), Some(("first name".into(), "")));
+        assert_eq!(split_key("\"a: b\": c"), Some(("a: b".into(), " c")));
+        assert_eq!(split_key("http://x"), None);
+        assert_eq!(split_key("- a: 1"), None);
+    }
+
+    #[test]
+    fn test_set_in_block_rewrites_only_the_target() {
+        let mut lines: Vec<String> = [
+            "  x: 1 # px",
+            "  inner:",
+            "    deep: old",
+            "    keep: me",
+            "  # tail",
+        ]
+        .map(String::from)
+        .to_vec();
+        set_in_block(&mut lines, 0, &["inner", "deep"], "new".into());
+        set_in_block(&mut lines, 0, &["y"], 2.into());
+        assert_eq!(
+            lines,
+            [
+                "  x: 1 # px",
+                "  inner:",
+                "    deep: new",
+                "    keep: me",
+                "  y: 2",
+                "  # tail"
+            ]
+        );
+    }
+}
@@ -0,0 +1,345 @@
+//! JSON frontmatter: a `{ … }` object opening the file.
+//!
+//! JSON has no comments and no line structure worth keeping, so a JSON
+//! header renders verbatim until a field changes, and pretty-printed
+//! from its fields after that. Untouched fields keep the text of their
+//! values either way.
+
+use crate::{view, Entry, Value};
+
+/// Parse a JSON value.
+pub(crate) fn parse(text: &str) -> Option<Value> {
+    let mut parser = Parser { text, pos: 0 };
+    let value = parser.value()?;
+    parser.skip_ws();
+    (parser.pos == text.len()).then_some(value)
+}
+
+/// The length in bytes of the JSON object opening `text`, if it opens
+/// with one.
+pub(crate) fn object_len(text: &str) -> Option<usize> {
+    let mut parser = Parser { text, pos: 0 };
+    if parser.peek()? != '{' {
+        return None;
+    }
+    parser.value()?;
+    Some(parser.pos)
+}
+
+/// Parse a header object into fields, each keeping its value's text.
+pub(crate) fn parse_header(header: &str) -> Option<Vec<Entry>> {
+    let mut parser = Parser { text: header, pos: 0 };
+    let mut entries = Vec::new();
+    parser.expect('{')?;
+    if !parser.eat('}') {
+        loop {
+            parser.skip_ws();
+            let key = parser.string()?;
+            parser.expect(':')?;
+            parser.skip_ws();
+            let start = parser.pos;
+            let value = parser.value()?;
+            let raw = &header[start..parser.pos];
+            entries.push(Entry::Field {
+                key,
+                value: view(&value, raw),
+                raw: Some(raw.to_string()),
+                block: vec![],
+            });
+            if parser.eat('}') {
+                break;
+            }
+            parser.expect(',')?;
+        }
+    }
+    parser.skip_ws();
+    (parser.pos == header.len()).then_some(entries)
+}
+
+/// A field holding `value`.
+pub(crate) fn render_field(key: &str, value: &Value) -> Entry {
+    let raw = render(value, 1);
+    Entry::Field {
+        key: key.to_string(),
+        value: view(value, &raw),
+        raw: Some(raw),
+        block: vec![],
+    }
+}
+
+/// Render a header object from its fields, with `\n` line breaks.
+pub(crate) fn render_header(entries: &[Entry]) -> String {
+    let members: Vec<String> = entries
+        .iter()
+        .filter_map(|entry| match entry {
+            Entry::Field { key, raw: Some(raw), .. } => {
+                Some(format!("  {}: {raw}", quote(key)))
+            }
+            Entry::Field { key, value, raw: None, .. } => {
+                Some(format!("  {}: {}", quote(key), quote(value)))
+            }
+            Entry::Raw(_) => None,
+        })
+        .collect();
+    format!("{{\n{}\n}}", members.join(",\n"))
+}
+
+/// Render a value, spreading non-empty collections over lines indented
+/// two spaces per level from `depth`.
+pub(crate) fn render(value: &Value, depth: usize) -> String {
+    let pad = |depth: usize| "  ".repeat(depth);
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Int(n) => n.to_string(),
+        // JSON has no infinities or NaN.
+        Value::Float(n) if !n.is_finite() => "null".to_string(),
+        Value::Float(n) => format!("{n:?}"),
+        Value::String(s) => quote(s),
+        Value::List(items) if items.is_empty() => "[]".to_string(),
+        Value::Map(entries) if entries.is_empty() => "{}".to_string(),
+        Value::List(items) => {
+            let items: Vec<String> = items
+                .iter()
+                .map(|item| {
+                    format!("{}{}", pad(depth + 1), render(item, depth + 1))
+                })
+                .collect();
+            format!("[\n{}\n{}]", items.join(",\n"), pad(depth))
+        }
+        Value::Map(entries) => {
+            let entries: Vec<String> = entries
+                .iter()
+                .map(|(k, v)| {
+                    let v = render(v, depth + 1);
+                    format!("{}{}: {v}", pad(depth + 1), quote(k))
+                })
+                .collect();
+            format!("{{\n{}\n{}}}", entries.join(",\n"), pad(depth))
+        }
+    }
+}
+
+/// Render a JSON string.
+fn quote(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => {
+                quoted.push_str(&format!("\\u{:04x}", u32::from(c)));
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// A recursive descent JSON parser.
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn skip_ws(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_ws();
+        let hit = self.peek() == Some(c);
+        if hit {
+            self.bump();
+        }
+        hit
+    }
+
+    fn expect(&mut self, c: char) -> Option<()> {
+        self.eat(c).then_some(())
+    }
+
+    fn value(&mut self) -> Option<Value> {
+        self.skip_ws();
+        match self.peek()? {
+            '{' => {
+                self.bump();
+                let mut entries = Vec::new();
+                if self.eat('}') {
+                    return Some(Value::Map(entries));
+                }
+                loop {
+                    self.skip_ws();
+                    let key = self.string()?;
+                    self.expect(':')?;
+                    entries.push((key, self.value()?));
+                    if self.eat('}') {
+                        return Some(Value::Map(entries));
+                    }
+                    self.expect(',')?;
+                }
+            }
+            '[' => {
+                self.bump();
+                let mut items = Vec::new();
+                if self.eat(']') {
+                    return Some(Value::List(items));
+                }
+                loop {
+                    items.push(self.value()?);
+                    if self.eat(']') {
+                        return Some(Value::List(items));
+                    }
+                    self.expect(',')?;
+                }
+            }
+            '"' => self.string().map(Value::String),
+            _ => self.literal(),
+        }
+    }
+
+    fn string(&mut self) -> Option<String> {
+        if self.bump()? != '"' {
+            return None;
+        }
+
+        let mut out = String::new();
+        loop {
+            match self.bump()? {
+                '"' => return Some(out),
+                '\\' => out.push(match self.bump()? {
+                    'n' => '\n',
+                    'r' => '\r',
+                    't' => '\t',
+                    'b' => '\u{8}',
+                    'f' => '\u{c}',
+                    'u' => self.unicode_escape()?,
+                    c @ ('"' | '\\' | '/') => c,
+                    _ => return None,
+                }),
+                c => out.push(c),
+            }
+        }
+    }
+
+    /// The character of a `\uXXXX` escape, joining surrogate pairs.
+    fn unicode_escape(&mut self) -> Option<char> {
+        let unit = self.hex4()?;
+        if !(0xD800..0xDC00).contains(&unit) {
+            return Some(
+                char::from_u32(unit).unwrap_or(char::REPLACEMENT_CHARACTER),
+            );
+        }
+
+        if !self.text[self.pos..].starts_with("\\u") {
+            return Some(char::REPLACEMENT_CHARACTER);
+        }
+        self.pos += 2;
+        let low = self.hex4()?;
+        let c = 0x10000 + ((unit - 0xD800) << 10) + low.wrapping_sub(0xDC00);
+        Some(char::from_u32(c).unwrap_or(char::REPLACEMENT_CHARACTER))
+    }
+
+    fn hex4(&mut self) -> Option<u32> {
+        let digits = self.text.get(self.pos..self.pos + 4)?;
+        let n = u32::from_str_radix(digits, 16).ok()?;
+        self.pos += 4;
+        Some(n)
+    }
+
+    /// `true`, `false`, `null` or a number.
+    fn literal(&mut self) -> Option<Value> {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+        {
+            self.bump();
+        }
+
+        let token = &self.text[start..self.pos];
+        match token {
+            "true" => Some(Value::Bool(true)),
+            "false" => Some(Value::Bool(false)),
+            "null" => Some(Value::Null),
+            _ if token
+                .starts_with(|c: char| c == '-' || c.is_ascii_digit()) =>
+            {
+                token
+                    .parse()
+                    .map(Value::Int)
+                    .or_else(|_| token.parse().map(Value::Float))
+                    .ok()
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse(r#"{"a": [1, 2.5, true, null], "b": "\u00e9\ud83d\ude00"}"#),
+            Some(Value::Map(vec![
+                (
+                    "a".into(),
+                    Value::List(vec![
+                        Value::Int(1),
+                        Value::Float(2.5),
+                        Value::Bool(true),
+                        Value::Null,
+                    ]),
+                ),
+                ("b".into(), "é😀".into()),
+            ]))
+        );
+        assert_eq!(parse("{\"a\": }"), None);
+        assert_eq!(parse("[1, 2,]"), None);
+    }
+
+    #[test]
+    fn test_object_len_stops_at_the_closing_brace() {
+        let text = "{\"a\": \"}\"}\nbody {";
+        assert_eq!(object_len(text), Some(10));
+        assert_eq!(object_len("{ unterminated"), None);
+        assert_eq!(object_len("[]"), None);
+    }
+
+    #[test]
+    fn test_render_round_trips() {
+        let value = Value::Map(vec![
+            ("list".into(), vec![1, 2].into()),
+            ("text".into(), "say \"hi\"\n".into()),
+            ("empty".into(), Value::Map(vec![])),
+        ]);
+        let text = render(&value, 0);
+        assert_eq!(
+            text,
+            "{\n  \"list\": [\n    1,\n    2\n  ],\n  \
+             \"text\": \"say \\\"hi\\\"\\n\",\n  \"empty\": {}\n}"
+        );
+        assert_eq!(parse(&text), Some(value));
+    }
+}
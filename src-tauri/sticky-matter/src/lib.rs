@@ -1,36 +1,104 @@
-//! Format-preserving YAML frontmatter for markdown files.
+//! Format-preserving YAML, TOML and JSON frontmatter for markdown
+//! files.
+
+mod block;
+mod de;
+mod error;
+mod json;
+mod lint;
+mod section;
+mod ser;
+mod toml;
+mod value;
 
 use std::borrow::Cow;
+use std::io::{self, BufRead};
+use std::ops::Range;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+pub use error::Error;
+pub use lint::{Diagnostic, Severity};
+pub use section::Section;
+pub use value::Value;
 
-/// A markdown file with an optional YAML frontmatter header.
+/// A markdown file with an optional frontmatter header.
+///
+/// Headers may be YAML between `---` fences, TOML between `+++` fences
+/// (as Hugo and Zola write them), or a JSON object opening the file.
+/// The same API reads and edits all three, and each renders back in
+/// its own [`Format`] until converted with [`set_format`].
 ///
 /// Parsing is lenient and infallible: any text yields a document, and
-/// header lines the parser doesn't manage — comments, lists, indented
-/// block content — survive rendering byte for byte, in their original
-/// order. Only the flat `key: value` fields touched through [`set`]
-/// and [`remove`] are rewritten.
+/// header lines survive rendering byte for byte, in their original
+/// order. Fields are read as strings through [`get`] or as typed
+/// [`Value`]s through [`value`], block sequences and nested mappings
+/// included. Only the nodes touched through [`set`], [`set_value`],
+/// [`set_value_at`] and [`remove`] are rewritten; comments and lines
+/// the parser doesn't understand pass through untouched.
+///
+/// Documents written with `\r\n` line endings are recognized as such
+/// and render back with them.
+///
+/// The body can be read and edited by markdown section as well, through
+/// [`sections`] and the methods after it, leaving the rest of it
+/// untouched.
 ///
+/// [`get`]: Self::get
+/// [`value`]: Self::value
 /// [`set`]: Self::set
+/// [`set_value`]: Self::set_value
+/// [`set_value_at`]: Self::set_value_at
 /// [`remove`]: Self::remove
+/// [`set_format`]: Self::set_format
+/// [`sections`]: Self::sections
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct Document {
+    /// The syntax of the header.
+    format: Format,
     /// The frontmatter lines, in file order.
     header: Vec<Entry>,
+    /// A JSON header as parsed, rendered verbatim until a field
+    /// changes.
+    json: Option<String>,
     /// The markdown body, without trailing newlines.
     body: String,
+    /// Whether lines end in `\r\n` rather than `\n`.
+    crlf: bool,
+}
+
+/// The syntax of a frontmatter header.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    /// YAML between `---` fences.
+    #[default]
+    Yaml,
+    /// TOML between `+++` fences.
+    Toml,
+    /// A JSON object opening the file.
+    Json,
 }
 
 /// A line in the frontmatter header.
 #[derive(Debug, Clone, PartialEq)]
 enum Entry {
-    /// A top-level `key: value` line.
+    /// A top-level `key: value` line and the block content under it.
+    ///
+    /// In TOML headers this is a `key = value` line, or a `[table]`
+    /// with its lines as the block; in JSON ones, an object member
+    /// whose `raw` text is the member's value.
     Field {
         /// The key before the colon.
         key: String,
-        /// The value, with quoting and trailing comments resolved.
+        /// The value on the key's line, with quoting and trailing
+        /// comments resolved.
         value: String,
         /// The original line, kept verbatim until the value changes.
         raw: Option<String>,
+        /// The indented lines (and compact `- ` items) under the key,
+        /// verbatim.
+        block: Vec<String>,
     },
     /// Any other line, rendered back verbatim.
     Raw(String),
@@ -47,20 +115,130 @@ impl Document {
     /// Never fails: text without a complete frontmatter header is a
     /// document whose entire content is the body.
     pub fn parse(text: &str) -> Self {
-        let Some((header, body)) = split(text) else {
-            return Self { header: vec![], body: trim_body(text) };
+        let crlf = is_crlf(text);
+        let body_only =
+            || Self { body: trim_body(text), crlf, ..Self::default() };
+        let Some((format, header, body)) = split(text) else {
+            return body_only();
+        };
+
+        let (header, json) = match format {
+            Format::Yaml => (parse_header(header), None),
+            Format::Toml => (toml::parse_header(header), None),
+            Format::Json => {
+                let header = header.replace("\r\n", "\n");
+                match json::parse_header(&header) {
+                    Some(entries) => (entries, Some(header)),
+                    None => return body_only(),
+                }
+            }
         };
+        Self { format, header, json, body: trim_body(body), crlf }
+    }
+
+    /// Parse only the head of a file: its header and the first
+    /// non-empty body line, read from `reader` without touching the
+    /// rest.
+    ///
+    /// The result is what [`parse`] makes of those lines, so its body
+    /// holds the first line alone. Useful for listing large files by
+    /// their header and title. A header that never closes is no header,
+    /// and finding that out takes the whole input.
+    ///
+    /// Fails only if reading does, including on text that isn't UTF-8.
+    ///
+    /// [`parse`]: Self::parse
+    pub fn parse_head(mut reader: impl BufRead) -> io::Result<Self> {
+        let mut text = String::new();
+        let mut read_line =
+            |text: &mut String| Ok::<_, io::Error>(reader.read_line(text)? > 0);
+
+        read_line(&mut text)?;
+        let first = text.trim_end_matches(['\r', '\n']).to_string();
+        match first.as_str() {
+            fence @ ("---" | "+++") => {
+                // The first header line never closes the header.
+                let mut lines = 0;
+                loop {
+                    let start = text.len();
+                    if !read_line(&mut text)? {
+                        break;
+                    }
+                    let current = text[start..].trim_end_matches(['\r', '\n']);
+                    if current == fence && lines > 0 {
+                        break;
+                    }
+                    lines += 1;
+                }
+            }
+            _ if first.starts_with('{') => {
+                while json::object_len(&text).is_none() {
+                    if !read_line(&mut text)? {
+                        break;
+                    }
+                }
+            }
+            // The first line is the body's, and all that's needed.
+            _ if !first.trim().is_empty() => return Ok(Self::parse(&text)),
+            _ => {}
+        }
+
+        loop {
+            let start = text.len();
+            if !read_line(&mut text)? || !text[start..].trim().is_empty() {
+                break;
+            }
+        }
+        Ok(Self::parse(&text))
+    }
+
+    /// Check file contents for header problems that [`parse`] glosses
+    /// over, like tab indentation, duplicate keys, unclosed quotes or a
+    /// misplaced fence, in line order.
+    ///
+    /// Purely informational: the parse reads the same text the same
+    /// way regardless.
+    ///
+    /// [`parse`]: Self::parse
+    pub fn diagnostics(text: &str) -> Vec<Diagnostic> {
+        lint::check(text)
+    }
+
+    /// Whether the document uses `\r\n` line endings.
+    pub fn is_crlf(&self) -> bool {
+        self.crlf
+    }
+
+    /// The syntax of the header. Documents without one are YAML, the
+    /// format new fields are written in.
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    /// Convert the header to another format.
+    ///
+    /// Every field is rewritten in the new syntax, in the same order;
+    /// comments and lines that aren't fields are dropped. TOML has no
+    /// null, so converting to it drops null fields too.
+    pub fn set_format(&mut self, format: Format) {
+        if format == self.format {
+            return;
+        }
 
-        Self {
-            header: header.lines().map(Entry::parse).collect(),
-            body: trim_body(body),
+        let fields = self.fields();
+        self.format = format;
+        self.header.clear();
+        self.json = None;
+        for (key, value) in fields {
+            self.set_value(&key, value);
         }
     }
 
     /// The value of a field.
     ///
-    /// The value has one layer of YAML quoting stripped and, for
-    /// plain values, any trailing ` # comment` removed.
+    /// The value has one layer of quoting stripped and, for plain
+    /// values, any trailing ` # comment` removed. Values that aren't
+    /// strings read as their source text.
     pub fn get(&self, key: &str) -> Option<&str> {
         self.header.iter().find_map(|entry| match entry {
             Entry::Field { key: k, value, .. } if k == key => {
@@ -70,35 +248,174 @@ impl Document {
         })
     }
 
+    /// The keys of the header's fields, in order.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.header.iter().filter_map(|entry| match entry {
+            Entry::Field { key, .. } => Some(key.as_str()),
+            _ => None,
+        })
+    }
+
+    /// The typed value of a field, including block sequences and
+    /// mappings indented under it.
+    pub fn value(&self, key: &str) -> Option<Value> {
+        self.field(key)?.value(self.format)
+    }
+
+    /// The typed value at a path of keys into nested mappings.
+    pub fn value_at(&self, path: &[&str]) -> Option<Value> {
+        let (key, rest) = path.split_first()?;
+        self.value(key)?.get_path(rest).cloned()
+    }
+
     /// Set a field.
     ///
-    /// An existing field keeps its position in the header; a new one
-    /// is appended at the end.
+    /// An existing field keeps its position in the header, and any
+    /// block content under it is dropped; a new one is appended at the
+    /// end.
     pub fn set(&mut self, key: &str, value: impl Into<String>) {
+        let key = key.to_string();
+        let (value, raw, block) = (value.into(), None, vec![]);
+        self.put(Entry::Field { key, value, raw, block });
+    }
+
+    /// Set a field to a typed value.
+    ///
+    /// Collections keep the style they were written in: a flow `[a, b]`
+    /// stays on the key's line, block content stays indented as it
+    /// was, and new collections are written in block style (as tables
+    /// in TOML). Setting a field to the value it already has leaves it
+    /// untouched. TOML has no null; setting a field there to
+    /// [`Value::Null`] removes it.
+    pub fn set_value(&mut self, key: &str, value: impl Into<Value>) {
         let value = value.into();
-        for entry in &mut self.header {
-            if let Entry::Field { key: k, value: v, raw } = entry {
-                if k == key {
-                    *v = value;
-                    *raw = None;
-                    return;
-                }
+        if self.value(key).as_ref() == Some(&value) {
+            return;
+        }
+
+        let field = match self.format {
+            Format::Yaml => self.yaml_field(key, &value),
+            Format::Toml if value.is_null() => {
+                self.remove(key);
+                return;
+            }
+            Format::Toml => {
+                let table = match self.field(key) {
+                    Some(Entry::Field { raw: Some(raw), .. }) => {
+                        toml::is_table(raw)
+                    }
+                    _ => !value.is_inline(),
+                };
+                toml::render_field(key, &value, table)
             }
+            Format::Json => json::render_field(key, &value),
+        };
+        self.put(field);
+    }
+
+    /// A YAML field holding `value`, in the style of the field it
+    /// replaces.
+    fn yaml_field(&self, key: &str, value: &Value) -> Entry {
+        let (flow, child_indent) = match self.field(key).and_then(Entry::node) {
+            Some((inline, block)) => {
+                let block: Vec<&str> =
+                    block.iter().map(String::as_str).collect();
+                let flow = value::strip_comment(&inline)
+                    .trim()
+                    .starts_with(['[', '{']);
+                (flow, block::child_indent(&block, 0, value))
+            }
+            None => (false, 2),
+        };
+
+        let mut lines =
+            block::render_entry(key, value, 0, child_indent, flow).into_iter();
+        let line = lines.next().expect("Entries render their key line");
+        Entry::Field {
+            key: key.to_string(),
+            value: parse_value(line[key.len() + 1..].trim()),
+            raw: Some(line),
+            block: lines.collect(),
         }
+    }
 
-        let key = key.to_string();
-        self.header.push(Entry::Field { key, value, raw: None });
+    /// Set the value at a path of keys into nested mappings, creating
+    /// the mappings along the way.
+    ///
+    /// Inside YAML block mappings only the lines of the node the path
+    /// ends at are rewritten; its siblings, and any comments between
+    /// them, keep their exact bytes. Elsewhere the whole field is.
+    pub fn set_value_at(&mut self, path: &[&str], value: impl Into<Value>) {
+        let value = value.into();
+        let Some((key, rest)) = path.split_first() else {
+            return;
+        };
+        if rest.is_empty() {
+            return self.set_value(key, value);
+        }
+
+        let yaml = self.format == Format::Yaml;
+        if let Some(Entry::Field { raw: Some(raw), block, .. }) =
+            self.field_mut(key).filter(|_| yaml)
+        {
+            let inline = value::strip_comment(&raw[key.len() + 1..]);
+            let refs: Vec<&str> = block.iter().map(String::as_str).collect();
+            if inline.trim().is_empty() && block::is_map(&refs) {
+                return block::set_in_block(block, 0, rest, value);
+            }
+        }
+
+        let mut node = self.value(key).unwrap_or(Value::Null);
+        node.set_path(rest, value);
+        self.set_value(key, node);
+    }
+
+    /// Deserialize the header into `T`, typically a struct declaring
+    /// the fields it cares about.
+    ///
+    /// Fields `T` doesn't declare are ignored unless it denies unknown
+    /// fields. Plain scalars that YAML reads as numbers or booleans
+    /// still deserialize into strings.
+    pub fn header_as<T: DeserializeOwned>(&self) -> Result<T, Error> {
+        T::deserialize(Value::Map(self.fields()))
+    }
+
+    /// Update the header from `value`, a struct or map serializing to
+    /// a mapping.
+    ///
+    /// Only fields whose value changed are rewritten, through
+    /// [`set_value_at`](Self::set_value_at): nested mappings merge key
+    /// by key, and fields `value` doesn't mention — foreign ones
+    /// included — keep their exact bytes. A `None` removes its
+    /// top-level field.
+    pub fn merge_header<T: Serialize>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), Error> {
+        let Value::Map(fields) = value.serialize(ser::Serializer)? else {
+            return Err(serde::ser::Error::custom(
+                "Headers merge from structs and maps",
+            ));
+        };
+
+        for (key, value) in fields {
+            if value.is_null() {
+                if self.value(&key).is_some_and(|v| !v.is_null()) {
+                    self.remove(&key);
+                }
+            } else {
+                self.merge_at(&[&key], value);
+            }
+        }
+        Ok(())
     }
 
     /// Remove a field, returning its value.
     ///
-    /// Only the field's own line is removed; block content indented
-    /// under it stays behind as raw lines.
+    /// The field's block content is removed with it.
     pub fn remove(&mut self, key: &str) -> Option<String> {
-        let index = self.header.iter().position(
-            |entry| matches!(entry, Entry::Field { key: k, .. } if k == key),
-        )?;
-
+        let index = self.position(key)?;
+        self.json = None;
         let Entry::Field { value, .. } = self.header.remove(index) else {
             return None;
         };
@@ -106,6 +423,8 @@ impl Document {
     }
 
     /// The markdown body, without trailing newlines.
+    ///
+    /// The body keeps the line endings it was parsed with.
     pub fn body(&self) -> &str {
         &self.body
     }
@@ -113,38 +432,272 @@ impl Document {
     /// Replace the body.
     ///
     /// Trailing newlines are normalized away; [`render`](Self::render)
-    /// terminates the file with a single one.
+    /// terminates the file with a single one. In a `\r\n` document,
+    /// bare `\n` line breaks are converted to match.
     pub fn set_body(&mut self, body: impl Into<String>) {
-        self.body = body.into();
-        self.body.truncate(self.body.trim_end_matches('\n').len());
+        let mut body = body.into();
+        if self.crlf {
+            body = body.replace("\r\n", "\n").replace('\n', "\r\n");
+        }
+        body.truncate(body.trim_end_matches(['\r', '\n']).len());
+        self.body = body;
+    }
+
+    /// The body's sections, in order; see [`Section`].
+    pub fn sections(&self) -> Vec<Section> {
+        section::headings(&self.body).into_iter().map(|h| h.section).collect()
+    }
+
+    /// The content of the first section `heading` names, without the
+    /// heading line and the blank lines around it.
+    ///
+    /// `heading` is either a section's title, matching any level, or
+    /// a full heading like `## Inbox`, matching only that level. The
+    /// content includes subsections.
+    pub fn section(&self, heading: &str) -> Option<&str> {
+        let heading = self.heading(heading)?;
+        Some(&self.body[heading.content(&self.body)])
+    }
+
+    /// Add lines to the end of a section's content, after any
+    /// subsections. Returns whether the section exists.
+    ///
+    /// This and the other section edits leave the rest of the body
+    /// byte for byte as it was.
+    pub fn append_to_section(&mut self, heading: &str, text: &str) -> bool {
+        self.edit_section(heading, text, |content, text, newline| {
+            (content.end..content.end, format!("{newline}{text}"))
+        })
+    }
+
+    /// Add lines to the start of a section's content, right under its
+    /// heading's blank lines. Returns whether the section exists.
+    pub fn prepend_to_section(&mut self, heading: &str, text: &str) -> bool {
+        self.edit_section(heading, text, |content, text, newline| {
+            (content.start..content.start, format!("{text}{newline}"))
+        })
+    }
+
+    /// Replace a section's content, keeping its heading and the blank
+    /// lines around it. Returns whether the section exists.
+    pub fn replace_section(&mut self, heading: &str, text: &str) -> bool {
+        self.edit_section(heading, text, |content, text, _| {
+            (content, text.to_string())
+        })
+    }
+
+    /// Insert a new section after the one `heading` names and its
+    /// subsections, separated by blank lines. `section` is the new
+    /// section's text, heading line included. Returns whether the
+    /// section `heading` exists.
+    pub fn insert_section_after(
+        &mut self,
+        heading: &str,
+        section: &str,
+    ) -> bool {
+        let Some(heading) = self.heading(heading) else {
+            return false;
+        };
+        let newline = if self.crlf { "\r\n" } else { "\n" };
+        let section = self.normalize(section);
+        let insert = if heading.end < self.body.len() {
+            format!("{section}{newline}{newline}")
+        } else {
+            format!("{newline}{newline}{section}")
+        };
+        self.body.insert_str(heading.end, &insert);
+        true
+    }
+
+    /// Splice `text` into the section `heading` names at the range
+    /// `edit` picks from its content range, or right under the heading
+    /// line if the section is empty.
+    fn edit_section(
+        &mut self,
+        heading: &str,
+        text: &str,
+        edit: impl FnOnce(Range<usize>, &str, &str) -> (Range<usize>, String),
+    ) -> bool {
+        let Some(heading) = self.heading(heading) else {
+            return false;
+        };
+        let newline = if self.crlf { "\r\n" } else { "\n" };
+        let text = self.normalize(text);
+        let content = heading.content(&self.body);
+
+        let (range, text) = if !content.is_empty() {
+            edit(content, &text, newline)
+        } else if content.start == self.body.len() && !self.body.ends_with('\n')
+        {
+            // The heading is the body's last line.
+            (content, format!("{newline}{text}"))
+        } else {
+            (content, format!("{text}{newline}"))
+        };
+        self.body.replace_range(range, &text);
+        true
+    }
+
+    /// The first heading `query` names.
+    fn heading(&self, query: &str) -> Option<section::Heading> {
+        section::headings(&self.body).into_iter().find(|h| h.matches(query))
+    }
+
+    /// Text to insert into the body: line breaks matching the
+    /// document's, trailing ones trimmed.
+    fn normalize(&self, text: &str) -> String {
+        let text = text.trim_end_matches(['\r', '\n']);
+        if self.crlf {
+            text.replace("\r\n", "\n").replace('\n', "\r\n")
+        } else {
+            text.replace("\r\n", "\n")
+        }
     }
 
     /// Render the document back into file contents.
     ///
     /// Untouched header lines reproduce byte for byte; modified
-    /// fields are written as `key: value`, quoted only when YAML
-    /// would misread the plain value. An empty header renders no
-    /// fences at all.
+    /// fields are written in the header's format, YAML values quoted
+    /// only when YAML would misread them plain. An empty header
+    /// renders no fences at all.
     pub fn render(&self) -> String {
+        let newline = if self.crlf { "\r\n" } else { "\n" };
+
         let mut out = String::new();
-        if !self.header.is_empty() {
-            out.push_str("---\n");
+        if let Some(json) = &self.json {
+            out.push_str(&json.replace('\n', newline));
+            out.push_str(newline);
+        } else if self.header.is_empty() {
+            // No header, no fences.
+        } else if self.format == Format::Json {
+            let header = json::render_header(&self.header);
+            out.push_str(&header.replace('\n', newline));
+            out.push_str(newline);
+        } else {
+            let fence = if self.format == Format::Toml { "+++" } else { "---" };
+            out.push_str(fence);
+            out.push_str(newline);
             for entry in &self.header {
-                entry.render_into(&mut out);
+                entry.render_into(&mut out, newline, self.format);
             }
-            out.push_str("---\n");
+            out.push_str(fence);
+            out.push_str(newline);
         }
 
         if !self.body.is_empty() {
             if !out.is_empty() {
-                out.push('\n');
+                out.push_str(newline);
             }
             out.push_str(&self.body);
-            out.push('\n');
+            out.push_str(newline);
         }
 
         out
     }
+
+    /// Merge `value` into the node at `path`, descending into
+    /// mappings on both sides.
+    fn merge_at(&mut self, path: &[&str], value: Value) {
+        match (value, self.value_at(path)) {
+            (Value::Map(entries), Some(Value::Map(_))) => {
+                for (key, value) in entries {
+                    let mut path = path.to_vec();
+                    path.push(&key);
+                    self.merge_at(&path, value);
+                }
+            }
+            (Value::Null, None) => {}
+            // A plain `id: 123` read into a string by `header_as`.
+            (Value::String(text), Some(current))
+                if self.spells_scalar(path, &current, &text) => {}
+            (value, current) if current.as_ref() != Some(&value) => {
+                self.set_value_at(path, value);
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether `text` is the number or boolean `current` at `path` as
+    /// written, or as [`header_as`](Self::header_as) reads it into a
+    /// string.
+    fn spells_scalar(
+        &self,
+        path: &[&str],
+        current: &Value,
+        text: &str,
+    ) -> bool {
+        matches!(current, Value::Bool(_) | Value::Int(_) | Value::Float(_))
+            && (value::render_scalar(current, false) == text
+                || matches!(path, [key] if self.get(key) == Some(text)))
+    }
+
+    /// Every field's typed value, in header order. Like `get`, the
+    /// first of duplicate keys wins.
+    fn fields(&self) -> Vec<(String, Value)> {
+        let mut fields: Vec<(String, Value)> = Vec::new();
+        for entry in &self.header {
+            if let Entry::Field { key, .. } = entry {
+                if !fields.iter().any(|(k, _)| k == key) {
+                    let value = entry.value(self.format).unwrap_or(Value::Null);
+                    fields.push((key.clone(), value));
+                }
+            }
+        }
+        fields
+    }
+
+    /// Store a field, replacing the one with its key in place or
+    /// appending it.
+    ///
+    /// TOML keys after a `[table]` belong to the table, so there plain
+    /// fields go before the first table instead.
+    fn put(&mut self, field: Entry) {
+        let Entry::Field { key, raw, .. } = &field else {
+            return;
+        };
+        let table = raw.as_deref().is_some_and(toml::is_table);
+
+        self.json = None;
+        let index = match self.position(key) {
+            Some(index) => {
+                self.header[index] = field;
+                index
+            }
+            None => {
+                self.header.push(field);
+                self.header.len() - 1
+            }
+        };
+
+        if self.format == Format::Toml && !table {
+            let first_table = self.header.iter().position(|entry| {
+                matches!(entry, Entry::Field { raw: Some(raw), .. }
+                    if toml::is_table(raw))
+            });
+            if let Some(first_table) = first_table.filter(|&i| i < index) {
+                let field = self.header.remove(index);
+                self.header.insert(first_table, field);
+            }
+        }
+    }
+
+    /// The index of the field named `key`.
+    fn position(&self, key: &str) -> Option<usize> {
+        self.header.iter().position(
+            |entry| matches!(entry, Entry::Field { key: k, .. } if k == key),
+        )
+    }
+
+    /// The field named `key`.
+    fn field(&self, key: &str) -> Option<&Entry> {
+        self.header.get(self.position(key)?)
+    }
+
+    /// The field named `key`, mutably.
+    fn field_mut(&mut self, key: &str) -> Option<&mut Entry> {
+        let index = self.position(key)?;
+        self.header.get_mut(index)
+    }
 }
 
 impl Entry {
@@ -170,52 +723,206 @@ impl Entry {
             key: key.to_string(),
             value: parse_value(rest.trim()),
             raw: Some(line.to_string()),
+            block: vec![],
         }
     }
 
-    /// Render the line, followed by a newline.
-    fn render_into(&self, out: &mut String) {
+    /// A field's typed value in a header of `format`.
+    fn value(&self, format: Format) -> Option<Value> {
+        let value = match (format, self) {
+            (_, Self::Raw(_)) => return None,
+            (Format::Yaml, _) => {
+                let (inline, block) = self.node()?;
+                let block: Vec<&str> =
+                    block.iter().map(String::as_str).collect();
+                block::parse_node(&inline, &block)
+            }
+            (_, Self::Field { value, raw: None, .. }) => {
+                Value::String(value.clone())
+            }
+            (Format::Toml, Self::Field { raw: Some(raw), block, .. }) => {
+                toml::field_value(raw, block)
+            }
+            (Format::Json, Self::Field { raw: Some(raw), .. }) => {
+                json::parse(raw).unwrap_or_else(|| Value::String(raw.clone()))
+            }
+        };
+        Some(value)
+    }
+
+    /// A YAML field's node: the text after its colon and its block
+    /// lines.
+    fn node(&self) -> Option<(Cow<'_, str>, &[String])> {
+        match self {
+            Self::Field { key, raw: Some(raw), block, .. } => {
+                Some((Cow::Borrowed(&raw[key.len() + 1..]), block))
+            }
+            Self::Field { value, raw: None, block, .. } => {
+                Some((quote(value), block))
+            }
+            Self::Raw(_) => None,
+        }
+    }
+
+    /// Render the entry's lines, each followed by `newline`.
+    fn render_into(&self, out: &mut String, newline: &str, format: Format) {
         match self {
             Self::Field { raw: Some(raw), .. } | Self::Raw(raw) => {
                 out.push_str(raw);
             }
-            Self::Field { key, value, raw: None } => {
-                out.push_str(key);
-                out.push_str(": ");
-                out.push_str(&quote(value));
+            Self::Field { key, value, raw: None, .. } => match format {
+                Format::Toml => {
+                    out.push_str(&toml::render_key(key));
+                    out.push_str(" = ");
+                    out.push_str(&toml::quote(value));
+                }
+                _ => {
+                    out.push_str(key);
+                    out.push_str(": ");
+                    out.push_str(&quote(value));
+                }
+            },
+        }
+        out.push_str(newline);
+
+        if let Self::Field { block, .. } = self {
+            for line in block {
+                out.push_str(line);
+                out.push_str(newline);
+            }
+        }
+    }
+}
+
+/// Parse header lines into entries, attaching block content to the
+/// field it sits under.
+///
+/// Indented lines belong to the field above them, and so do `- `
+/// items at column 0 when the field's own line has no value. Blank
+/// lines and comments between them go along; trailing ones stay
+/// separate lines.
+fn parse_header(header: &str) -> Vec<Entry> {
+    let mut entries = Vec::new();
+    let mut pending: Vec<&str> = Vec::new();
+
+    for line in header.lines() {
+        let continues = match entries.last() {
+            Some(Entry::Field { key, raw: Some(raw), .. }) => {
+                line.starts_with([' ', '\t'])
+                    || (block::is_item(line)
+                        && value::strip_comment(&raw[key.len() + 1..])
+                            .trim()
+                            .is_empty())
             }
+            _ => false,
+        };
+
+        if continues {
+            let Some(Entry::Field { block, .. }) = entries.last_mut() else {
+                unreachable!("Only fields continue");
+            };
+            block.extend(pending.drain(..).map(str::to_string));
+            block.push(line.to_string());
+        } else if block::is_filler(line) && !entries.is_empty() {
+            pending.push(line);
+        } else {
+            entries.extend(pending.drain(..).map(Entry::parse));
+            entries.push(Entry::parse(line));
         }
-        out.push('\n');
     }
+
+    entries.extend(pending.into_iter().map(Entry::parse));
+    entries
+}
+
+/// Whether text uses `\r\n` line endings, judged by its first line
+/// break.
+fn is_crlf(text: &str) -> bool {
+    text.find('\n').is_some_and(|i| text[..i].ends_with('\r'))
+}
+
+/// Split text into header and body if it starts with frontmatter,
+/// detecting its format.
+///
+/// Fence lines may end in `\n` or `\r\n`. The closing fence needs at
+/// least one header line before it. A JSON header is an object opening
+/// the text, alone on its last line.
+fn split(text: &str) -> Option<(Format, &str, &str)> {
+    if text.starts_with('{') {
+        let len = json::object_len(text)?;
+        let (line, body) =
+            text[len..].split_once('\n').unwrap_or((&text[len..], ""));
+        return line.trim().is_empty().then_some((
+            Format::Json,
+            &text[..len],
+            body,
+        ));
+    }
+
+    if let Some((header, body)) = split_fenced(text, "---") {
+        return Some((Format::Yaml, header, body));
+    }
+    split_fenced(text, "+++").map(|(header, body)| (Format::Toml, header, body))
+}
+
+/// Split text into header and body at `fence` lines.
+fn split_fenced<'a>(text: &'a str, fence: &str) -> Option<(&'a str, &'a str)> {
+    let rest = text.strip_prefix(fence)?;
+    let rest = rest.strip_prefix('\n').or_else(|| rest.strip_prefix("\r\n"))?;
+
+    let mut start = 0;
+    while let Some(newline) = rest[start..].find('\n').map(|i| start + i) {
+        let next = &rest[newline + 1..];
+        let (line, body) = match next.find('\n') {
+            Some(end) => (&next[..end], &next[end + 1..]),
+            None => (next, ""),
+        };
+        if line.strip_suffix('\r').unwrap_or(line) == fence {
+            let header = &rest[..newline];
+            return Some((header.strip_suffix('\r').unwrap_or(header), body));
+        }
+        start = newline + 1;
+    }
+    None
 }
 
-/// Split text into header and body if it starts with a frontmatter
-/// fence.
-fn split(text: &str) -> Option<(&str, &str)> {
-    let rest = text.strip_prefix("---\n")?;
-    match rest.find("\n---\n") {
-        Some(end) => Some((&rest[..end], &rest[end + 5..])),
-        None => rest.strip_suffix("\n---").map(|header| (header, "")),
+/// The string view of a field's value: strings as they are, anything
+/// else as its source `text`.
+fn view(value: &Value, text: &str) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        _ => text.trim().to_string(),
     }
 }
 
 /// Strip the blank separator line and trailing newlines.
 fn trim_body(body: &str) -> String {
-    let body = body.strip_prefix('\n').unwrap_or(body);
-    body.trim_end_matches('\n').to_string()
+    let body = body
+        .strip_prefix('\n')
+        .or_else(|| body.strip_prefix("\r\n"))
+        .unwrap_or(body);
+    body.trim_end_matches(['\r', '\n']).to_string()
 }
 
 /// Extract the value from the text after a field's colon.
 ///
 /// Strips one layer of quoting from quoted values and a trailing
 /// ` # comment` from plain ones. Only the escapes [`Document::render`]
-/// writes (`\"` and `\\`) are interpreted.
+/// writes (`\"`, `\\`, `\n`, `\r` and `\t`) are interpreted.
 fn parse_value(raw: &str) -> String {
     if let Some(inner) = strip_quotes(raw, '"') {
         let mut out = String::with_capacity(inner.len());
         let mut chars = inner.chars();
         while let Some(c) = chars.next() {
-            out.push(if c == '\\' { chars.next().unwrap_or('\\') } else { c });
+            out.push(match c {
+                '\\' => match chars.next() {
+                    Some('n') => '\n',
+                    Some('r') => '\r',
+                    Some('t') => '\t',
+                    escaped => escaped.unwrap_or('\\'),
+                },
+                _ => c,
+            });
         }
         out
     } else if let Some(inner) = strip_quotes(raw, '\'') {
@@ -232,20 +939,11 @@ fn strip_quotes(raw: &str, quote: char) -> Option<&str> {
 
 /// Quote a value if YAML would misread it plain.
 fn quote(value: &str) -> Cow<'_, str> {
-    if !needs_quotes(value) {
-        return Cow::Borrowed(value);
-    }
-
-    let mut quoted = String::with_capacity(value.len() + 2);
-    quoted.push('"');
-    for c in value.chars() {
-        if matches!(c, '"' | '\\') {
-            quoted.push('\\');
-        }
-        quoted.push(c);
+    if needs_quotes(value) {
+        Cow::Owned(value::double_quote(value))
+    } else {
+        Cow::Borrowed(value)
     }
-    quoted.push('"');
-    Cow::Owned(quoted)
 }
 
 /// Whether a plain scalar would parse back differently.
@@ -263,10 +961,13 @@ fn needs_quotes(value: &str) -> bool {
         || value.contains(" #")
         || value.contains(": ")
         || value.ends_with(':')
+        || value.contains(['\n', '\r'])
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
+
     use super::*;
 
     #[test]
@@ -293,12 +994,43 @@ mod tests {
         test("---\nid: note_abc\n---\n\n# Title\n\nSome text.\n");
         test("---\nid: note_abc\n---\n");
         test("# Just a body\n");
+        test("---\r\nid: note_abc\r\n---\r\n\r\n# Title\r\n\r\nSome text.\r\n");
+        test("---\r\nid: note_abc\r\n---\r\n");
+        test("# Just a body\r\n\r\nwith lines\r\n");
         test(
             "---\n# a comment\nid:   spaced   \ntags:\n  - inbox\n  \
              - later\nempty:\nweird text here\n---\n\nbody\n",
         );
     }
 
+    #[test]
+    fn test_crlf_documents_keep_their_line_endings() {
+        let mut doc =
+            Document::parse("---\r\nid: old\r\ntags: [a]\r\n---\r\n\r\nhi\r\n");
+        assert!(doc.is_crlf());
+        assert_eq!(doc.get("id"), Some("old"));
+        assert_eq!(doc.body(), "hi");
+
+        doc.set("id", "new");
+        doc.set("createdAt", "2026-07-19");
+        doc.set_body("hi\nthere\n");
+        assert_eq!(
+            doc.render(),
+            "---\r\nid: new\r\ntags: [a]\r\ncreatedAt: 2026-07-19\r\n\
+             ---\r\n\r\nhi\r\nthere\r\n",
+        );
+    }
+
+    #[test]
+    fn test_crlf_body_gains_a_crlf_header() {
+        let mut doc = Document::parse("# Title\r\n\r\nbody\r\n");
+        doc.set("id", "note_abc");
+        assert_eq!(
+            doc.render(),
+            "---\r\nid: note_abc\r\n---\r\n\r\n# Title\r\n\r\nbody\r\n",
+        );
+    }
+
     #[test]
     fn test_get_resolves_quoting_and_comments() {
         #[track_caller]
@@ -326,6 +1058,7 @@ mod tests {
             doc.render(),
             "---\n# keep me\nid: new\ntags: [a, b]\n---\n\nhi\n",
         );
+        assert_eq!(doc.keys().collect::<Vec<_>>(), ["id", "tags"]);
     }
 
     #[test]
@@ -397,4 +1130,468 @@ mod tests {
         assert_eq!(doc.body(), "");
         assert_eq!(doc.render(), text);
     }
+
+    #[test]
+    fn test_typed_values() {
+        let doc = Document::parse(concat!(
+            "---\n",
+            "pinned: true\n",
+            "size: 12\n",
+            "ratio: 0.5\n",
+            "empty: ~\n",
+            "tags:\n",
+            "  - a\n",
+            "  # between items\n",
+            "  - \"b c\"\n",
+            "window: {x: 1, y: 2}\n",
+            "---\n",
+        ));
+
+        assert_eq!(doc.value("pinned"), Some(Value::Bool(true)));
+        assert_eq!(doc.value("size"), Some(Value::Int(12)));
+        assert_eq!(doc.value("ratio"), Some(Value::Float(0.5)));
+        assert_eq!(doc.value("empty"), Some(Value::Null));
+        assert_eq!(doc.value("tags"), Some(vec!["a", "b c"].into()));
+        assert_eq!(doc.value_at(&["window", "y"]), Some(Value::Int(2)));
+        assert_eq!(doc.value("missing"), None);
+    }
+
+    #[test]
+    fn test_compact_sequences_belong_to_their_key() {
+        let text = "---\ntags:\n- a\n- b\n\n# trailing\n---\n";
+        let mut doc = Document::parse(text);
+        assert_eq!(doc.value("tags"), Some(vec!["a", "b"].into()));
+        assert_eq!(doc.render(), text);
+
+        doc.set_value("tags", vec!["a", "b", "c"]);
+        assert_eq!(
+            doc.render(),
+            "---\ntags:\n- a\n- b\n- c\n\n# trailing\n---\n"
+        );
+    }
+
+    #[test]
+    fn test_set_value_keeps_the_collection_style() {
+        let mut doc = Document::parse(
+            "---\nflow: [a, b]\nblock:\n    - a\nother: 1 # keep\n---\n",
+        );
+        doc.set_value("flow", vec!["a", "true"]);
+        doc.set_value("block", vec!["x", "y"]);
+        doc.set_value("new", vec![1, 2]);
+        assert_eq!(
+            doc.render(),
+            concat!(
+                "---\n",
+                "flow: [a, \"true\"]\n",
+                "block:\n",
+                "    - x\n",
+                "    - y\n",
+                "other: 1 # keep\n",
+                "new:\n",
+                "  - 1\n",
+                "  - 2\n",
+                "---\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_unchanged_values_keep_their_bytes() {
+        let text = "---\ntags: [ a,b ] # spacing\n---\n";
+        let mut doc = Document::parse(text);
+        doc.set_value("tags", vec!["a", "b"]);
+        assert_eq!(doc.render(), text);
+    }
+
+    #[test]
+    fn test_nested_edits_rewrite_only_the_node() {
+        let mut doc = Document::parse(concat!(
+            "---\n",
+            "window:\n",
+            "  x: 10   # left edge\n",
+            "  size:\n",
+            "    width: 300\n",
+            "    height: 200\n",
+            "---\n",
+        ));
+        doc.set_value_at(&["window", "size", "width"], 320);
+        doc.set_value_at(&["window", "monitor"], "Built-in");
+        doc.set_value_at(&["layout", "columns"], 2);
+        assert_eq!(
+            doc.render(),
+            concat!(
+                "---\n",
+                "window:\n",
+                "  x: 10   # left edge\n",
+                "  size:\n",
+                "    width: 320\n",
+                "    height: 200\n",
+                "  monitor: Built-in\n",
+                "layout:\n",
+                "  columns: 2\n",
+                "---\n",
+            )
+        );
+        assert_eq!(doc.value_at(&["window", "x"]), Some(Value::Int(10)));
+    }
+
+    #[test]
+    fn test_set_and_remove_take_block_content_along() {
+        let mut doc = Document::parse("---\na:\n  - 1\nb:\n  c: 2\n---\n");
+        doc.set("a", "flat");
+        doc.remove("b");
+        assert_eq!(doc.render(), "---\na: flat\n---\n");
+    }
+
+    #[test]
+    fn test_multiline_strings_round_trip() {
+        let mut doc = Document::new();
+        doc.set("note", "two\nlines");
+        let doc = Document::parse(&doc.render());
+        assert_eq!(doc.get("note"), Some("two\nlines"));
+    }
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Header {
+        id: String,
+        #[serde(default)]
+        tags: Vec<String>,
+        pinned: Option<bool>,
+        window: Option<Window>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        kind: Option<Kind>,
+    }
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Window {
+        x: i32,
+        y: i32,
+    }
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Kind {
+        Todo,
+        Reminder { at: String },
+    }
+
+    #[test]
+    fn test_header_as() {
+        let doc = Document::parse(concat!(
+            "---\n",
+            "id: 123\n",
+            "tags: [a, b]\n",
+            "window:\n",
+            "  x: 10\n",
+            "  y: 20\n",
+            "kind:\n",
+            "  reminder:\n",
+            "    at: tomorrow\n",
+            "foreign: ignored\n",
+            "---\n",
+        ));
+
+        let header: Header = doc.header_as().unwrap();
+        assert_eq!(
+            header,
+            Header {
+                id: "123".into(),
+                tags: vec!["a".into(), "b".into()],
+                pinned: None,
+                window: Some(Window { x: 10, y: 20 }),
+                kind: Some(Kind::Reminder { at: "tomorrow".into() }),
+            }
+        );
+
+        let doc = Document::parse("---\nid: a\nkind: todo\n---\n");
+        let header: Header = doc.header_as().unwrap();
+        assert_eq!(header.kind, Some(Kind::Todo));
+
+        let doc = Document::parse("---\nid: a\npinned: maybe\n---\n");
+        assert!(doc.header_as::<Header>().is_err());
+    }
+
+    #[test]
+    fn test_merge_header_rewrites_only_changes() {
+        let mut doc = Document::parse(concat!(
+            "---\n",
+            "id: note_a # mine\n",
+            "tags: [a, b]\n",
+            "pinned: true\n",
+            "window:\n",
+            "  x: 10\n",
+            "  y: 20  # from the left\n",
+            "  screen: 1\n",
+            "aliases:\n",
+            "  - foreign\n",
+            "---\n",
+            "\n",
+            "Body\n",
+        ));
+
+        let mut header: Header = doc.header_as().unwrap();
+        header.tags.push("c".into());
+        header.pinned = None;
+        header.window = Some(Window { x: 15, y: 20 });
+        doc.merge_header(&header).unwrap();
+
+        assert_eq!(
+            doc.render(),
+            concat!(
+                "---\n",
+                "id: note_a # mine\n",
+                "tags: [a, b, c]\n",
+                "window:\n",
+                "  x: 15\n",
+                "  y: 20  # from the left\n",
+                "  screen: 1\n",
+                "aliases:\n",
+                "  - foreign\n",
+                "---\n",
+                "\n",
+                "Body\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_merge_header_round_trip_keeps_the_bytes() {
+        #[track_caller]
+        fn test(text: &str) {
+            let mut doc = Document::parse(text);
+            let header: BTreeMap<String, String> = doc.header_as().unwrap();
+            doc.merge_header(&header).unwrap();
+            assert_eq!(doc.render(), text);
+        }
+
+        test("---\nid: 123\n---\n\nBody\n");
+        test("---\nid: 007\nversion: 1.50\ndraft: false # for now\n---\n");
+        test("+++\nid = 123\nratio = 0.5\n+++\n");
+    }
+
+    #[test]
+    fn test_merge_header_appends_new_fields() {
+        let mut doc = Document::new();
+        let header = Header {
+            id: "note_a".into(),
+            tags: vec![],
+            pinned: Some(false),
+            window: None,
+            kind: Some(Kind::Todo),
+        };
+        doc.merge_header(&header).unwrap();
+        assert_eq!(
+            doc.render(),
+            "---\nid: note_a\ntags: []\npinned: false\nkind: todo\n---\n"
+        );
+        assert_eq!(doc.header_as::<Header>().unwrap(), header);
+    }
+
+    #[test]
+    fn test_toml_headers() {
+        let text = concat!(
+            "+++\n",
+            "title = \"Hello\" # from Hugo\n",
+            "draft = false\n",
+            "\n",
+            "[params]\n",
+            "color = \"blue\"\n",
+            "+++\n",
+            "\n",
+            "Body\n",
+        );
+        let mut doc = Document::parse(text);
+        assert_eq!(doc.format(), Format::Toml);
+        assert_eq!(doc.render(), text);
+        assert_eq!(doc.get("title"), Some("Hello"));
+        assert_eq!(doc.value("draft"), Some(Value::Bool(false)));
+        assert_eq!(doc.value_at(&["params", "color"]), Some("blue".into()));
+
+        doc.set("id", "note_a");
+        doc.set_value("draft", true);
+        doc.set_value_at(&["params", "size"], 2);
+        assert_eq!(
+            doc.render(),
+            concat!(
+                "+++\n",
+                "title = \"Hello\" # from Hugo\n",
+                "draft = true\n",
+                "\n",
+                "id = \"note_a\"\n",
+                "[params]\n",
+                "color = \"blue\"\n",
+                "size = 2\n",
+                "+++\n",
+                "\n",
+                "Body\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_json_headers() {
+        let text = "{\"title\":\"Hello\",\"tags\":[\"a\"]}\n\nBody\n";
+        let mut doc = Document::parse(text);
+        assert_eq!(doc.format(), Format::Json);
+        assert_eq!(doc.render(), text);
+        assert_eq!(doc.get("title"), Some("Hello"));
+        assert_eq!(doc.value("tags"), Some(vec!["a"].into()));
+
+        doc.set("id", "note_a");
+        assert_eq!(
+            doc.render(),
+            concat!(
+                "{\n",
+                "  \"title\": \"Hello\",\n",
+                "  \"tags\": [\"a\"],\n",
+                "  \"id\": \"note_a\"\n",
+                "}\n",
+                "\n",
+                "Body\n",
+            )
+        );
+        let reparsed = Document::parse(&doc.render());
+        assert_eq!(reparsed.get("id"), Some("note_a"));
+        assert_eq!(reparsed.render(), doc.render());
+    }
+
+    #[test]
+    fn test_json_needs_a_complete_object() {
+        for text in ["{ not json }\n", "{\"a\": 1} trailing\n", "{\"a\": 1"] {
+            let doc = Document::parse(text);
+            assert_eq!(doc.format(), Format::Yaml);
+            assert_eq!(doc.body(), text.trim_end());
+        }
+    }
+
+    #[test]
+    fn test_set_format_converts_between_formats() {
+        let mut doc = Document::parse(concat!(
+            "---\n",
+            "title: Hello # comment\n",
+            "tags:\n",
+            "  - a\n",
+            "window:\n",
+            "  x: 1\n",
+            "gone: ~\n",
+            "---\n",
+            "Body\n",
+        ));
+
+        doc.set_format(Format::Toml);
+        assert_eq!(
+            doc.render(),
+            concat!(
+                "+++\n",
+                "title = \"Hello\"\n",
+                "tags = [\"a\"]\n",
+                "[window]\n",
+                "x = 1\n",
+                "+++\n",
+                "\n",
+                "Body\n",
+            )
+        );
+
+        doc.set_format(Format::Json);
+        assert_eq!(
+            doc.render(),
+            concat!(
+                "{\n",
+                "  \"title\": \"Hello\",\n",
+                "  \"tags\": [\n",
+                "    \"a\"\n",
+                "  ],\n",
+                "  \"window\": {\n",
+                "    \"x\": 1\n",
+                "  }\n",
+                "}\n",
+                "\n",
+                "Body\n",
+            )
+        );
+
+        doc.set_format(Format::Yaml);
+        assert_eq!(
+            doc.render(),
+            "---\ntitle: Hello\ntags:\n  - a\nwindow:\n  x: 1\n---\n\nBody\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_head_stops_after_the_first_body_line() {
+        fn test(head: &str, expected: &str) {
+            let text = format!("{head}\nthe rest\n");
+            let mut reader = io::Cursor::new(text.as_bytes());
+            let doc = Document::parse_head(&mut reader).unwrap();
+            assert_eq!(reader.position() as usize, head.len(), "{head:?}");
+            assert_eq!(doc.body(), expected, "{head:?}");
+            assert_eq!(doc, Document::parse(head), "{head:?}");
+        }
+
+        test("---\nid: a\n---\n\n\n# Title\n", "\n# Title");
+        test("---\n---\n---\nTitle\n", "Title");
+        test("---\r\nid: a\r\n---\r\n\r\nTitle\r\n", "Title");
+        test("+++\nid = \"a\"\n+++\n\nTitle\n", "Title");
+        test("{\n  \"id\": \"a\"\n}\n\nTitle\n", "Title");
+        test("\n\nTitle\n", "\nTitle");
+        test("Title\n", "Title");
+    }
+
+    #[test]
+    fn test_parse_head_of_unterminated_header_reads_everything() {
+        let text = "---\nid: a\n\nBody\n";
+        let doc = Document::parse_head(text.as_bytes()).unwrap();
+        assert_eq!(doc, Document::parse(text));
+        assert_eq!(doc.get("id"), None);
+
+        let doc = Document::parse_head(&b"---\nid: \xff\n---\n"[..]);
+        assert_eq!(doc.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_section_edits_keep_the_rest_of_the_body() {
+        let text = "---\nid: a\n---\n\n# Notes\n\n## Inbox\n\n- one\n\n\
+                    ## Status\nDraft  \n\n```md\n## Inbox\n```\n";
+        let mut doc = Document::parse(text);
+        assert_eq!(
+            doc.sections(),
+            [
+                Section { level: 1, title: "Notes".into() },
+                Section { level: 2, title: "Inbox".into() },
+                Section { level: 2, title: "Status".into() },
+            ]
+        );
+        assert_eq!(doc.section("Inbox"), Some("- one"));
+        assert_eq!(
+            doc.section("## Status"),
+            Some("Draft  \n\n```md\n## Inbox\n```")
+        );
+        assert_eq!(doc.section("# Status"), None);
+
+        assert!(doc.append_to_section("Inbox", "- two\n"));
+        assert!(doc.prepend_to_section("Inbox", "- zero"));
+        assert!(doc.replace_section("Status", "Done"));
+        assert!(doc.insert_section_after("Inbox", "## Later\n\n- three"));
+        assert!(!doc.append_to_section("Missing", "x"));
+        assert_eq!(
+            doc.render(),
+            "---\nid: a\n---\n\n# Notes\n\n## Inbox\n\n- zero\n- one\n- two\
+             \n\n## Later\n\n- three\n\n## Status\nDone\n"
+        );
+    }
+
+    #[test]
+    fn test_section_edits_on_empty_sections() {
+        let mut doc = Document::parse("## A\n## B");
+        assert!(doc.append_to_section("A", "one"));
+        assert!(doc.replace_section("B", "two"));
+        assert!(doc.insert_section_after("B", "## C"));
+        assert_eq!(doc.body(), "## A\none\n## B\ntwo\n\n## C");
+
+        let mut doc = Document::parse("## A\r\n\r\n- a\r\n");
+        assert!(doc.append_to_section("A", "- b\n- c"));
+        assert_eq!(doc.render(), "## A\r\n\r\n- a\r\n- b\r\n- c\r\n");
+    }
 }
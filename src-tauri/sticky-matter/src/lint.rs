@@ -0,0 +1,402 @@
+//! Strict checks of a frontmatter header.
+//!
+//! [`Document::parse`](crate::Document::parse) never fails: whatever it
+//! can't read as a header field passes through as text. These checks
+//! explain those cases, so a user can find out why a field isn't read.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::{block, json, parse_header, split, toml, Entry, Format};
+
+/// A problem found in a file's header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The 1-based line of the file the problem is on.
+    pub line: usize,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// How badly a [`Diagnostic`] affects reading the header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// Something is read, but likely not as its author meant.
+    Warning,
+    /// Something isn't read at all, or the header isn't.
+    Error,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "line {}: {severity}: {}", self.line, self.message)
+    }
+}
+
+/// Check file contents, returning problems in line order.
+pub(crate) fn check(text: &str) -> Vec<Diagnostic> {
+    let mut out = Lint(Vec::new());
+    match split(text) {
+        None => out.unread(text),
+        Some((Format::Yaml, header, _)) => out.yaml(header),
+        Some((Format::Toml, header, _)) => out.toml(header),
+        Some((Format::Json, header, _)) => out.json(header),
+    }
+
+    let mut diagnostics = out.0;
+    diagnostics.sort_by_key(|d| d.line);
+    diagnostics
+}
+
+/// The diagnostics collected so far.
+struct Lint(Vec<Diagnostic>);
+
+impl Lint {
+    fn warn(&mut self, line: usize, message: impl Into<String>) {
+        self.push(line, Severity::Warning, message);
+    }
+
+    fn error(&mut self, line: usize, message: impl Into<String>) {
+        self.push(line, Severity::Error, message);
+    }
+
+    fn push(
+        &mut self,
+        line: usize,
+        severity: Severity,
+        message: impl Into<String>,
+    ) {
+        let message = message.into();
+        self.0.push(Diagnostic { line, severity, message });
+    }
+
+    /// Explain why text that looks like it has a header has none.
+    fn unread(&mut self, text: &str) {
+        let first = text.lines().next().unwrap_or("");
+        let first = first.trim_end();
+        if first == "---" || first == "+++" {
+            self.error(
+                1,
+                "The header is never closed, so the file is all body",
+            );
+            self.misplaced_fences(text.lines().skip(1), 2, first);
+            return;
+        }
+        if first.starts_with('{') {
+            let message = match json::object_len(text) {
+                None => {
+                    "The JSON header doesn't parse, so the file is all body"
+                }
+                Some(_) => {
+                    "The JSON header must end its line, so the file is all body"
+                }
+            };
+            self.error(1, message);
+            return;
+        }
+
+        let Some((line, fence)) =
+            text.lines().enumerate().find(|(_, line)| !line.trim().is_empty())
+        else {
+            return;
+        };
+        // Only worth a word if the header would be read with the fence
+        // in place.
+        if matches!(fence.trim(), "---" | "+++")
+            && split(text.trim_start()).is_some()
+        {
+            self.warn(
+                line + 1,
+                "A fence only opens a header on line 1, at column 0; \
+                 this header is read as body",
+            );
+        }
+    }
+
+    /// Point at the `lines`, starting on file line `first`, that were
+    /// likely meant to close the header with `fence`.
+    fn misplaced_fences<'a>(
+        &mut self,
+        lines: impl Iterator<Item = &'a str>,
+        first: usize,
+        fence: &str,
+    ) {
+        for (i, line) in lines.enumerate() {
+            if line.trim() == fence && line != fence {
+                self.warn(
+                    first + i,
+                    "A fence only closes a header alone on its line at \
+                     column 0, so this one doesn't",
+                );
+            }
+        }
+    }
+
+    fn yaml(&mut self, header: &str) {
+        self.misplaced_fences(header.lines(), 2, "---");
+        for (i, line) in header.lines().enumerate() {
+            let indent = &line[..line.len() - line.trim_start().len()];
+            if indent.contains('\t') {
+                self.error(
+                    i + 2,
+                    "Indented with a tab; YAML only indents with spaces",
+                );
+            }
+        }
+
+        let mut keys = Keys::default();
+        let mut line = 2;
+        for entry in parse_header(header) {
+            match &entry {
+                Entry::Field { key, raw: Some(raw), block, .. } => {
+                    keys.add(self, key, line);
+                    self.quotes(line, &raw[key.len() + 1..], block);
+                }
+                // A stray fence is reported by `misplaced_fences`.
+                Entry::Raw(raw) if raw.trim() == "---" => {}
+                Entry::Raw(raw) if !block::is_filler(raw) => {
+                    let message = match raw.split_once(':') {
+                        Some((key, rest))
+                            if !key.contains(char::is_whitespace)
+                                && !rest.starts_with(' ') =>
+                        {
+                            "No space after the colon, so this isn't a field"
+                        }
+                        _ => "Not a `key: value` line, so it isn't read",
+                    };
+                    self.warn(line, message);
+                }
+                _ => {}
+            }
+            line += lines(&entry);
+        }
+    }
+
+    /// Check that a quoted YAML value closes its quotes, on its line or
+    /// the block lines continuing it.
+    fn quotes(&mut self, line: usize, value: &str, block: &[String]) {
+        let value = value.trim_start();
+        let Some(quote) =
+            value.chars().next().filter(|c| matches!(c, '"' | '\''))
+        else {
+            return;
+        };
+
+        let mut text = value[1..].to_string();
+        for line in block {
+            text.push('\n');
+            text.push_str(line);
+        }
+        if !closes(&text, quote) {
+            self.error(
+                line,
+                format!("Unclosed {quote}; the value is read as written"),
+            );
+        }
+    }
+
+    fn toml(&mut self, header: &str) {
+        self.misplaced_fences(header.lines(), 2, "+++");
+        let mut keys = Keys::default();
+        let mut line = 2;
+        // Arrays of tables pass through verbatim, unchecked.
+        let mut foreign = false;
+        for entry in toml::parse_header(header) {
+            match &entry {
+                Entry::Field { key, raw: Some(raw), block, .. } => {
+                    foreign = false;
+                    keys.add(self, key, line);
+                    if !toml::is_table(raw)
+                        && toml::parse_field(raw, block).is_none()
+                    {
+                        self.warn(
+                            line,
+                            "Not a valid TOML value, so it is read as text",
+                        );
+                    }
+                }
+                Entry::Raw(raw) if raw.trim_start().starts_with("[[") => {
+                    foreign = true;
+                }
+                Entry::Raw(raw) if raw.trim() == "+++" => {}
+                Entry::Raw(raw) if !foreign && !block::is_filler(raw) => {
+                    self.warn(
+                        line,
+                        "Not a `key = value` line, so it isn't read",
+                    );
+                }
+                _ => {}
+            }
+            line += lines(&entry);
+        }
+    }
+
+    fn json(&mut self, header: &str) {
+        let header = header.replace("\r\n", "\n");
+        let Some(entries) = json::parse_header(&header) else {
+            self.error(
+                1,
+                "The JSON header doesn't parse, so the file is all body",
+            );
+            return;
+        };
+
+        let mut keys = Keys::default();
+        let mut seen: HashMap<&str, usize> = HashMap::new();
+        for entry in &entries {
+            let Entry::Field { key, .. } = entry else { continue };
+            // Each occurrence of a key is found after the previous one.
+            let from = seen.get(key.as_str()).copied().unwrap_or(0);
+            let at = member_at(&header, key, from);
+            seen.insert(key.as_str(), at + 1);
+            keys.add(self, key, header[..at].matches('\n').count() + 1);
+        }
+    }
+}
+
+/// The first line of each top-level key, reporting repeats.
+#[derive(Default)]
+struct Keys(HashMap<String, usize>);
+
+impl Keys {
+    fn add(&mut self, lint: &mut Lint, key: &str, line: usize) {
+        match self.0.get(key) {
+            Some(first) => lint.warn(
+                line,
+                format!("Duplicate key `{key}`; only line {first} is read"),
+            ),
+            None => {
+                self.0.insert(key.to_string(), line);
+            }
+        }
+    }
+}
+
+/// How many lines an entry spans.
+fn lines(entry: &Entry) -> usize {
+    match entry {
+        Entry::Field { block, .. } => 1 + block.len(),
+        Entry::Raw(_) => 1,
+    }
+}
+
+/// Whether the text after an opening `quote` closes it: `"` strings
+/// escape with backslashes, `'` ones by doubling the quote.
+fn closes(text: &str, quote: char) -> bool {
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if quote == '"' => {
+                chars.next();
+            }
+            '\'' if quote == '\'' && chars.peek() == Some(&'\'') => {
+                chars.next();
+            }
+            c if c == quote => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+/// The byte offset of the JSON member `key` at or after `from`.
+fn member_at(header: &str, key: &str, from: usize) -> usize {
+    let name = format!("\"{key}\"");
+    let mut pos = from;
+    while let Some(i) = header[pos..].find(&name) {
+        let at = pos + i;
+        let rest = header[at + name.len()..].trim_start();
+        if rest.starts_with(':') {
+            return at;
+        }
+        pos = at + name.len();
+    }
+    from
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(text: &str) -> Vec<(usize, Severity)> {
+        check(text).into_iter().map(|d| (d.line, d.severity)).collect()
+    }
+
+    #[test]
+    fn test_clean_headers_have_no_diagnostics() {
+        assert_eq!(
+            messages("---\nid: a\n# comment\ntags:\n  - x\n---\nBody\n"),
+            []
+        );
+        assert_eq!(messages("+++\nid = \"a\"\n[extra]\nx = 1\n+++\n"), []);
+        assert_eq!(messages("{\"id\": \"a\"}\nBody\n"), []);
+        assert_eq!(messages("Just a body\n"), []);
+    }
+
+    #[test]
+    fn test_yaml_problems() {
+        use Severity::*;
+        let text = "---\nid: a\ntags:\n\t- x\ntitle: \"open\nid: b\n\
+                    key:value\n= x\n---\n";
+        assert_eq!(
+            messages(text),
+            [(4, Error), (5, Error), (6, Warning), (7, Warning), (8, Warning)]
+        );
+        let diagnostics = check(text);
+        assert_eq!(
+            diagnostics[2].to_string(),
+            "line 6: warning: Duplicate key `id`; only line 2 is read"
+        );
+    }
+
+    #[test]
+    fn test_quotes_may_close_on_later_lines() {
+        assert_eq!(messages("---\na: \"one\n  two\"\nb: 'it''s'\n---\n"), []);
+        assert_eq!(messages("---\na: 'it''s\n---\n"), [(2, Severity::Error)]);
+    }
+
+    #[test]
+    fn test_misplaced_and_unclosed_fences() {
+        assert_eq!(messages("\n---\nid: a\n---\n"), [(2, Severity::Warning)]);
+        assert_eq!(messages(" ---\nid: a\n---\n"), [(1, Severity::Warning)]);
+        assert_eq!(messages("---\nid: a\n\nBody\n"), [(1, Severity::Error)]);
+        assert_eq!(
+            messages("---\nid: a\n ---\nbody\n"),
+            [(1, Severity::Error), (3, Severity::Warning)]
+        );
+        assert_eq!(
+            messages("---\nid: a\n  ---\n---\n"),
+            [(3, Severity::Warning)]
+        );
+        assert_eq!(
+            messages("---\nid: a\ntitle: b\n ---\n---\n"),
+            [(4, Severity::Warning)]
+        );
+        assert_eq!(
+            messages("+++\nid = 1\n +++\n+++\n"),
+            [(3, Severity::Warning)]
+        );
+        assert_eq!(messages("{\"id\": }\n"), [(1, Severity::Error)]);
+    }
+
+    #[test]
+    fn test_toml_and_json_problems() {
+        use Severity::*;
+        assert_eq!(
+            messages("+++\nid = \"a\"\nid = \"b\"\nx = [1\n+++\n"),
+            [(3, Warning), (4, Warning)]
+        );
+        assert_eq!(
+            messages("+++\nplain line\n[[items]]\nname = \"x\"\n+++\n"),
+            [(2, Warning)]
+        );
+        assert_eq!(
+            messages("{\n  \"id\": \"a\",\n  \"id\": \"b\"\n}\n"),
+            [(3, Warning)]
+        );
+    }
+}
@@ -0,0 +1,205 @@
+//! Markdown sections: the parts of a body between ATX headings.
+//!
+//! A section runs from its heading to the next heading of the same or
+//! a higher level, so it contains its subsections. Lines inside fenced
+//! code blocks are never headings. Setext headings (underlined with
+//! `===` or `---`) aren't recognized; they are rare in notes, and a
+//! `---` line is far more often a thematic break.
+
+use std::ops::Range;
+
+/// A heading-delimited part of a markdown body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    /// The heading level: 1 for `#` through 6 for `######`.
+    pub level: u8,
+    /// The heading text, without the `#` markers around it.
+    pub title: String,
+}
+
+/// A heading found in a body, with the byte offsets of its section.
+#[derive(Debug)]
+pub(crate) struct Heading {
+    pub(crate) section: Section,
+    /// Where the line after the heading starts, or the body's end.
+    pub(crate) line_end: usize,
+    /// Where the next heading of the same or a higher level starts,
+    /// or the body's end.
+    pub(crate) end: usize,
+}
+
+impl Heading {
+    /// The content under the heading, without the blank lines around
+    /// it. Empty content sits right after the heading line.
+    pub(crate) fn content(&self, body: &str) -> Range<usize> {
+        let mut start = self.line_end;
+        let mut end = self.line_end;
+        let mut pos = self.line_end;
+        for line in body[self.line_end..self.end].split_inclusive('\n') {
+            if !line.trim().is_empty() {
+                if end == self.line_end {
+                    start = pos;
+                }
+                end = pos + line.trim_end_matches(['\r', '\n']).len();
+            }
+            pos += line.len();
+        }
+        start..end
+    }
+
+    /// Whether `query` names this heading: its title alone matches
+    /// headings of any level, a full `## Title` line only that level.
+    pub(crate) fn matches(&self, query: &str) -> bool {
+        match parse_heading(query) {
+            Some(query) => query == self.section,
+            None => query.trim() == self.section.title,
+        }
+    }
+}
+
+/// The headings of a body, in order.
+pub(crate) fn headings(body: &str) -> Vec<Heading> {
+    let mut headings: Vec<Heading> = Vec::new();
+    let mut fence: Option<(char, usize)> = None;
+    let mut pos = 0;
+
+    for line in body.split_inclusive('\n') {
+        let start = pos;
+        pos += line.len();
+        let line = line.trim_end_matches(['\r', '\n']);
+
+        if let Some((marker, len)) = fence {
+            if closes_fence(line, marker, len) {
+                fence = None;
+            }
+            continue;
+        }
+        if let Some(opened) = opens_fence(line) {
+            fence = Some(opened);
+            continue;
+        }
+
+        if let Some(section) = parse_heading(line) {
+            for open in &mut headings {
+                if open.end == body.len() && open.section.level >= section.level
+                {
+                    open.end = start;
+                }
+            }
+            headings.push(Heading { section, line_end: pos, end: body.len() });
+        }
+    }
+    headings
+}
+
+/// Parse an ATX heading line: up to three spaces, one to six `#`s,
+/// then whitespace or the end of the line.
+fn parse_heading(line: &str) -> Option<Section> {
+    let rest = strip_indent(line)?;
+    let level = rest.len() - rest.trim_start_matches('#').len();
+    let rest = &rest[level..];
+    if !(1..=6).contains(&level)
+        || !(rest.is_empty() || rest.starts_with([' ', '\t']))
+    {
+        return None;
+    }
+
+    // An optional closing sequence of `#`s needs whitespace before it.
+    let title = rest.trim();
+    let unclosed = title.trim_end_matches('#');
+    let title = if unclosed.is_empty() || unclosed.ends_with([' ', '\t']) {
+        unclosed.trim_end()
+    } else {
+        title
+    };
+    Some(Section { level: level as u8, title: title.to_string() })
+}
+
+/// The marker and length of the code fence a line opens.
+fn opens_fence(line: &str) -> Option<(char, usize)> {
+    let rest = strip_indent(line)?;
+    let marker = rest.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+    let len = rest.len() - rest.trim_start_matches(marker).len();
+    // Backtick fences can't have backticks in their info string.
+    let info = &rest[len..];
+    (len >= 3 && !(marker == '`' && info.contains('`')))
+        .then_some((marker, len))
+}
+
+/// Whether a line closes a fence opened with `len` `marker`s.
+fn closes_fence(line: &str, marker: char, len: usize) -> bool {
+    let Some(rest) = strip_indent(line) else {
+        return false;
+    };
+    let run = rest.len() - rest.trim_start_matches(marker).len();
+    run >= len && rest[run..].trim().is_empty()
+}
+
+/// A line without its indentation, unless it is indented four spaces
+/// or more (an indented code block).
+fn strip_indent(line: &str) -> Option<&str> {
+    let rest = line.trim_start_matches(' ');
+    (line.len() - rest.len() < 4).then_some(rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_heading() {
+        fn test(line: &str, expected: Option<(u8, &str)>) {
+            let expected = expected.map(|(level, title)| Section {
+                level,
+                title: title.to_string(),
+            });
+            assert_eq!(parse_heading(line), expected, "{line:?}");
+        }
+
+        test("# Title", Some((1, "Title")));
+        test("###   Spaced  ", Some((3, "Spaced")));
+        test("   ## Indented", Some((2, "Indented")));
+        test("## Closed ##", Some((2, "Closed")));
+        test("## C#", Some((2, "C#")));
+        test("##", Some((2, "")));
+        test("#hashtag", None);
+        test("####### Seven", None);
+        test("    # Code", None);
+    }
+
+    #[test]
+    fn test_sections_nest_and_skip_fences() {
+        let body = "# A\n\n## B\n```sh\n# not a heading\n```\n## C\n# D\n";
+        let found: Vec<(String, &str)> = headings(body)
+            .iter()
+            .map(|h| (h.section.title.clone(), &body[h.line_end..h.end]))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("A".into(), "\n## B\n```sh\n# not a heading\n```\n## C\n"),
+                ("B".into(), "```sh\n# not a heading\n```\n"),
+                ("C".into(), ""),
+                ("D".into(), ""),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_content_skips_surrounding_blank_lines() {
+        let body = "## A\n\n- one\n\n- two\n\n\n## B\n## C";
+        let headings = headings(body);
+        assert_eq!(&body[headings[0].content(body)], "- one\n\n- two");
+        assert_eq!(headings[1].content(body), 26..26);
+        assert_eq!(headings[2].content(body), body.len()..body.len());
+    }
+
+    #[test]
+    fn test_matches_title_or_full_heading() {
+        let heading = &headings("## Inbox")[0];
+        assert!(heading.matches("Inbox"));
+        assert!(heading.matches("## Inbox"));
+        assert!(!heading.matches("# Inbox"));
+        assert!(!heading.matches("inbox"));
+    }
+}
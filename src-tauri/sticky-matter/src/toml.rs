@@ -0,0 +1,627 @@
+//! TOML frontmatter, between `+++` fences.
+//!
+//! TOML headers are kept as lines, like YAML ones: each top-level
+//! `key = value` line is a field, and so is each `[table]`, its lines
+//! forming the field's block. Arrays of tables and lines the parser
+//! doesn't follow pass through verbatim.
+
+use crate::{view, Entry, Value};
+
+/// Parse header lines into entries.
+pub(crate) fn parse_header(header: &str) -> Vec<Entry> {
+    let mut entries: Vec<Entry> = Vec::new();
+    // Lines go to the last field's block inside a table, and while a
+    // value spans several lines.
+    let mut table = false;
+    let mut open = false;
+    // Inside an array of tables, which passes through verbatim.
+    let mut foreign = false;
+
+    for line in header.lines() {
+        if !open {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("[[") {
+                (table, foreign) = (false, true);
+                entries.push(Entry::Raw(line.to_string()));
+                continue;
+            }
+            if let Some(name) = table_name(trimmed) {
+                (table, foreign) = (true, false);
+                entries.push(Entry::Field {
+                    key: name,
+                    value: String::new(),
+                    raw: Some(line.to_string()),
+                    block: vec![],
+                });
+                continue;
+            }
+        }
+
+        if foreign {
+            entries.push(Entry::Raw(line.to_string()));
+        } else if table || open {
+            let Some(Entry::Field { raw: Some(raw), block, value, .. }) =
+                entries.last_mut()
+            else {
+                unreachable!("Tables and open values are fields");
+            };
+            block.push(line.to_string());
+            if open {
+                let node = parse_field(raw, block);
+                open = opens_multiline(raw) && node.is_none();
+                if let Some(node) = node {
+                    *value = view(&node, &render(&node));
+                }
+            }
+        } else if let Some((path, rest)) = split_key(line) {
+            let node = parse_value(rest);
+            open = node.is_none() && opens_multiline(line);
+            let node = node.unwrap_or_else(|| plain(rest));
+            entries.push(Entry::Field {
+                key: path.join("."),
+                value: view(&node, &render(&node)),
+                raw: Some(line.to_string()),
+                block: vec![],
+            });
+        } else {
+            entries.push(Entry::Raw(line.to_string()));
+        }
+    }
+    entries
+}
+
+/// The typed value of a field: its key line and block lines.
+pub(crate) fn field_value(raw: &str, block: &[String]) -> Value {
+    if is_table(raw) {
+        return parse_table(block);
+    }
+    parse_field(raw, block).unwrap_or_else(|| {
+        split_key(raw).map_or(Value::Null, |(_, rest)| plain(rest))
+    })
+}
+
+/// A field holding `value`: a `[key]` table when `table` and the value
+/// is a mapping, a `key = value` line otherwise.
+pub(crate) fn render_field(key: &str, value: &Value, table: bool) -> Entry {
+    let (raw, block, text) = match value {
+        Value::Map(entries) if table => {
+            let lines = entries
+                .iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| format!("{} = {}", render_key(k), render(v)));
+            let raw = format!("[{}]", render_key(key));
+            (raw, lines.collect(), String::new())
+        }
+        _ => {
+            let text = render(value);
+            (format!("{} = {text}", render_key(key)), vec![], text)
+        }
+    };
+
+    Entry::Field {
+        key: key.to_string(),
+        value: view(value, &text),
+        raw: Some(raw),
+        block,
+    }
+}
+
+/// Whether a field's key line is a `[table]` header.
+pub(crate) fn is_table(raw: &str) -> bool {
+    table_name(raw.trim_start()).is_some()
+}
+
+/// Render a value inline. TOML has no null, so nulls in collections
+/// are left out; a bare null renders as an empty string.
+pub(crate) fn render(value: &Value) -> String {
+    match value {
+        Value::Null => "\"\"".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Int(n) => n.to_string(),
+        Value::Float(n) if n.is_nan() => "nan".to_string(),
+        Value::Float(n) if n.is_infinite() => {
+            if *n > 0.0 { "inf" } else { "-inf" }.to_string()
+        }
+        Value::Float(n) => format!("{n:?}"),
+        Value::String(s) => quote(s),
+        Value::List(items) => {
+            let items: Vec<String> = items
+                .iter()
+                .filter(|item| !item.is_null())
+                .map(render)
+                .collect();
+            format!("[{}]", items.join(", "))
+        }
+        Value::Map(entries) if entries.is_empty() => "{}".to_string(),
+        Value::Map(entries) => {
+            let entries: Vec<String> = entries
+                .iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| format!("{} = {}", render_key(k), render(v)))
+                .collect();
+            format!("{{ {} }}", entries.join(", "))
+        }
+    }
+}
+
+/// Render a key, quoted unless it is bare.
+pub(crate) fn render_key(key: &str) -> String {
+    let bare = !key.is_empty()
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || "_-".contains(c));
+    if bare {
+        key.to_string()
+    } else {
+        quote(key)
+    }
+}
+
+/// Render a basic string.
+pub(crate) fn quote(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => {
+                quoted.push_str(&format!("\\u{:04X}", u32::from(c)));
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// The value of a `key = value` line and the lines continuing it, if
+/// it parses.
+pub(crate) fn parse_field(raw: &str, block: &[String]) -> Option<Value> {
+    let (_, rest) = split_key(raw)?;
+    let mut text = rest.to_string();
+    for line in block {
+        text.push('\n');
+        text.push_str(line);
+    }
+    parse_value(&text)
+}
+
+/// Whether a `key = value` line starts a value that may span lines: an
+/// array or a multi-line string.
+fn opens_multiline(raw: &str) -> bool {
+    split_key(raw).is_some_and(|(_, rest)| {
+        let rest = rest.trim_start();
+        rest.starts_with('[')
+            || rest.starts_with("\"\"\"")
+            || rest.starts_with("'''")
+    })
+}
+
+/// Text that doesn't parse, kept as a string without its comment.
+fn plain(rest: &str) -> Value {
+    let text = rest.split(" #").next().unwrap_or(rest);
+    Value::String(text.trim().to_string())
+}
+
+/// Parse a table's lines into a mapping; dotted keys nest.
+fn parse_table(lines: &[String]) -> Value {
+    let mut table = Value::Map(vec![]);
+    let mut pending: Option<(Vec<String>, String)> = None;
+
+    for line in lines {
+        if let Some((path, text)) = &mut pending {
+            text.push('\n');
+            text.push_str(line);
+            if let Some(value) = parse_value(text) {
+                let path: Vec<&str> = path.iter().map(String::as_str).collect();
+                table.set_path(&path, value);
+                pending = None;
+            }
+            continue;
+        }
+
+        let Some((path, rest)) = split_key(line) else {
+            continue;
+        };
+        match parse_value(rest) {
+            Some(value) => {
+                let path: Vec<&str> = path.iter().map(String::as_str).collect();
+                table.set_path(&path, value);
+            }
+            None if opens_multiline(line) => {
+                pending = Some((path, rest.to_string()));
+            }
+            None => {
+                let path: Vec<&str> = path.iter().map(String::as_str).collect();
+                table.set_path(&path, plain(rest));
+            }
+        }
+    }
+    table
+}
+
+/// Split a `key = value` line into its key path and the text after
+/// the `=`.
+fn split_key(line: &str) -> Option<(Vec<String>, &str)> {
+    let mut parser = Parser { text: line, pos: 0 };
+    parser.skip_spaces();
+    let path = parser.key()?;
+    parser.skip_spaces();
+    (parser.bump()? == '=').then_some(())?;
+    Some((path, &line[parser.pos..]))
+}
+
+/// The name of a `[table]` header line, dotted parts joined.
+fn table_name(line: &str) -> Option<String> {
+    let rest = line.strip_prefix('[')?;
+    if rest.starts_with('[') {
+        return None;
+    }
+
+    let mut parser = Parser { text: rest, pos: 0 };
+    parser.skip_spaces();
+    let path = parser.key()?;
+    parser.skip_spaces();
+    (parser.bump()? == ']').then_some(())?;
+    let tail = rest[parser.pos..].trim();
+    (tail.is_empty() || tail.starts_with('#')).then(|| path.join("."))
+}
+
+/// Parse a value and whatever comments follow it.
+fn parse_value(text: &str) -> Option<Value> {
+    let mut parser = Parser { text, pos: 0 };
+    let value = parser.value()?;
+    parser.skip_ws();
+    (parser.pos == text.len()).then_some(value)
+}
+
+/// A recursive descent parser over TOML values and keys.
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn starts_with(&self, s: &str) -> bool {
+        self.text[self.pos..].starts_with(s)
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.peek().is_some_and(|c| c == ' ' || c == '\t') {
+            self.bump();
+        }
+    }
+
+    /// Skip whitespace, line breaks and comments.
+    fn skip_ws(&mut self) {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_whitespace() => {
+                    self.bump();
+                }
+                Some('#') => {
+                    while self.peek().is_some_and(|c| c != '\n') {
+                        self.bump();
+                    }
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_ws();
+        let hit = self.peek() == Some(c);
+        if hit {
+            self.bump();
+        }
+        hit
+    }
+
+    /// A dotted key, as its parts.
+    fn key(&mut self) -> Option<Vec<String>> {
+        let mut path = Vec::new();
+        loop {
+            let part = match self.peek()? {
+                '"' => self.basic_string()?,
+                '\'' => self.literal_string()?,
+                _ => {
+                    let start = self.pos;
+                    while self.peek().is_some_and(|c| {
+                        c.is_ascii_alphanumeric() || c == '_' || c == '-'
+                    }) {
+                        self.bump();
+                    }
+                    if self.pos == start {
+                        return None;
+                    }
+                    self.text[start..self.pos].to_string()
+                }
+            };
+            path.push(part);
+
+            self.skip_spaces();
+            if self.peek() != Some('.') {
+                return Some(path);
+            }
+            self.bump();
+            self.skip_spaces();
+        }
+    }
+
+    fn value(&mut self) -> Option<Value> {
+        self.skip_ws();
+        if self.starts_with("\"\"\"") {
+            return self.multiline_string("\"\"\"", true).map(Value::String);
+        }
+        if self.starts_with("'''") {
+            return self.multiline_string("'''", false).map(Value::String);
+        }
+
+        match self.peek()? {
+            '"' => self.basic_string().map(Value::String),
+            '\'' => self.literal_string().map(Value::String),
+            '[' => {
+                self.bump();
+                let mut items = Vec::new();
+                loop {
+                    if self.eat(']') {
+                        return Some(Value::List(items));
+                    }
+                    items.push(self.value()?);
+                    if !self.eat(',') {
+                        return self.eat(']').then_some(Value::List(items));
+                    }
+                }
+            }
+            '{' => {
+                self.bump();
+                let mut table = Value::Map(vec![]);
+                loop {
+                    if self.eat('}') {
+                        return Some(table);
+                    }
+                    self.skip_ws();
+                    let path = self.key()?;
+                    if !self.eat('=') {
+                        return None;
+                    }
+                    let path: Vec<&str> =
+                        path.iter().map(String::as_str).collect();
+                    table.set_path(&path, self.value()?);
+                    if !self.eat(',') {
+                        return self.eat('}').then_some(table);
+                    }
+                }
+            }
+            _ => self.bare(),
+        }
+    }
+
+    fn basic_string(&mut self) -> Option<String> {
+        self.bump();
+        let mut out = String::new();
+        loop {
+            match self.bump()? {
+                '"' => return Some(out),
+                '\\' => out.push(self.escape()?),
+                '\n' => return None,
+                c => out.push(c),
+            }
+        }
+    }
+
+    fn literal_string(&mut self) -> Option<String> {
+        self.bump();
+        let start = self.pos;
+        loop {
+            match self.bump()? {
+                '\'' => {
+                    return Some(self.text[start..self.pos - 1].to_string())
+                }
+                '\n' => return None,
+                _ => {}
+            }
+        }
+    }
+
+    /// A `"""` or `'''` string. A line break right after the opening
+    /// delimiter is trimmed, and in basic strings a `\` at the end of
+    /// a line trims the break and the whitespace after it.
+    fn multiline_string(
+        &mut self,
+        delimiter: &str,
+        basic: bool,
+    ) -> Option<String> {
+        self.pos += delimiter.len();
+        if self.starts_with("\r\n") {
+            self.pos += 2;
+        } else if self.starts_with("\n") {
+            self.pos += 1;
+        }
+
+        let mut out = String::new();
+        loop {
+            if self.starts_with(delimiter) {
+                self.pos += delimiter.len();
+                return Some(out);
+            }
+            match self.bump()? {
+                '\\' if basic => {
+                    if self.peek().is_some_and(char::is_whitespace) {
+                        while self.peek().is_some_and(char::is_whitespace) {
+                            self.bump();
+                        }
+                    } else {
+                        out.push(self.escape()?);
+                    }
+                }
+                c => out.push(c),
+            }
+        }
+    }
+
+    /// The character of an escape, after its backslash.
+    fn escape(&mut self) -> Option<char> {
+        let digits = match self.bump()? {
+            'n' => return Some('\n'),
+            'r' => return Some('\r'),
+            't' => return Some('\t'),
+            'b' => return Some('\u{8}'),
+            'f' => return Some('\u{c}'),
+            'e' => return Some('\u{1b}'),
+            c @ ('"' | '\\') => return Some(c),
+            'u' => 4,
+            'U' => 8,
+            _ => return None,
+        };
+        let hex = self.text.get(self.pos..self.pos + digits)?;
+        let c = char::from_u32(u32::from_str_radix(hex, 16).ok()?)?;
+        self.pos += digits;
+        Some(c)
+    }
+
+    /// A boolean, number, or date and time. Dates and times have no
+    /// [`Value`] of their own and read as strings.
+    fn bare(&mut self) -> Option<Value> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| {
+            !c.is_whitespace() && !matches!(c, ',' | ']' | '}' | '#')
+        }) {
+            self.bump();
+        }
+
+        let token = &self.text[start..self.pos];
+        let digits = token.replace('_', "");
+        let unsigned = digits.trim_start_matches(['+', '-']);
+        let radix = match unsigned.get(..2) {
+            Some("0x") => Some(16),
+            Some("0o") => Some(8),
+            Some("0b") => Some(2),
+            _ => None,
+        };
+
+        let value = match token {
+            "" => return None,
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            "inf" | "+inf" => Value::Float(f64::INFINITY),
+            "-inf" => Value::Float(f64::NEG_INFINITY),
+            "nan" | "+nan" | "-nan" => Value::Float(f64::NAN),
+            _ if radix.is_some() => {
+                let radix = radix.expect("Checked above");
+                Value::Int(i64::from_str_radix(&unsigned[2..], radix).ok()?)
+            }
+            _ => match digits.parse::<i64>() {
+                Ok(n) => Value::Int(n),
+                Err(_) => match digits.parse::<f64>() {
+                    Ok(n)
+                        if unsigned
+                            .starts_with(|c: char| c.is_ascii_digit()) =>
+                    {
+                        Value::Float(n)
+                    }
+                    _ => Value::String(token.to_string()),
+                },
+            },
+        };
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_values() {
+        assert_eq!(parse_value("1_000"), Some(Value::Int(1000)));
+        assert_eq!(parse_value("0xff"), Some(Value::Int(255)));
+        assert_eq!(parse_value("-1.5e2"), Some(Value::Float(-150.0)));
+        assert_eq!(
+            parse_value("1979-05-27T07:32:00Z # a date"),
+            Some("1979-05-27T07:32:00Z".into())
+        );
+        assert_eq!(
+            parse_value(r#""tab\there" "#),
+            Some(Value::from("tab\there"))
+        );
+        assert_eq!(parse_value(r"'C:\path'"), Some("C:\\path".into()));
+        assert_eq!(
+            parse_value("[\n  \"a\", # first\n  'b',\n]"),
+            Some(vec!["a", "b"].into())
+        );
+        assert_eq!(
+            parse_value("{ x = 1, size.w = 2 }"),
+            Some(Value::Map(vec![
+                ("x".into(), 1.into()),
+                ("size".into(), Value::Map(vec![("w".into(), 2.into())])),
+            ]))
+        );
+        assert_eq!(
+            parse_value("\"\"\"\nline\\\n   joined\"\"\""),
+            Some("linejoined".into())
+        );
+        assert_eq!(parse_value("[1, 2"), None);
+    }
+
+    #[test]
+    fn test_parse_header() {
+        let entries = parse_header(concat!(
+            "title = \"Hello\"\n",
+            "tags = [\n",
+            "  \"a\",\n",
+            "]\n",
+            "# comment\n",
+            "[params]\n",
+            "x = 1\n",
+            "[[menu]]\n",
+            "name = \"main\"\n",
+        ));
+
+        let keys: Vec<&str> = entries
+            .iter()
+            .map(|entry| match entry {
+                Entry::Field { key, .. } => key.as_str(),
+                Entry::Raw(_) => "",
+            })
+            .collect();
+        assert_eq!(keys, ["title", "tags", "", "params", "", ""]);
+
+        let Entry::Field { raw: Some(raw), block, .. } = &entries[3] else {
+            panic!("Tables are fields");
+        };
+        assert_eq!(
+            field_value(raw, block),
+            Value::Map(vec![("x".into(), 1.into())])
+        );
+    }
+
+    #[test]
+    fn test_render_round_trips() {
+        let value = Value::Map(vec![
+            ("a b".into(), vec![Value::from("x\"y"), Value::Null].into()),
+            ("n".into(), Value::Float(2.0)),
+        ]);
+        assert_eq!(render(&value), r#"{ "a b" = ["x\"y"], n = 2.0 }"#);
+        assert_eq!(
+            parse_value(&render(&value)),
+            Some(Value::Map(vec![
+                ("a b".into(), vec!["x\"y"].into()),
+                ("n".into(), Value::Float(2.0)),
+            ]))
+        );
+    }
+}
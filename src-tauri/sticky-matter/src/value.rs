@@ -0,0 +1,512 @@
+//! Typed YAML values: scalars and flow collections.
+
+use std::borrow::Cow;
+
+use crate::{needs_quotes, parse_value};
+
+/// A typed frontmatter value.
+///
+/// Covers the YAML that shows up in frontmatter: scalars resolved by
+/// the YAML 1.2 core schema, sequences, and mappings. Anchors, tags
+/// and multi-document streams are out of scope.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    List(Vec<Value>),
+    /// A mapping, in file order.
+    Map(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::Int(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// The value as a float; integers convert.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Int(n) => Some(*n as f64),
+            Self::Float(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[Value]> {
+        match self {
+            Self::List(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_map(&self) -> Option<&[(String, Value)]> {
+        match self {
+            Self::Map(entries) => Some(entries),
+            _ => None,
+        }
+    }
+
+    /// The value under `key`, if this is a mapping that has it.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.as_map()?.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    /// The value at a path of mapping keys.
+    pub fn get_path(&self, path: &[&str]) -> Option<&Value> {
+        path.iter().try_fold(self, |value, key| value.get(key))
+    }
+
+    /// Set the value at a path of mapping keys, turning anything in
+    /// the way into a mapping.
+    pub fn set_path(&mut self, path: &[&str], value: Value) {
+        let Some((key, rest)) = path.split_first() else {
+            *self = value;
+            return;
+        };
+
+        if !matches!(self, Self::Map(_)) {
+            *self = Self::Map(vec![]);
+        }
+        let Self::Map(entries) = self else { unreachable!() };
+
+        let index = match entries.iter().position(|(k, _)| k == key) {
+            Some(index) => index,
+            None => {
+                entries.push((key.to_string(), Self::Null));
+                entries.len() - 1
+            }
+        };
+        entries[index].1.set_path(rest, value);
+    }
+
+    /// Whether the value renders inline after its key: anything but
+    /// a non-empty collection.
+    pub(crate) fn is_inline(&self) -> bool {
+        match self {
+            Self::List(items) => items.is_empty(),
+            Self::Map(entries) => entries.is_empty(),
+            _ => true,
+        }
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Self::String(s.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Self::String(s)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Self::Bool(b)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(n: i64) -> Self {
+        Self::Int(n)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(n: i32) -> Self {
+        Self::Int(n.into())
+    }
+}
+
+impl From<f64> for Value {
+    fn from(n: f64) -> Self {
+        Self::Float(n)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(items: Vec<T>) -> Self {
+        Self::List(items.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Null, Into::into)
+    }
+}
+
+/// Resolve a scalar: quoted text is a string; plain text is null,
+/// a boolean or a number when it spells one, and a string otherwise.
+pub(crate) fn scalar(text: &str) -> Value {
+    let text = text.trim();
+    if text.starts_with(['"', '\'']) {
+        return Value::String(parse_value(text));
+    }
+
+    match text {
+        "" | "~" | "null" | "Null" | "NULL" => return Value::Null,
+        "true" | "True" | "TRUE" => return Value::Bool(true),
+        "false" | "False" | "FALSE" => return Value::Bool(false),
+        ".inf" | ".Inf" | ".INF" | "+.inf" | "+.Inf" | "+.INF" => {
+            return Value::Float(f64::INFINITY)
+        }
+        "-.inf" | "-.Inf" | "-.INF" => return Value::Float(f64::NEG_INFINITY),
+        ".nan" | ".NaN" | ".NAN" => return Value::Float(f64::NAN),
+        _ => {}
+    }
+
+    if is_int(text) {
+        if let Ok(n) = text.parse() {
+            return Value::Int(n);
+        }
+    }
+    if is_float(text) {
+        if let Ok(n) = text.parse() {
+            return Value::Float(n);
+        }
+    }
+    Value::String(text.to_string())
+}
+
+/// `[-+]?[0-9]+`
+fn is_int(text: &str) -> bool {
+    let digits = text.strip_prefix(['-', '+']).unwrap_or(text);
+    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// `[-+]?(\.[0-9]+|[0-9]+(\.[0-9]*)?)([eE][-+]?[0-9]+)?`
+fn is_float(text: &str) -> bool {
+    let text = text.strip_prefix(['-', '+']).unwrap_or(text);
+    let (mantissa, exponent) = match text.split_once(['e', 'E']) {
+        Some((m, e)) => (m, Some(e)),
+        None => (text, None),
+    };
+
+    let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    let mantissa_ok = (!whole.is_empty() || !fraction.is_empty())
+        && digits(whole)
+        && digits(fraction);
+    let exponent_ok = exponent.is_none_or(|e| {
+        let e = e.strip_prefix(['-', '+']).unwrap_or(e);
+        !e.is_empty() && digits(e)
+    });
+    mantissa_ok && exponent_ok
+}
+
+/// Cut a trailing ` # comment` off a line, minding quotes.
+pub(crate) fn strip_comment(text: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+    let mut prev = ' ';
+    for (i, c) in text.char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if q == '"' && c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+        } else if c == '#' && prev.is_whitespace() {
+            return &text[..i];
+        } else if matches!(c, '"' | '\'') && !prev.is_alphanumeric() {
+            quote = Some(c);
+        }
+        prev = c;
+    }
+    text
+}
+
+/// Parse a flow collection like `[a, "b, c", {x: 1}]`. Malformed
+/// text is a plain string, as YAML tools tend to show it anyway.
+pub(crate) fn flow(text: &str) -> Value {
+    let mut parser = FlowParser { text, pos: 0 };
+    match parser.value() {
+        Some(value) if parser.at_end() => value,
+        _ => Value::String(text.trim().to_string()),
+    }
+}
+
+/// A recursive descent parser over flow-style YAML.
+struct FlowParser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl FlowParser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn skip_ws(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.skip_ws();
+        self.pos == self.text.len()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_ws();
+        let hit = self.peek() == Some(c);
+        if hit {
+            self.bump();
+        }
+        hit
+    }
+
+    fn value(&mut self) -> Option<Value> {
+        self.skip_ws();
+        match self.peek()? {
+            '[' => self.list(),
+            '{' => self.map(),
+            _ => Some(scalar(&self.scalar_text(false)?)),
+        }
+    }
+
+    fn list(&mut self) -> Option<Value> {
+        self.bump();
+        let mut items = Vec::new();
+        loop {
+            if self.eat(']') {
+                return Some(Value::List(items));
+            }
+            items.push(self.value()?);
+            if !self.eat(',') && !matches!(self.peek(), Some(']')) {
+                return None;
+            }
+        }
+    }
+
+    fn map(&mut self) -> Option<Value> {
+        self.bump();
+        let mut entries = Vec::new();
+        loop {
+            if self.eat('}') {
+                return Some(Value::Map(entries));
+            }
+            self.skip_ws();
+            let key = parse_value(&self.scalar_text(true)?);
+            let value = if self.eat(':') { self.value()? } else { Value::Null };
+            entries.push((key, value));
+            if !self.eat(',') && !matches!(self.peek(), Some('}')) {
+                return None;
+            }
+        }
+    }
+
+    /// The raw text of a scalar, quotes included. Plain scalars end at
+    /// a flow indicator, and keys also at a `:` separator.
+    fn scalar_text(&mut self, key: bool) -> Option<String> {
+        let start = self.pos;
+        match self.peek()? {
+            q @ ('"' | '\'') => {
+                self.bump();
+                loop {
+                    match self.bump()? {
+                        '\\' if q == '"' => {
+                            self.bump()?;
+                        }
+                        c if c == q => {
+                            // `''` is an escaped quote in single quotes.
+                            if q == '\'' && self.peek() == Some('\'') {
+                                self.bump();
+                            } else {
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {
+                while let Some(c) = self.peek() {
+                    let rest = &self.text[self.pos + c.len_utf8()..];
+                    let separator = key
+                        && c == ':'
+                        && (rest.is_empty()
+                            || rest.starts_with([' ', ',', ']', '}']));
+                    if matches!(c, ',' | ']' | '}') || separator {
+                        break;
+                    }
+                    self.bump();
+                }
+            }
+        }
+        Some(self.text[start..self.pos].trim().to_string())
+    }
+}
+
+/// Render a value on a single line: a scalar, or a collection in flow
+/// style.
+pub(crate) fn render_flow(value: &Value) -> String {
+    match value {
+        Value::List(items) => {
+            let items: Vec<String> = items.iter().map(render_flow).collect();
+            format!("[{}]", items.join(", "))
+        }
+        Value::Map(entries) => {
+            let entries: Vec<String> = entries
+                .iter()
+                .map(|(k, v)| format!("{}: {}", render_key(k), render_flow(v)))
+                .collect();
+            format!("{{{}}}", entries.join(", "))
+        }
+        _ => render_scalar(value, true).into_owned(),
+    }
+}
+
+/// Render a scalar. Strings are quoted when they would read back as
+/// something else, including as another type; `flow` also quotes the
+/// flow indicators that would end them early.
+pub(crate) fn render_scalar(value: &Value, flow: bool) -> Cow<'static, str> {
+    match value {
+        Value::Null => Cow::Borrowed("null"),
+        Value::Bool(true) => Cow::Borrowed("true"),
+        Value::Bool(false) => Cow::Borrowed("false"),
+        Value::Int(n) => Cow::Owned(n.to_string()),
+        Value::Float(n) if n.is_nan() => Cow::Borrowed(".nan"),
+        Value::Float(n) if n.is_infinite() => {
+            Cow::Borrowed(if *n > 0.0 { ".inf" } else { "-.inf" })
+        }
+        Value::Float(n) => Cow::Owned(format!("{n:?}")),
+        Value::String(s) => {
+            let quoted = needs_quotes(s)
+                || !matches!(scalar(s), Value::String(_))
+                || (flow && s.contains([',', '[', ']', '{', '}']));
+            Cow::Owned(if quoted { double_quote(s) } else { s.clone() })
+        }
+        Value::List(_) | Value::Map(_) => Cow::Owned(render_flow(value)),
+    }
+}
+
+/// Render a mapping key, quoted unless it is a plain word.
+pub(crate) fn render_key(key: &str) -> Cow<'_, str> {
+    let plain = !key.is_empty()
+        && !needs_quotes(key)
+        && !key.contains(':')
+        && !key.contains([',', '[', ']', '{', '}']);
+    if plain {
+        Cow::Borrowed(key)
+    } else {
+        Cow::Owned(double_quote(key))
+    }
+}
+
+/// Wrap text in double quotes, escaping what YAML requires.
+pub(crate) fn double_quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scalars_resolve_by_the_core_schema() {
+        assert_eq!(scalar("~"), Value::Null);
+        assert_eq!(scalar("True"), Value::Bool(true));
+        assert_eq!(scalar("-42"), Value::Int(-42));
+        assert_eq!(scalar("1.5e3"), Value::Float(1500.0));
+        assert_eq!(scalar(".5"), Value::Float(0.5));
+        assert_eq!(scalar("\"42\""), Value::String("42".into()));
+        assert_eq!(scalar("2026-07-19"), Value::String("2026-07-19".into()));
+        assert_eq!(scalar("inf"), Value::String("inf".into()));
+    }
+
+    #[test]
+    fn test_flow_collections() {
+        assert_eq!(
+            flow("[a, \"b, c\", 3, [x], {k: v}]"),
+            Value::List(vec![
+                "a".into(),
+                "b, c".into(),
+                Value::Int(3),
+                Value::List(vec!["x".into()]),
+                Value::Map(vec![("k".into(), "v".into())]),
+            ])
+        );
+        assert_eq!(
+            flow("{x: 1, url: http://a.b/c, 'q': ''}"),
+            Value::Map(vec![
+                ("x".into(), Value::Int(1)),
+                ("url".into(), "http://a.b/c".into()),
+                ("q".into(), "".into()),
+            ])
+        );
+        assert_eq!(flow("[]"), Value::List(vec![]));
+        assert_eq!(flow("[a, b"), Value::String("[a, b".into()));
+    }
+
+    #[test]
+    fn test_strings_that_read_as_other_types_render_quoted() {
+        assert_eq!(render_scalar(&"true".into(), false), "\"true\"");
+        assert_eq!(render_scalar(&"12".into(), false), "\"12\"");
+        assert_eq!(render_scalar(&"a, b".into(), false), "a, b");
+        assert_eq!(render_scalar(&"a, b".into(), true), "\"a, b\"");
+        assert_eq!(
+            render_scalar(&"two\nlines".into(), false),
+            "\"two\\nlines\""
+        );
+        assert_eq!(render_scalar(&Value::Float(2.0), false), "2.0");
+    }
+
+    #[test]
+    fn test_comments_outside_quotes_are_stripped() {
+        assert_eq!(strip_comment("[a, b] # note"), "[a, b] ");
+        assert_eq!(strip_comment("\"a # b\" # c"), "\"a # b\" ");
+        assert_eq!(strip_comment("a#b"), "a#b");
+    }
+}
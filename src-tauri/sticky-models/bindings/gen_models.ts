@@ -2,10 +2,209 @@
 
 export type AnyModel = Note;
 
-export type Note = { model: "note", id: string, createdAt: string, updatedAt: string, content: string, };
+/**
+ * A note linking to another one, with the line holding the first
+ * such link.
+ */
+export type Backlink = { id: string, title: string, snippet: string, };
+
+/**
+ * A day that has a daily note.
+ */
+export type DailyNote = { date: string, noteId: string, };
+
+export type DiagnosticSeverity = "warning" | "error";
+
+/**
+ * A note moved out of the vault because it expired.
+ */
+export type ExpiredNote = { id: string, title: string, };
+
+export type LinkEdge = { from: string, to: string, };
+
+/**
+ * The wiki links between all notes.
+ */
+export type LinkGraph = { 
+/**
+ * Every note, newest first.
+ */
+nodes: Array<LinkNode>, 
+/**
+ * One edge per linking note and linked note.
+ */
+edges: Array<LinkEdge>, 
+/**
+ * Links whose target names no note, once per note and target.
+ */
+unresolved: Array<UnresolvedLink>, };
+
+export type LinkNode = { id: string, title: string, };
+
+/**
+ * A note and its content. The fields after `content` are derived from
+ * the file on read and ignored on write.
+ */
+export type Note = { model: "note", id: string, createdAt: string, updatedAt: string, content: string, 
+/**
+ * The note's tags, from its header and inline hashtags; see
+ * [`crate::tags`].
+ */
+tags: Array<string>, 
+/**
+ * The other names wiki links may use for the note, from its
+ * header.
+ */
+aliases: Array<string>, 
+/**
+ * When the note expires, if it is ephemeral; see
+ * [`crate::expiry`].
+ */
+expiresAt: string | null, 
+/**
+ * Whether the note is archived; see [`crate::archive`].
+ */
+archived: boolean, 
+/**
+ * Whether the note is pinned, which lists it ahead of the rest;
+ * see [`crate::store::NotesStore::pin`].
+ */
+pinned: boolean, 
+/**
+ * The note's color label, if it has one; see [`crate::colors`].
+ */
+color: NoteColor | null, };
+
+/**
+ * A note's color label, from a fixed palette.
+ */
+export type NoteColor = "red" | "orange" | "yellow" | "green" | "blue" | "purple" | "gray";
+
+/**
+ * A problem in a note file's header that keeps a field from being
+ * read as written.
+ */
+export type NoteDiagnostic = { 
+/**
+ * The 1-based line of the file.
+ */
+line: number, severity: DiagnosticSeverity, message: string, };
 
 /**
  * A note matched by a search, with its display title and, for body
  * matches, an excerpt around the first match.
  */
 export type NoteSearchHit = { note: Note, title: string, snippet: string | null, };
+
+/**
+ * What listing a note needs: its identity, timestamps and display
+ * title, read without loading the whole body.
+ */
+export type NoteSummary = { id: string, title: string, createdAt: string, updatedAt: string, 
+/**
+ * The day this note is the daily note of, if it is one; see
+ * [`crate::daily`].
+ */
+date: string | null, 
+/**
+ * The note's reminder, if it has one; see [`crate::reminders`].
+ */
+reminder: Reminder | null, 
+/**
+ * When the note expires, if it is ephemeral; see
+ * [`crate::expiry`].
+ */
+expiresAt: string | null, 
+/**
+ * Whether the note is archived; see [`crate::archive`].
+ */
+archived: boolean, 
+/**
+ * Whether the note is pinned; see [`Note::pinned`].
+ */
+pinned: boolean, 
+/**
+ * The note's color label, if it has one; see [`crate::colors`].
+ */
+color: NoteColor | null, };
+
+/**
+ * A note template and the prompts to ask before creating a note
+ * from it.
+ */
+export type NoteTemplate = { 
+/**
+ * The template file's name, without its extension.
+ */
+name: string, prompts: Array<string>, };
+
+/**
+ * A note's reminder, in local time.
+ */
+export type Reminder = { at: string, repeat: Repeat | null, snoozedUntil: string | null, };
+
+export type Repeat = "daily" | "weekdays" | "weekly" | "monthly" | "yearly";
+
+/**
+ * Links left pointing at a note's old title after it was retitled.
+ */
+export type StaleLinks = { oldTitle: string, newTitle: string, 
+/**
+ * The notes holding such links.
+ */
+noteIds: Array<string>, };
+
+/**
+ * A tag and how many notes carry it.
+ */
+export type TagCount = { tag: string, count: number, };
+
+/**
+ * A checklist item in a note: `- [ ] text` or `- [x] text`.
+ */
+export type Task = { noteId: string, noteTitle: string, taskRef: TaskRef, 
+/**
+ * The item's text, without its checkbox and due date.
+ */
+text: string, done: boolean, 
+/**
+ * The date of an `@due(YYYY-MM-DD)` in the text.
+ */
+due: string | null, 
+/**
+ * How many tasks this one is nested in.
+ */
+depth: number, 
+/**
+ * The line of the task this one is nested in, if any.
+ */
+parentLine: number | null, };
+
+/**
+ * Which tasks to list; every field left out matches all tasks.
+ */
+export type TaskFilter = { 
+/**
+ * Only done tasks, or only open ones.
+ */
+done: boolean | null, 
+/**
+ * Only tasks due on or before this date.
+ */
+dueBy: string | null, };
+
+/**
+ * Where a task sits in its note, as of when it was read: enough to
+ * find it again after other edits to the note.
+ */
+export type TaskRef = { 
+/**
+ * The 0-based line of the note's body.
+ */
+line: number, 
+/**
+ * The whole text of that line.
+ */
+source: string, };
+
+export type UnresolvedLink = { from: string, target: string, };
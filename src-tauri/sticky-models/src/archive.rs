@@ -0,0 +1,82 @@
+//! Archived notes: notes set aside with `archived: true` in their
+//! header.
+//!
+//! Archived notes stay in the vault but drop out of the tray, the
+//! default listings and window toggling; searching with
+//! [`SEARCH_TERM`] finds them again.
+//!
+//! With `archiveAfterDays` in the vault config, notes left unedited
+//! that long are archived automatically. Unarchiving writes
+//! `archived: false` rather than dropping the field, which keeps a
+//! note out of that.
+//!
+//! Files the store tracks without touching them, in non-invasive mode,
+//! keep the state in its sidecar index instead.
+
+use chrono::{DateTime, TimeDelta, Utc};
+use sticky_matter::{Document, Value};
+
+/// The frontmatter field marking a note archived.
+pub const ARCHIVED: &str = "archived";
+
+/// The search term restricting results to archived notes.
+pub const SEARCH_TERM: &str = "is:archived";
+
+/// The archived state in a note's header: `None` when it has none.
+pub fn header_archived(doc: &Document) -> Option<bool> {
+    match doc.value(ARCHIVED) {
+        Some(Value::Bool(archived)) => Some(archived),
+        _ => None,
+    }
+}
+
+/// Whether a note is archived.
+pub fn is_archived(doc: &Document) -> bool {
+    header_archived(doc) == Some(true)
+}
+
+/// Archive or unarchive a note. Returns whether the header changed.
+pub fn set_archived(doc: &mut Document, archived: bool) -> bool {
+    if header_archived(doc) == Some(archived) {
+        return false;
+    }
+    doc.set_value(ARCHIVED, archived);
+    true
+}
+
+/// Whether a note last updated at `updated_at`, with the archived state
+/// `archived` in its header, is due for archiving after `days` days
+/// unedited. Notes with any archived state are left alone.
+pub fn is_idle(
+    archived: Option<bool>,
+    updated_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+    days: u32,
+) -> bool {
+    archived.is_none() && updated_at + TimeDelta::days(days.into()) <= now
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn archives_and_unarchives() {
+        let mut doc = Document::parse("---\ntitle: x\n---\n\nBody\n");
+        assert_eq!(header_archived(&doc), None);
+        assert!(set_archived(&mut doc, true));
+        assert!(is_archived(&doc));
+        assert!(!set_archived(&mut doc, true));
+        assert!(set_archived(&mut doc, false));
+        assert_eq!(
+            doc.render(),
+            "---\ntitle: x\narchived: false\n---\n\nBody\n"
+        );
+
+        let updated: DateTime<Utc> = "2026-10-01T12:00:00Z".parse().unwrap();
+        let now: DateTime<Utc> = "2026-10-31T12:00:00Z".parse().unwrap();
+        assert!(is_idle(None, updated, now, 30));
+        assert!(!is_idle(None, updated, now, 31));
+        assert!(!is_idle(Some(false), updated, now, 1));
+    }
+}
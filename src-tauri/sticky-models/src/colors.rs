@@ -0,0 +1,120 @@
+//! Color labels: a `color:` field in a note's header, naming one of a
+//! fixed palette.
+//!
+//! The app tints the note's window with it and marks the note in the
+//! tray; searching with a `color:` term, like `color:red`, keeps only
+//! the notes labeled so. Keep the tints in sync with the `data-color`
+//! rules in `src-web/src/styles/global.css`.
+
+use sticky_matter::{Document, Value};
+
+use crate::models::NoteColor;
+
+/// The frontmatter field holding a note's color label.
+pub const COLOR: &str = "color";
+
+/// The prefix of search terms filtering by color label.
+pub const SEARCH_PREFIX: &str = "color:";
+
+/// Every color label, in palette order.
+pub const PALETTE: [NoteColor; 7] = [
+    NoteColor::Red,
+    NoteColor::Orange,
+    NoteColor::Yellow,
+    NoteColor::Green,
+    NoteColor::Blue,
+    NoteColor::Purple,
+    NoteColor::Gray,
+];
+
+/// The color label in a note's header, if it names one of the palette.
+pub fn header_color(doc: &Document) -> Option<NoteColor> {
+    match doc.value(COLOR) {
+        Some(Value::String(name)) => parse(&name),
+        _ => None,
+    }
+}
+
+/// The color label called `name`, in any case.
+pub fn parse(name: &str) -> Option<NoteColor> {
+    let name = name.trim().to_lowercase();
+    PALETTE.into_iter().find(|color| self::name(*color) == name)
+}
+
+/// The name a color label is written with.
+pub fn name(color: NoteColor) -> &'static str {
+    match color {
+        NoteColor::Red => "red",
+        NoteColor::Orange => "orange",
+        NoteColor::Yellow => "yellow",
+        NoteColor::Green => "green",
+        NoteColor::Blue => "blue",
+        NoteColor::Purple => "purple",
+        NoteColor::Gray => "gray",
+    }
+}
+
+/// The marker the tray shows before a labeled note's title.
+pub fn marker(color: NoteColor) -> &'static str {
+    match color {
+        NoteColor::Red => "🔴",
+        NoteColor::Orange => "🟠",
+        NoteColor::Yellow => "🟡",
+        NoteColor::Green => "🟢",
+        NoteColor::Blue => "🔵",
+        NoteColor::Purple => "🟣",
+        NoteColor::Gray => "⚪",
+    }
+}
+
+/// The background of a labeled note's window, as RGB: a pale tint in
+/// light mode and a deep one in dark mode.
+pub fn tint(color: NoteColor, dark: bool) -> (u8, u8, u8) {
+    match (color, dark) {
+        (NoteColor::Red, false) => (254, 226, 226),
+        (NoteColor::Red, true) => (69, 10, 10),
+        (NoteColor::Orange, false) => (255, 237, 213),
+        (NoteColor::Orange, true) => (67, 20, 7),
+        (NoteColor::Yellow, false) => (254, 249, 195),
+        (NoteColor::Yellow, true) => (66, 32, 6),
+        (NoteColor::Green, false) => (220, 252, 231),
+        (NoteColor::Green, true) => (5, 46, 22),
+        (NoteColor::Blue, false) => (219, 234, 254),
+        (NoteColor::Blue, true) => (23, 37, 84),
+        (NoteColor::Purple, false) => (243, 232, 255),
+        (NoteColor::Purple, true) => (59, 7, 100),
+        (NoteColor::Gray, false) => (244, 244, 245),
+        (NoteColor::Gray, true) => (39, 39, 42),
+    }
+}
+
+/// The saturated shade of a color label, as RGB, for small marks like
+/// the window's traffic lights.
+pub fn accent(color: NoteColor) -> (u8, u8, u8) {
+    match color {
+        NoteColor::Red => (248, 113, 113),
+        NoteColor::Orange => (251, 146, 60),
+        NoteColor::Yellow => (250, 204, 21),
+        NoteColor::Green => (74, 222, 128),
+        NoteColor::Blue => (96, 165, 250),
+        NoteColor::Purple => (192, 132, 252),
+        NoteColor::Gray => (161, 161, 170),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_color_labels() {
+        let color = |header: &str| {
+            header_color(&Document::parse(&format!("---\n{header}\n---\n")))
+        };
+        assert_eq!(color("color: Red"), Some(NoteColor::Red));
+        assert_eq!(color("color: gray"), Some(NoteColor::Gray));
+        assert_eq!(color("color: teal"), None);
+        assert_eq!(color("color: [red]"), None);
+        assert!(PALETTE.into_iter().all(|c| parse(name(c)) == Some(c)));
+    }
+}
@@ -0,0 +1,58 @@
+//! Daily notes: one note per day, marked by a `date:` header field.
+//!
+//! A note is the daily note of the day its `date` field holds, written
+//! `YYYY-MM-DD`; its filename doesn't matter, so renaming or retitling
+//! it keeps it that day's note. A new daily note is titled with its
+//! date and started from the vault's daily template, if one is set
+//! (see [`crate::state::StoreConfig`]).
+
+use chrono::NaiveDate;
+use sticky_matter::{Document, Value};
+
+/// The frontmatter field holding a daily note's date.
+pub const DATE: &str = "date";
+
+/// How a daily note's date is written, in its header and its title.
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// The date in a note's `date` field, if it holds one.
+pub fn header_date(doc: &Document) -> Option<NaiveDate> {
+    match doc.value(DATE) {
+        Some(Value::String(s)) => {
+            NaiveDate::parse_from_str(s.trim(), DATE_FORMAT).ok()
+        }
+        _ => None,
+    }
+}
+
+/// The title a new daily note starts with.
+pub fn title(date: NaiveDate) -> String {
+    date.format(DATE_FORMAT).to_string()
+}
+
+/// A new daily note with no template: its date in the header and as
+/// its heading.
+pub fn seed(date: NaiveDate) -> Document {
+    let mut doc = Document::new();
+    doc.set(DATE, title(date));
+    doc.set_body(format!("# {}\n", title(date)));
+    doc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_only_plain_dates() {
+        let date = |header: &str| {
+            header_date(&Document::parse(&format!("---\n{header}\n---\n")))
+                .map(|d| d.to_string())
+        };
+        assert_eq!(date("date: 2026-10-19").as_deref(), Some("2026-10-19"));
+        assert_eq!(date("date: '2026-10-19'").as_deref(), Some("2026-10-19"));
+        assert_eq!(date("date: 2026-10-19T09:00"), None);
+        assert_eq!(date("date: yesterday"), None);
+        assert_eq!(date("day: 2026-10-19"), None);
+    }
+}
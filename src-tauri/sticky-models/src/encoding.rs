@@ -0,0 +1,278 @@
+//! Text encodings and line endings of note files.
+//!
+//! Notes are edited and searched as UTF-8 with `\n` line endings, but
+//! files dropped into the vault by other tools may be anything. Each
+//! file is decoded on read and encoded back in its original
+//! [`FileFormat`] on write, so a Latin-1 file saved from Sticky is
+//! still a Latin-1 file.
+
+use log::warn;
+
+/// The UTF-8 byte order mark.
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+/// The UTF-16 little-endian byte order mark.
+const UTF16_LE_BOM: &[u8] = b"\xFF\xFE";
+/// The UTF-16 big-endian byte order mark.
+const UTF16_BE_BOM: &[u8] = b"\xFE\xFF";
+
+/// How many leading bytes the BOM-less UTF-16 sniffing looks at.
+const SNIFF_LEN: usize = 512;
+
+/// The Windows-1252 characters at bytes `0x80..=0x9F`; everywhere else
+/// the encoding matches Latin-1. The five bytes Windows-1252 leaves
+/// undefined map to the C1 controls of the same value, like browsers
+/// do, so every byte decodes and encodes back.
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}',
+    '\u{2020}', '\u{2021}', '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}',
+    '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}', '\u{0090}', '\u{2018}',
+    '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}',
+    '\u{017E}', '\u{0178}',
+];
+
+/// A file's character encoding.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Encoding {
+    #[default]
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    /// Windows-1252, the superset of Latin-1 that legacy Windows and
+    /// macOS editors write.
+    Windows1252,
+}
+
+/// How a note file's text is laid out on disk.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FileFormat {
+    pub encoding: Encoding,
+    /// Whether the text starts with a byte order mark.
+    pub bom: bool,
+    /// Whether lines end in `\r\n` rather than `\n`.
+    pub crlf: bool,
+}
+
+impl FileFormat {
+    /// Whether files in this format are plain UTF-8 with `\n` line
+    /// endings, the format Sticky creates notes in.
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Decode file contents into text with `\n` line endings, detecting
+/// the format they were stored in.
+///
+/// Byte order marks win; BOM-less UTF-16 is recognized by its zero
+/// bytes; anything else that isn't valid UTF-8 is read as
+/// Windows-1252, which accepts every byte. That includes invalid UTF-8
+/// behind a UTF-8 byte order mark, which is kept as it is.
+pub fn decode(bytes: &[u8]) -> (String, FileFormat) {
+    let (text, encoding, bom) = if let Some(rest) = bytes.strip_prefix(UTF8_BOM)
+    {
+        let (text, encoding) = decode_8bit(rest);
+        (text, encoding, true)
+    } else if let Some(rest) = bytes.strip_prefix(UTF16_LE_BOM) {
+        (decode_utf16(rest, u16::from_le_bytes), Encoding::Utf16Le, true)
+    } else if let Some(rest) = bytes.strip_prefix(UTF16_BE_BOM) {
+        (decode_utf16(rest, u16::from_be_bytes), Encoding::Utf16Be, true)
+    } else if let Some(encoding) = sniff_utf16(bytes) {
+        let text = match encoding {
+            Encoding::Utf16Be => decode_utf16(bytes, u16::from_be_bytes),
+            _ => decode_utf16(bytes, u16::from_le_bytes),
+        };
+        (text, encoding, false)
+    } else {
+        let (text, encoding) = decode_8bit(bytes);
+        (text, encoding, false)
+    };
+
+    let crlf = is_crlf(&text);
+    let text =
+        if text.contains("\r\n") { text.replace("\r\n", "\n") } else { text };
+    (text, FileFormat { encoding, bom, crlf })
+}
+
+/// How many leading bytes to skip to stream a file as UTF-8, judged by
+/// its opening `bytes`: the length of its UTF-8 byte order mark, if
+/// any. `None` for UTF-16, which can only be [`decode`]d whole.
+///
+/// Whether the rest is valid UTF-8 only shows while reading it.
+pub fn utf8_start(bytes: &[u8]) -> Option<usize> {
+    if bytes.starts_with(UTF8_BOM) {
+        Some(UTF8_BOM.len())
+    } else if bytes.starts_with(UTF16_LE_BOM)
+        || bytes.starts_with(UTF16_BE_BOM)
+        || bytes.contains(&0)
+    {
+        None
+    } else {
+        Some(0)
+    }
+}
+
+/// Encode text with `\n` line endings back into `format`.
+///
+/// Text Windows-1252 cannot represent (an emoji typed into a legacy
+/// file) is written as UTF-8 instead: converting the file beats losing
+/// characters.
+pub fn encode(text: &str, format: FileFormat) -> Vec<u8> {
+    let text = if format.crlf {
+        text.replace("\r\n", "\n").replace('\n', "\r\n")
+    } else {
+        text.to_string()
+    };
+
+    let (bytes, bom) = match format.encoding {
+        Encoding::Utf8 => (text.into_bytes(), UTF8_BOM),
+        Encoding::Utf16Le => {
+            (encode_utf16(&text, u16::to_le_bytes), UTF16_LE_BOM)
+        }
+        Encoding::Utf16Be => {
+            (encode_utf16(&text, u16::to_be_bytes), UTF16_BE_BOM)
+        }
+        Encoding::Windows1252 => match encode_windows_1252(&text) {
+            Some(bytes) => (bytes, UTF8_BOM),
+            None => {
+                warn!("Text outside Windows-1252; writing UTF-8 instead");
+                (text.into_bytes(), UTF8_BOM)
+            }
+        },
+    };
+    if format.bom {
+        [bom, &bytes].concat()
+    } else {
+        bytes
+    }
+}
+
+/// Whether text uses `\r\n` line endings: at least as many lines end
+/// in `\r\n` as in a bare `\n`, and there is at least one.
+fn is_crlf(text: &str) -> bool {
+    let crlf = text.matches("\r\n").count();
+    let lf = text.matches('\n').count() - crlf;
+    crlf > 0 && crlf >= lf
+}
+
+/// Detect BOM-less UTF-16 by the zero high bytes of its ASCII
+/// characters: plenty of them, all on the same side of each pair.
+fn sniff_utf16(bytes: &[u8]) -> Option<Encoding> {
+    let sample = &bytes[..bytes.len().min(SNIFF_LEN)];
+    if sample.len() < 2 || !bytes.len().is_multiple_of(2) {
+        return None;
+    }
+
+    let pairs = sample.len() / 2;
+    let zeros = |offset: usize| {
+        sample.iter().skip(offset).step_by(2).filter(|&&b| b == 0).count()
+    };
+    let (even, odd) = (zeros(0), zeros(1));
+
+    if odd * 4 >= pairs && even == 0 {
+        Some(Encoding::Utf16Le)
+    } else if even * 4 >= pairs && odd == 0 {
+        Some(Encoding::Utf16Be)
+    } else {
+        None
+    }
+}
+
+/// Decode UTF-8, or Windows-1252 when the bytes aren't valid UTF-8.
+fn decode_8bit(bytes: &[u8]) -> (String, Encoding) {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => (text.to_string(), Encoding::Utf8),
+        Err(_) => (decode_windows_1252(bytes), Encoding::Windows1252),
+    }
+}
+
+fn decode_utf16(bytes: &[u8], unit: fn([u8; 2]) -> u16) -> String {
+    let units = bytes.chunks_exact(2).map(|pair| unit([pair[0], pair[1]]));
+    char::decode_utf16(units)
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
+fn encode_utf16(text: &str, bytes: fn(u16) -> [u8; 2]) -> Vec<u8> {
+    text.encode_utf16().flat_map(bytes).collect()
+}
+
+fn decode_windows_1252(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|&b| match b {
+            0x80..=0x9F => WINDOWS_1252_HIGH[(b - 0x80) as usize],
+            _ => char::from(b),
+        })
+        .collect()
+}
+
+/// Encode text as Windows-1252, or `None` if any character has no
+/// byte there.
+fn encode_windows_1252(text: &str) -> Option<Vec<u8>> {
+    text.chars()
+        .map(|c| match u32::from(c) {
+            0x00..=0x7F | 0xA0..=0xFF => Some(c as u8),
+            _ => WINDOWS_1252_HIGH
+                .iter()
+                .position(|&high| high == c)
+                .map(|i| 0x80 + i as u8),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[track_caller]
+    fn round_trip(bytes: &[u8], text: &str, encoding: Encoding, crlf: bool) {
+        let (decoded, format) = decode(bytes);
+        assert_eq!(decoded, text);
+        assert_eq!((format.encoding, format.crlf), (encoding, crlf));
+        assert_eq!(encode(&decoded, format), bytes);
+    }
+
+    #[test]
+    fn round_trips_every_format() {
+        round_trip(b"caf\xC3\xA9\n", "café\n", Encoding::Utf8, false);
+        round_trip(b"\xEF\xBB\xBF---\n", "---\n", Encoding::Utf8, false);
+        round_trip(b"caf\xE9 \x80\n", "café €\n", Encoding::Windows1252, false);
+        let bom_1252 = b"\xEF\xBB\xBFcaf\xE9\n";
+        round_trip(bom_1252, "café\n", Encoding::Windows1252, false);
+        round_trip(b"\xFF\xFEh\0i\0", "hi", Encoding::Utf16Le, false);
+        round_trip(b"\xFE\xFF\0h\0i", "hi", Encoding::Utf16Be, false);
+        round_trip(b"h\0i\0\n\0", "hi\n", Encoding::Utf16Le, false);
+        round_trip(b"a\r\nb\r\n", "a\nb\n", Encoding::Utf8, true);
+    }
+
+    #[test]
+    fn sniffs_utf16_without_a_bom() {
+        let (text, format) = decode(b"h\0i\0\n\0");
+        assert_eq!(text, "hi\n");
+        assert_eq!(format.encoding, Encoding::Utf16Le);
+    }
+
+    #[test]
+    fn utf8_start_skips_the_bom_and_refuses_utf16() {
+        assert_eq!(utf8_start(b"\xEF\xBB\xBFhi"), Some(3));
+        assert_eq!(utf8_start(b"caf\xE9"), Some(0));
+        assert_eq!(utf8_start(b"\xFF\xFEh\0"), None);
+        assert_eq!(utf8_start(b"h\0i\0"), None);
+    }
+
+    #[test]
+    fn mixed_line_endings_follow_the_majority() {
+        assert!(!decode(b"a\nb\nc\r\n").1.crlf);
+        assert!(decode(b"a\r\nb\r\nc\n").1.crlf);
+    }
+
+    #[test]
+    fn unrepresentable_text_falls_back_to_utf8() {
+        let format = FileFormat {
+            encoding: Encoding::Windows1252,
+            ..Default::default()
+        };
+        assert_eq!(encode("café 🎉", format), "café 🎉".as_bytes());
+    }
+}
@@ -0,0 +1,105 @@
+//! Ephemeral notes: notes that expire on their own.
+//!
+//! A note expires at the local time in its `expires:` field, written
+//! like a reminder's (`2026-10-19 18:00`; a bare date means its start),
+//! or once left unedited for as long as its `expireAfter:` field says:
+//! `30m`, `8h` or `2d`, or a bare number of hours. With both, whichever
+//! comes first wins.
+//!
+//! Expired notes are moved to the vault's `expired` folder, without
+//! their expiry fields, from where they can be moved back.
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeDelta, Utc};
+use sticky_matter::{Document, Value};
+
+use crate::reminders::TIME_FORMATS;
+
+/// The folder of the vault holding expired notes.
+pub const EXPIRED_DIR: &str = "expired";
+
+/// The frontmatter field holding a note's expiry time.
+pub const EXPIRES: &str = "expires";
+
+/// The frontmatter field holding how long a note may sit unedited.
+pub const EXPIRE_AFTER: &str = "expireAfter";
+
+/// When a note last updated at `updated_at` expires, if ever.
+pub fn expires_at(
+    doc: &Document,
+    updated_at: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let fixed = match doc.value(EXPIRES) {
+        Some(Value::String(s)) => parse_time(&s).and_then(|time| {
+            time.and_local_timezone(Local).earliest().map(|time| time.to_utc())
+        }),
+        _ => None,
+    };
+    let idle = match doc.value(EXPIRE_AFTER) {
+        Some(Value::Int(hours)) => Some(TimeDelta::hours(hours)),
+        Some(Value::String(s)) => parse_duration(&s),
+        _ => None,
+    }
+    .filter(|idle| *idle > TimeDelta::zero())
+    .and_then(|idle| updated_at.checked_add_signed(idle));
+
+    match (fixed, idle) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+fn parse_time(text: &str) -> Option<NaiveDateTime> {
+    let text = text.trim();
+    TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+        .or_else(|| {
+            let date = NaiveDate::parse_from_str(text, "%Y-%m-%d").ok()?;
+            date.and_hms_opt(0, 0, 0)
+        })
+}
+
+/// A duration like `30m`, `8h` or `2d`; a bare number is hours.
+fn parse_duration(text: &str) -> Option<TimeDelta> {
+    let text = text.trim();
+    let (number, unit) = match text.char_indices().last()? {
+        (i, unit) if unit.is_ascii_alphabetic() => (&text[..i], unit),
+        _ => (text, 'h'),
+    };
+    let number: i64 = number.trim().parse().ok()?;
+    match unit.to_ascii_lowercase() {
+        'm' => TimeDelta::try_minutes(number),
+        'h' => TimeDelta::try_hours(number),
+        'd' => TimeDelta::try_days(number),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn reads_fixed_and_idle_expiry() {
+        let updated: DateTime<Utc> = "2026-10-19T12:00:00Z".parse().unwrap();
+        let expiry = |header: &str| {
+            let doc = Document::parse(&format!("---\n{header}\n---\n"));
+            expires_at(&doc, updated).map(|at| (at - updated).num_minutes())
+        };
+        assert_eq!(expiry("expireAfter: 8"), Some(8 * 60));
+        assert_eq!(expiry("expireAfter: 30m"), Some(30));
+        assert_eq!(expiry("expireAfter: 2d"), Some(2 * 24 * 60));
+        assert_eq!(expiry("expireAfter: soon"), None);
+        assert_eq!(expiry("expireAfter: -1h"), None);
+        assert_eq!(expiry("title: x"), None);
+
+        let fixed =
+            Local.with_ymd_and_hms(2026, 10, 19, 18, 0, 0).unwrap().to_utc();
+        let doc = Document::parse(
+            "---\nexpires: 2026-10-19 18:00\nexpireAfter: 30d\n---\n",
+        );
+        assert_eq!(expires_at(&doc, updated), Some(fixed));
+    }
+}
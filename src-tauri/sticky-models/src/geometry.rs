@@ -0,0 +1,483 @@
+//! Window geometry in logical pixels, independent of the windowing
+//! toolkit.
+//!
+//! The app converts monitor work areas and window frames into
+//! [`Rect`]s, so where a window may go is decided here and tested
+//! without a display.
+
+use serde::{Deserialize, Serialize};
+
+/// An axis-aligned rectangle in logical pixels, from its top-left
+/// corner.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Rect {
+    /// The area `self` and `other` have in common, zero when apart.
+    pub fn overlap(&self, other: &Rect) -> f64 {
+        let (width, height) = self.intersection(other);
+        width * height
+    }
+
+    /// The width and height of what `self` and `other` have in common,
+    /// zero when apart.
+    fn intersection(&self, other: &Rect) -> (f64, f64) {
+        let width = (self.x + self.width).min(other.x + other.width)
+            - self.x.max(other.x);
+        let height = (self.y + self.height).min(other.y + other.height)
+            - self.y.max(other.y);
+        (width.max(0.0), height.max(0.0))
+    }
+
+    /// The squared distance between the centers of `self` and `other`.
+    fn distance(&self, other: &Rect) -> f64 {
+        let dx = (self.x + self.width / 2.0) - (other.x + other.width / 2.0);
+        let dy = (self.y + self.height / 2.0) - (other.y + other.height / 2.0);
+        dx * dx + dy * dy
+    }
+}
+
+/// A monitor as windows are placed on it: its work area and, where
+/// the platform reports one, its name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Screen {
+    pub name: Option<String>,
+    pub area: Rect,
+}
+
+/// Where `frame` may go given the monitors' work areas: on the area it
+/// overlaps most, or the nearest one when it is on none, shrunk to fit
+/// that area and moved just far enough to lie inside it. Without any
+/// areas the frame is kept as is.
+pub fn clamp_to_work_areas(frame: Rect, areas: &[Rect]) -> Rect {
+    match closest(&frame, areas.iter()) {
+        Some(area) => clamp_into(frame, area),
+        None => frame,
+    }
+}
+
+/// Whether `frame` is stranded off screen: no work area shows enough
+/// of it, across and down, to grab it by. Without any areas nothing
+/// is.
+pub fn is_off_screen(frame: &Rect, areas: &[Rect]) -> bool {
+    !areas.is_empty()
+        && !areas.iter().any(|area| {
+            let (width, height) = frame.intersection(area);
+            width >= MIN_VISIBLE && height >= MIN_VISIBLE
+        })
+}
+
+/// Where a new window of `width` by `height` goes on `area`: inset
+/// from its top-left corner, then stepped down and right past windows
+/// in `taken` already starting there, so it never hides one exactly.
+pub fn place_new(width: f64, height: f64, area: &Rect, taken: &[Rect]) -> Rect {
+    let start = Rect {
+        x: area.x + NEW_WINDOW_INSET,
+        y: area.y + NEW_WINDOW_INSET,
+        width,
+        height,
+    };
+    let is_taken = |frame: &Rect| {
+        taken.iter().any(|other| {
+            (other.x - frame.x).abs() < CASCADE_STEP / 2.0
+                && (other.y - frame.y).abs() < CASCADE_STEP / 2.0
+        })
+    };
+
+    let mut frame = clamp_into(start, area);
+    for _ in 0..MAX_PLACEMENT_STEPS {
+        if !is_taken(&frame) {
+            return frame;
+        }
+        let next = Rect {
+            x: frame.x + CASCADE_STEP,
+            y: frame.y + CASCADE_STEP,
+            ..frame
+        };
+        // Past the bottom or right edge, start over at the corner.
+        frame = if next.x + width > area.x + area.width
+            || next.y + height > area.y + area.height
+        {
+            Rect { x: area.x, y: area.y, ..frame }
+        } else {
+            next
+        };
+    }
+    // Every spot is taken; the first one will do.
+    clamp_into(start, area)
+}
+
+/// The screen `frame` is on: the one it overlaps most, or the nearest
+/// when it is on none.
+pub fn screen_of<'s>(
+    frame: &Rect,
+    screens: &'s [Screen],
+) -> Option<&'s Screen> {
+    let area = closest(frame, screens.iter().map(|screen| &screen.area))?;
+    screens.iter().find(|screen| screen.area == *area)
+}
+
+/// Moves `frame`, placed on `saved` back then, onto the screens now:
+/// onto the screen of the same name, or failing that the one where
+/// `saved` was. When that screen's work area differs, the frame keeps
+/// its relative position on it, and shrinks with it but never grows.
+pub fn relocate(frame: Rect, saved: &Screen, screens: &[Screen]) -> Rect {
+    let named = saved.name.as_ref().and_then(|name| {
+        screens.iter().find(|screen| screen.name.as_ref() == Some(name))
+    });
+    let area = named
+        .map(|screen| &screen.area)
+        .or_else(|| closest(&saved.area, screens.iter().map(|s| &s.area)));
+    let Some(area) = area else {
+        return frame;
+    };
+
+    let from = &saved.area;
+    let scale_x = area.width / from.width;
+    let scale_y = area.height / from.height;
+    let rescaled = Rect {
+        x: area.x + (frame.x - from.x) * scale_x,
+        y: area.y + (frame.y - from.y) * scale_y,
+        width: frame.width * scale_x.min(1.0),
+        height: frame.height * scale_y.min(1.0),
+    };
+    clamp_into(rescaled, area)
+}
+
+/// How much of a window must show on a work area, across and down,
+/// for it to count as on screen.
+const MIN_VISIBLE: f64 = 40.0;
+
+/// How far from a work area's top-left corner new windows open.
+const NEW_WINDOW_INSET: f64 = 100.0;
+
+/// How many spots [`place_new`] tries before settling for the first.
+const MAX_PLACEMENT_STEPS: usize = 64;
+
+/// Space kept between arranged windows and the work area's edges.
+const ARRANGE_GAP: f64 = 8.0;
+
+/// How far each cascaded window sits down and right of the last.
+const CASCADE_STEP: f64 = 28.0;
+
+/// The ways [`arrange`] lays out windows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Arrangement {
+    /// Overlapping, each a step down and right of the last, from the
+    /// top-left corner.
+    Cascade,
+    /// In a grid filling the work area.
+    Tile,
+    /// One below the other along the right edge.
+    StackRight,
+    /// Moved over from the other screens, keeping their relative
+    /// positions; windows already on the work area stay put.
+    Gather,
+}
+
+/// Where `frames`, back to front, go when arranged on `area`; one
+/// frame each, in the same order. `screens` tells where gathered
+/// windows come from. Widths are kept within `min_width` and
+/// `max_width`; heights only ever shrink, as note windows fit their
+/// height to the note.
+pub fn arrange(
+    arrangement: Arrangement,
+    frames: &[Rect],
+    area: &Rect,
+    screens: &[Screen],
+    min_width: f64,
+    max_width: f64,
+) -> Vec<Rect> {
+    let inner = Rect {
+        x: area.x + ARRANGE_GAP,
+        y: area.y + ARRANGE_GAP,
+        width: area.width - 2.0 * ARRANGE_GAP,
+        height: area.height - 2.0 * ARRANGE_GAP,
+    };
+    let fit_width =
+        |width: f64| width.clamp(min_width, max_width).min(inner.width);
+
+    match arrangement {
+        Arrangement::Cascade => {
+            let mut offset = 0.0;
+            frames
+                .iter()
+                .map(|frame| {
+                    let width = fit_width(frame.width);
+                    let height = frame.height.min(inner.height);
+                    // Past the bottom or right edge, start over at the
+                    // corner.
+                    if offset + width > inner.width
+                        || offset + height > inner.height
+                    {
+                        offset = 0.0;
+                    }
+                    let placed = Rect {
+                        x: inner.x + offset,
+                        y: inner.y + offset,
+                        width,
+                        height,
+                    };
+                    offset += CASCADE_STEP;
+                    placed
+                })
+                .collect()
+        }
+        Arrangement::Tile => {
+            let count = frames.len().max(1);
+            // As square a grid as the minimum width allows.
+            let fitting = ((inner.width + ARRANGE_GAP)
+                / (min_width + ARRANGE_GAP))
+                .floor()
+                .max(1.0) as usize;
+            let columns =
+                ((count as f64).sqrt().ceil() as usize).clamp(1, fitting);
+            let rows = count.div_ceil(columns);
+            let cell_width = (inner.width - ARRANGE_GAP * (columns - 1) as f64)
+                / columns as f64;
+            let cell_height =
+                (inner.height - ARRANGE_GAP * (rows - 1) as f64) / rows as f64;
+
+            frames
+                .iter()
+                .enumerate()
+                .map(|(i, frame)| Rect {
+                    x: inner.x
+                        + (i % columns) as f64 * (cell_width + ARRANGE_GAP),
+                    y: inner.y
+                        + (i / columns) as f64 * (cell_height + ARRANGE_GAP),
+                    width: fit_width(cell_width),
+                    height: frame.height.min(cell_height),
+                })
+                .collect()
+        }
+        Arrangement::StackRight => {
+            let count = frames.len().max(1) as f64;
+            let width = frames
+                .iter()
+                .map(|frame| fit_width(frame.width))
+                .fold(0.0, f64::max);
+            let share = (inner.height - ARRANGE_GAP * (count - 1.0)) / count;
+
+            let mut y = inner.y;
+            frames
+                .iter()
+                .map(|frame| {
+                    let height = frame.height.min(share);
+                    let placed = Rect {
+                        x: inner.x + inner.width - width,
+                        y,
+                        width,
+                        height,
+                    };
+                    y += height + ARRANGE_GAP;
+                    placed
+                })
+                .collect()
+        }
+        Arrangement::Gather => {
+            let here = [Screen { name: None, area: *area }];
+            frames
+                .iter()
+                .map(|frame| {
+                    let moved = match screen_of(frame, screens) {
+                        Some(from) if from.area != *area => {
+                            let from = Screen { name: None, area: from.area };
+                            relocate(*frame, &from, &here)
+                        }
+                        _ => *frame,
+                    };
+                    let width = moved.width.clamp(min_width, max_width);
+                    clamp_into(Rect { width, ..moved }, area)
+                })
+                .collect()
+        }
+    }
+}
+
+/// The area `frame` overlaps most, or the nearest when it overlaps
+/// none.
+fn closest<'a>(
+    frame: &Rect,
+    areas: impl Iterator<Item = &'a Rect>,
+) -> Option<&'a Rect> {
+    areas.max_by(|a, b| {
+        a.overlap(frame)
+            .total_cmp(&b.overlap(frame))
+            .then(b.distance(frame).total_cmp(&a.distance(frame)))
+    })
+}
+
+/// `frame` shrunk to fit `area` and moved just far enough to lie
+/// inside it.
+fn clamp_into(frame: Rect, area: &Rect) -> Rect {
+    let width = frame.width.min(area.width);
+    let height = frame.height.min(area.height);
+    Rect {
+        x: frame.x.clamp(area.x, area.x + area.width - width),
+        y: frame.y.clamp(area.y, area.y + area.height - height),
+        width,
+        height,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f64, y: f64, width: f64, height: f64) -> Rect {
+        Rect { x, y, width, height }
+    }
+
+    #[test]
+    fn clamps_frames_into_work_areas() {
+        let areas =
+            [rect(0.0, 25.0, 1440.0, 875.0), rect(1440.0, 0.0, 1920.0, 1080.0)];
+
+        // Fully visible frames stay put, on either monitor.
+        let inside = rect(100.0, 100.0, 400.0, 300.0);
+        assert_eq!(clamp_to_work_areas(inside, &areas), inside);
+        let right = rect(2000.0, 500.0, 400.0, 300.0);
+        assert_eq!(clamp_to_work_areas(right, &areas), right);
+
+        // A frame straddling both lands on the one holding most of it.
+        let straddling = rect(1300.0, 100.0, 400.0, 300.0);
+        assert_eq!(
+            clamp_to_work_areas(straddling, &areas),
+            rect(1440.0, 100.0, 400.0, 300.0)
+        );
+
+        // A frame left on a disconnected monitor comes to the nearest,
+        // shrunk to fit it.
+        let gone = rect(-2000.0, 0.0, 400.0, 2000.0);
+        assert_eq!(
+            clamp_to_work_areas(gone, &areas),
+            rect(0.0, 25.0, 400.0, 875.0)
+        );
+
+        assert_eq!(clamp_to_work_areas(gone, &[]), gone);
+    }
+
+    #[test]
+    fn relocates_frames_across_monitor_arrangements() {
+        let screen =
+            |name: &str, area| Screen { name: Some(name.into()), area };
+        let laptop = screen("Built-in", rect(0.0, 25.0, 1440.0, 875.0));
+        let external = screen("Studio", rect(1440.0, 0.0, 2560.0, 1440.0));
+        let frame = rect(2000.0, 400.0, 400.0, 600.0);
+        assert_eq!(
+            screen_of(&frame, &[laptop.clone(), external.clone()]),
+            Some(&external)
+        );
+
+        // Its screen is still there, where it was: nothing moves.
+        let screens = [laptop.clone(), external.clone()];
+        assert_eq!(relocate(frame, &external, &screens), frame);
+
+        // Found by name after moving to the left of the laptop.
+        let moved = screen("Studio", rect(-2560.0, 0.0, 2560.0, 1440.0));
+        assert_eq!(
+            relocate(frame, &external, &[laptop.clone(), moved]),
+            rect(-2000.0, 400.0, 400.0, 600.0)
+        );
+
+        // Unplugged: the laptop takes its place, scaled down to it.
+        let placed = relocate(frame, &external, &[laptop]);
+        assert_eq!(placed.x, 315.0);
+        assert_eq!(placed.width, 225.0);
+        assert!(placed.y >= 25.0 && placed.y + placed.height <= 900.0);
+    }
+
+    #[test]
+    fn places_new_windows_and_finds_stranded_ones() {
+        let area = rect(1440.0, 0.0, 1920.0, 1080.0);
+        assert_eq!(
+            place_new(400.0, 700.0, &area, &[]),
+            rect(1540.0, 100.0, 400.0, 700.0)
+        );
+
+        // Steps past windows opened at the same spot, but not past
+        // windows elsewhere.
+        let taken = [
+            rect(1540.0, 100.0, 400.0, 300.0),
+            rect(1570.0, 126.0, 400.0, 300.0),
+            rect(1700.0, 100.0, 400.0, 300.0),
+        ];
+        assert_eq!(
+            place_new(400.0, 700.0, &area, &taken),
+            rect(1596.0, 156.0, 400.0, 700.0)
+        );
+
+        // Too tall to step down: starts over at the corner.
+        let tall = place_new(400.0, 960.0, &area, &taken[..1]);
+        assert_eq!(tall, rect(1440.0, 0.0, 400.0, 960.0));
+
+        let areas = [rect(0.0, 25.0, 1440.0, 875.0), area];
+        assert!(!is_off_screen(&taken[0], &areas));
+        // A sliver is not enough to grab.
+        assert!(is_off_screen(&rect(-380.0, 100.0, 400.0, 300.0), &areas));
+        assert!(is_off_screen(&rect(4000.0, 100.0, 400.0, 300.0), &areas));
+        assert!(!is_off_screen(&rect(4000.0, 100.0, 400.0, 300.0), &[]));
+    }
+
+    #[test]
+    fn arranges_windows_on_a_work_area() {
+        let area = rect(0.0, 0.0, 1000.0, 800.0);
+        let frames = [
+            rect(500.0, 300.0, 300.0, 200.0),
+            rect(100.0, 100.0, 900.0, 900.0),
+            rect(50.0, 600.0, 450.0, 150.0),
+        ];
+        let arrange = |arrangement, screens: &[Screen]| {
+            arrange(arrangement, &frames, &area, screens, 400.0, 700.0)
+        };
+
+        // Widths come within the bounds; heights only shrink. The
+        // full-height window has no room for a step and starts over.
+        assert_eq!(
+            arrange(Arrangement::Cascade, &[]),
+            [
+                rect(8.0, 8.0, 400.0, 200.0),
+                rect(8.0, 8.0, 700.0, 784.0),
+                rect(36.0, 36.0, 450.0, 150.0),
+            ]
+        );
+
+        // Two columns of 400 fit, three would not.
+        let tiled = arrange(Arrangement::Tile, &[]);
+        assert_eq!(tiled[0], rect(8.0, 8.0, 488.0, 200.0));
+        assert_eq!(tiled[1], rect(504.0, 8.0, 488.0, 388.0));
+        assert_eq!(tiled[2], rect(8.0, 404.0, 488.0, 150.0));
+
+        let stacked = arrange(Arrangement::StackRight, &[]);
+        assert!(stacked.iter().all(|frame| frame.x == 292.0));
+        assert_eq!(
+            stacked.iter().map(|frame| frame.y).collect::<Vec<_>>(),
+            [8.0, 216.0, 480.0]
+        );
+
+        // A window on the other screen comes over to the same spot.
+        let screens = [
+            Screen { name: None, area },
+            Screen { name: None, area: rect(1000.0, 0.0, 1000.0, 800.0) },
+        ];
+        let mut frames = frames;
+        frames[0].x += 1000.0;
+        let gathered = super::arrange(
+            Arrangement::Gather,
+            &frames,
+            &area,
+            &screens,
+            400.0,
+            700.0,
+        );
+        assert_eq!(gathered[0], rect(500.0, 300.0, 400.0, 200.0));
+        assert_eq!(gathered[2], frames[2]);
+    }
+}
@@ -1,7 +1,21 @@
+pub mod archive;
+pub mod colors;
 pub mod constants;
+pub mod daily;
+pub mod encoding;
 pub mod error;
+pub mod expiry;
+pub mod geometry;
+pub mod links;
+mod markdown;
 pub mod models;
 pub mod plugin;
 pub mod queries;
+pub mod reminders;
+pub mod state;
 pub mod store;
+pub mod sweeper;
+pub mod tags;
+pub mod tasks;
+pub mod templates;
 pub mod watcher;
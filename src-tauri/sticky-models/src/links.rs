@@ -0,0 +1,226 @@
+//! Wiki links between notes: `[[Note Title]]` and `[[note_id|label]]`.
+//!
+//! A link's target names a note by its id, its title or one of its
+//! aliases (the `aliases` frontmatter field), tried in that order.
+//! Titles and aliases compare case-insensitively. `[[Target#Heading]]`
+//! points into a section and resolves like `[[Target]]`. Links inside
+//! code spans and fenced code blocks don't count.
+
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+use sticky_matter::{Document, Value};
+
+use crate::markdown::prose;
+
+/// The frontmatter field holding the other names of a note.
+const ALIASES: &str = "aliases";
+
+/// A wiki link found in a note body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Link {
+    /// The id, title or alias the link points at.
+    pub target: String,
+    /// The section after a `#`, if any.
+    pub heading: Option<String>,
+    /// The text after a `|`, shown instead of the target.
+    pub label: Option<String>,
+    /// The byte range of the whole link, brackets included.
+    pub range: Range<usize>,
+    /// The byte range of the target text.
+    pub target_range: Range<usize>,
+}
+
+/// The wiki links of a markdown body, in order.
+pub fn links(body: &str) -> Vec<Link> {
+    let mut links = Vec::new();
+    for part in prose(body) {
+        let mut pos = part.start;
+        while let Some(open) = body[pos..part.end].find("[[").map(|i| pos + i) {
+            let inner = open + 2;
+            let Some(close) = body[inner..part.end].find("]]") else {
+                break;
+            };
+            let text = &body[inner..inner + close];
+            // `[[` opening inside a link starts over from there.
+            if let Some(nested) = text.rfind("[[") {
+                pos = inner + nested;
+                continue;
+            }
+            pos = inner + close + 2;
+            links.extend(parse(text, inner, open..pos));
+        }
+    }
+    links
+}
+
+/// Parse the text between a link's brackets, which starts at byte
+/// `at` of the body.
+fn parse(text: &str, at: usize, range: Range<usize>) -> Option<Link> {
+    let (name, label) = match text.split_once('|') {
+        Some((name, label)) => (name, Some(label.trim())),
+        None => (text, None),
+    };
+    let (target, heading) = match name.split_once('#') {
+        Some((target, heading)) => (target, Some(heading.trim())),
+        None => (name, None),
+    };
+
+    // `[[#Heading]]` points into the same note; there is nothing to
+    // resolve.
+    let trimmed = target.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    let start = at + target.len() - target.trim_start().len();
+    Some(Link {
+        target: trimmed.to_string(),
+        heading: heading.filter(|h| !h.is_empty()).map(str::to_string),
+        label: label.filter(|l| !l.is_empty()).map(str::to_string),
+        range,
+        target_range: start..start + trimmed.len(),
+    })
+}
+
+/// The aliases in a note's `aliases` field: a list, or a string of
+/// names separated by commas.
+pub fn aliases(doc: &Document) -> Vec<String> {
+    let names: Vec<String> = match doc.value(ALIASES) {
+        Some(Value::List(items)) => items
+            .iter()
+            .filter_map(|item| item.as_str().map(str::to_string))
+            .collect(),
+        Some(Value::String(s)) => s.split(',').map(str::to_string).collect(),
+        _ => Vec::new(),
+    };
+    names
+        .into_iter()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// Whether a title can be written as a link target: it must not
+/// contain the characters that end one.
+pub fn is_linkable(title: &str) -> bool {
+    !title.trim().is_empty() && !title.contains(['|', '#', '[', ']', '\n'])
+}
+
+/// Point the links to `from` in a body at `to` instead, keeping their
+/// headings and labels. Returns the new body, or `None` if no link
+/// changed.
+pub fn retarget(body: &str, from: &str, to: &str) -> Option<String> {
+    let mut text = body.to_string();
+    let mut changed = false;
+    for link in links(body).into_iter().rev() {
+        if same_name(&link.target, from) {
+            text.replace_range(link.target_range, to);
+            changed = true;
+        }
+    }
+    changed.then_some(text)
+}
+
+/// Whether two titles or aliases name the same note.
+pub fn same_name(a: &str, b: &str) -> bool {
+    a.trim().to_lowercase() == b.trim().to_lowercase()
+}
+
+/// Resolves link targets to note ids.
+#[derive(Debug, Default)]
+pub struct Resolver {
+    ids: HashSet<String>,
+    titles: HashMap<String, String>,
+    aliases: HashMap<String, String>,
+}
+
+impl Resolver {
+    /// Make a note linkable. A title or alias shared by several notes
+    /// resolves to the one added first.
+    pub fn add(&mut self, id: &str, title: &str, aliases: &[String]) {
+        self.ids.insert(id.to_string());
+        self.titles.entry(key(title)).or_insert_with(|| id.to_string());
+        for alias in aliases {
+            self.aliases.entry(key(alias)).or_insert_with(|| id.to_string());
+        }
+    }
+
+    /// The id of the note a link target names, if any.
+    pub fn resolve(&self, target: &str) -> Option<&str> {
+        let target = target.trim();
+        if let Some(id) = self.ids.get(target) {
+            return Some(id);
+        }
+        let target = key(target);
+        self.titles
+            .get(&target)
+            .or_else(|| self.aliases.get(&target))
+            .map(String::as_str)
+    }
+}
+
+fn key(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_links_outside_code() {
+        let body = "See [[Groceries]] and [[ note_abc | the plan ]].\n\
+                    `[[code]]` [[Trip#Packing]] [[#Local]] [[]] [[a [[b]]\n\
+                    ```\n[[fenced]]\n```\n";
+        let found = links(body);
+        let targets: Vec<_> = found
+            .iter()
+            .map(|l| {
+                (
+                    &body[l.target_range.clone()],
+                    l.heading.as_deref(),
+                    l.label.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            targets,
+            [
+                ("Groceries", None, None),
+                ("note_abc", None, Some("the plan")),
+                ("Trip", Some("Packing"), None),
+                ("b", None, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn retargets_links_keeping_labels_and_headings() {
+        let body = "[[old title]], [[Old Title|it]], [[Old Title#Top]], \
+                    [[Old Titles]] `[[Old Title]]`";
+        assert_eq!(
+            retarget(body, "Old Title", "New").as_deref(),
+            Some(
+                "[[New]], [[New|it]], [[New#Top]], \
+                 [[Old Titles]] `[[Old Title]]`"
+            )
+        );
+        assert_eq!(retarget(body, "Missing", "New"), None);
+        assert!(!is_linkable("A | B"));
+    }
+
+    #[test]
+    fn resolves_ids_then_titles_then_aliases() {
+        let doc = Document::parse("---\naliases: [Shopping, Food]\n---\n");
+        let mut resolver = Resolver::default();
+        resolver.add("note_a", "Groceries", &aliases(&doc));
+        resolver.add("note_b", "Shopping", &[]);
+        resolver.add("note_c", "Groceries", &["note_a".to_string()]);
+
+        assert_eq!(resolver.resolve("note_b"), Some("note_b"));
+        assert_eq!(resolver.resolve(" groceries "), Some("note_a"));
+        assert_eq!(resolver.resolve("SHOPPING"), Some("note_b"));
+        assert_eq!(resolver.resolve("food"), Some("note_a"));
+        assert_eq!(resolver.resolve("Nothing"), None);
+    }
+}
@@ -0,0 +1,97 @@
+//! The parts of a markdown body that are prose rather than code.
+//!
+//! Tags, links and tasks are only read from prose: fenced code blocks
+//! and inline code spans are skipped, so snippets like `C#` or
+//! `a[[0]]` aren't mistaken for them.
+
+use std::ops::Range;
+
+/// The byte ranges of a body outside fenced code blocks and code
+/// spans, in order. A range never spans a line break.
+pub(crate) fn prose(body: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    for (start, line) in unfenced_lines(body) {
+        ranges.extend(
+            line_prose(line)
+                .into_iter()
+                .filter(|range| !range.is_empty())
+                .map(|range| range.start + start..range.end + start),
+        );
+    }
+    ranges
+}
+
+/// The lines of a body outside fenced code blocks, without their line
+/// endings, with the byte offset each starts at.
+pub(crate) fn unfenced_lines(body: &str) -> Vec<(usize, &str)> {
+    let mut lines = Vec::new();
+    let mut fence: Option<(char, usize)> = None;
+    let mut offset = 0;
+
+    for line in body.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+
+        let trimmed = line.trim_start_matches(' ');
+        let marker = trimmed.chars().next().filter(|c| matches!(c, '`' | '~'));
+        if let Some(marker) = marker {
+            let run = trimmed.len() - trimmed.trim_start_matches(marker).len();
+            match fence {
+                Some((open, len)) if open == marker && run >= len => {
+                    fence = None;
+                    continue;
+                }
+                None if run >= 3 => {
+                    fence = Some((marker, run));
+                    continue;
+                }
+                _ => {}
+            }
+        }
+        if fence.is_none() {
+            lines.push((start, line.trim_end_matches(['\r', '\n'])));
+        }
+    }
+    lines
+}
+
+/// The parts of one line outside code spans.
+fn line_prose(line: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut from = 0;
+    let mut pos = 0;
+
+    while let Some(i) = line[pos..].find('`').map(|i| pos + i) {
+        // A code span runs up to the next run of as many backticks,
+        // if there is one.
+        let run = line[i..].len() - line[i..].trim_start_matches('`').len();
+        let ticks = &line[i..i + run];
+        let close = line[i + run..].match_indices(ticks).find(|(j, _)| {
+            let after = i + run + j + run;
+            !line[after..].starts_with('`')
+        });
+        match close {
+            Some((j, _)) => {
+                ranges.push(from..i);
+                from = i + run + j + run;
+                pos = from;
+            }
+            None => pos = i + run,
+        }
+    }
+    ranges.push(from..line.len());
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_code_spans_and_fences() {
+        let body = "a `b` c ``d ` e`` f `g\n```\nh\n```\n~~~\ni\n~~~\nj\r\n";
+        let parts: Vec<&str> =
+            prose(body).into_iter().map(|range| &body[range]).collect();
+        assert_eq!(parts, ["a ", " c ", " f `g", "j"]);
+    }
+}
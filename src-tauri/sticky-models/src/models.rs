@@ -1,8 +1,10 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
 use ts_rs::TS;
 
+/// A note and its content. The fields after `content` are derived from
+/// the file on read and ignored on write.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default, TS)]
 #[serde(default, rename_all = "camelCase")]
 #[ts(export, export_to = "gen_models.ts")]
@@ -13,6 +15,22 @@ pub struct Note {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub content: String,
+    /// The note's tags, from its header and inline hashtags; see
+    /// [`crate::tags`].
+    pub tags: Vec<String>,
+    /// The other names wiki links may use for the note, from its
+    /// header.
+    pub aliases: Vec<String>,
+    /// When the note expires, if it is ephemeral; see
+    /// [`crate::expiry`].
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Whether the note is archived; see [`crate::archive`].
+    pub archived: bool,
+    /// Whether the note is pinned, which lists it ahead of the rest;
+    /// see [`crate::store::NotesStore::pin`].
+    pub pinned: bool,
+    /// The note's color label, if it has one; see [`crate::colors`].
+    pub color: Option<NoteColor>,
 }
 
 impl Note {
@@ -21,6 +39,41 @@ impl Note {
     }
 }
 
+/// What listing a note needs: its identity, timestamps and display
+/// title, read without loading the whole body.
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct NoteSummary {
+    pub id: String,
+    pub title: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// The day this note is the daily note of, if it is one; see
+    /// [`crate::daily`].
+    pub date: Option<NaiveDate>,
+    /// The note's reminder, if it has one; see [`crate::reminders`].
+    pub reminder: Option<Reminder>,
+    /// When the note expires, if it is ephemeral; see
+    /// [`crate::expiry`].
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Whether the note is archived; see [`crate::archive`].
+    pub archived: bool,
+    /// Whether the note is pinned; see [`Note::pinned`].
+    pub pinned: bool,
+    /// The note's color label, if it has one; see [`crate::colors`].
+    pub color: Option<NoteColor>,
+}
+
+/// A tag and how many notes carry it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct TagCount {
+    pub tag: String,
+    pub count: u32,
+}
+
 /// A note matched by a search, with its display title and, for body
 /// matches, an excerpt around the first match.
 #[derive(Debug, Clone, Serialize, TS)]
@@ -32,6 +85,199 @@ pub struct NoteSearchHit {
     pub snippet: Option<String>,
 }
 
+/// A problem in a note file's header that keeps a field from being
+/// read as written.
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct NoteDiagnostic {
+    /// The 1-based line of the file.
+    pub line: u32,
+    pub severity: DiagnosticSeverity,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub enum DiagnosticSeverity {
+    Warning,
+    Error,
+}
+
+/// A note linking to another one, with the line holding the first
+/// such link.
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct Backlink {
+    pub id: String,
+    pub title: String,
+    pub snippet: String,
+}
+
+/// The wiki links between all notes.
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct LinkGraph {
+    /// Every note, newest first.
+    pub nodes: Vec<LinkNode>,
+    /// One edge per linking note and linked note.
+    pub edges: Vec<LinkEdge>,
+    /// Links whose target names no note, once per note and target.
+    pub unresolved: Vec<UnresolvedLink>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct LinkNode {
+    pub id: String,
+    pub title: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct LinkEdge {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct UnresolvedLink {
+    pub from: String,
+    pub target: String,
+}
+
+/// Links left pointing at a note's old title after it was retitled.
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct StaleLinks {
+    pub old_title: String,
+    pub new_title: String,
+    /// The notes holding such links.
+    pub note_ids: Vec<String>,
+}
+
+/// A checklist item in a note: `- [ ] text` or `- [x] text`.
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct Task {
+    pub note_id: String,
+    pub note_title: String,
+    pub task_ref: TaskRef,
+    /// The item's text, without its checkbox and due date.
+    pub text: String,
+    pub done: bool,
+    /// The date of an `@due(YYYY-MM-DD)` in the text.
+    pub due: Option<NaiveDate>,
+    /// How many tasks this one is nested in.
+    pub depth: u32,
+    /// The line of the task this one is nested in, if any.
+    pub parent_line: Option<u32>,
+}
+
+/// Where a task sits in its note, as of when it was read: enough to
+/// find it again after other edits to the note.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct TaskRef {
+    /// The 0-based line of the note's body.
+    pub line: u32,
+    /// The whole text of that line.
+    pub source: String,
+}
+
+/// Which tasks to list; every field left out matches all tasks.
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct TaskFilter {
+    /// Only done tasks, or only open ones.
+    pub done: Option<bool>,
+    /// Only tasks due on or before this date.
+    pub due_by: Option<NaiveDate>,
+}
+
+/// A note template and the prompts to ask before creating a note
+/// from it.
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct NoteTemplate {
+    /// The template file's name, without its extension.
+    pub name: String,
+    pub prompts: Vec<String>,
+}
+
+/// A day that has a daily note.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct DailyNote {
+    pub date: NaiveDate,
+    pub note_id: String,
+}
+
+/// A note's reminder, in local time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct Reminder {
+    pub at: NaiveDateTime,
+    pub repeat: Option<Repeat>,
+    pub snoozed_until: Option<NaiveDateTime>,
+}
+
+impl Reminder {
+    /// When the reminder next fires: the time it is snoozed until, if
+    /// any, or its own.
+    pub fn due(&self) -> NaiveDateTime {
+        self.snoozed_until.unwrap_or(self.at)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub enum Repeat {
+    Daily,
+    Weekdays,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// A note's color label, from a fixed palette.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub enum NoteColor {
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Blue,
+    Purple,
+    Gray,
+}
+
+/// A note moved out of the vault because it expired.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct ExpiredNote {
+    pub id: String,
+    pub title: String,
+}
+
 pub enum ModelType {
     TypeNote,
 }
@@ -5,10 +5,10 @@ use tauri::plugin::TauriPlugin;
 use tauri::{AppHandle, Manager, Runtime};
 
 use crate::store::NotesStore;
-use crate::watcher;
+use crate::{sweeper, watcher};
 
 /// The plugin owning note storage: opens the store and starts the
-/// file watcher.
+/// file watcher and the expired-note sweeper.
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
     tauri::plugin::Builder::new("sticky_models")
         .setup(|app_handle, _api| {
@@ -25,6 +25,7 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
             if let Err(e) = watcher::start(app_handle) {
                 error!("Failed to start notes watcher: {e:?}");
             }
+            sweeper::start(app_handle);
 
             Ok(())
         })
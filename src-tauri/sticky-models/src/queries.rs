@@ -1,18 +1,33 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
 
+use chrono::NaiveDate;
+
 use crate::error::Result;
-use crate::models::{ModelType, Note, NoteSearchHit};
+use crate::models::{
+    Backlink, DailyNote, LinkGraph, ModelType, Note, NoteDiagnostic,
+    NoteSearchHit, NoteSummary, NoteTemplate, StaleLinks, TagCount, Task,
+    TaskFilter, TaskRef,
+};
 use crate::store::NotesStore;
 use nanoid::nanoid;
 use tauri::{AppHandle, Manager, Runtime};
 
-/// List all notes, newest first.
+/// List all notes but the archived ones, newest first.
 pub async fn list_notes<R: Runtime>(
     app_handle: &AppHandle<R>,
 ) -> Result<Vec<Note>> {
     app_handle.state::<NotesStore>().list()
 }
 
+/// Summarize all notes but the archived ones, newest first, without
+/// reading whole bodies.
+pub async fn list_note_summaries<R: Runtime>(
+    app_handle: &AppHandle<R>,
+) -> Result<Vec<NoteSummary>> {
+    app_handle.state::<NotesStore>().summaries()
+}
+
 /// Search notes by title and body, best matches first. An empty
 /// query matches every note, newest first.
 pub async fn search_notes<R: Runtime>(
@@ -22,6 +37,126 @@ pub async fn search_notes<R: Runtime>(
     app_handle.state::<NotesStore>().search(query)
 }
 
+/// List every tag in use with its note count, most used first.
+pub async fn list_tags<R: Runtime>(
+    app_handle: &AppHandle<R>,
+) -> Result<Vec<TagCount>> {
+    app_handle.state::<NotesStore>().tags()
+}
+
+/// Rename a tag and the tags nested in it across all notes, returning
+/// the ids of the notes changed.
+pub async fn rename_tag<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    from: &str,
+    to: &str,
+) -> Result<Vec<String>> {
+    app_handle.state::<NotesStore>().rename_tag(from, to)
+}
+
+/// List the notes linking to a note, newest first.
+pub async fn list_backlinks<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    id: &str,
+) -> Result<Vec<Backlink>> {
+    app_handle.state::<NotesStore>().backlinks(id)
+}
+
+/// Every note and the wiki links between them.
+pub async fn link_graph<R: Runtime>(
+    app_handle: &AppHandle<R>,
+) -> Result<LinkGraph> {
+    app_handle.state::<NotesStore>().link_graph()
+}
+
+/// The links left pointing at a note's old title since it was
+/// retitled, offered once per title change.
+pub async fn take_stale_links<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    id: &str,
+) -> Result<Option<StaleLinks>> {
+    app_handle.state::<NotesStore>().take_stale_links(id)
+}
+
+/// Point the wiki links to one title at another across all notes,
+/// returning the ids of the notes changed.
+pub async fn rewrite_links<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    from: &str,
+    to: &str,
+) -> Result<Vec<String>> {
+    app_handle.state::<NotesStore>().rewrite_links(from, to)
+}
+
+/// List the tasks of all notes that `filter` lets through.
+pub async fn list_tasks<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    filter: &TaskFilter,
+) -> Result<Vec<Task>> {
+    app_handle.state::<NotesStore>().tasks(filter)
+}
+
+/// Tick or untick one task in a note's file.
+pub async fn toggle_task<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    note_id: &str,
+    task: &TaskRef,
+) -> Result<Task> {
+    app_handle.state::<NotesStore>().toggle_task(note_id, task)
+}
+
+/// Archive or unarchive a note, in place.
+pub async fn set_archived<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    note_id: &str,
+    archived: bool,
+) -> Result<bool> {
+    app_handle.state::<NotesStore>().set_archived(note_id, archived)
+}
+
+/// Archive a note, or unarchive an archived one, returning whether it
+/// is now archived.
+pub async fn toggle_archived<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    note_id: &str,
+) -> Result<bool> {
+    app_handle.state::<NotesStore>().toggle_archived(note_id)
+}
+
+/// Pin a note after the ones already pinned.
+pub async fn pin_note<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    note_id: &str,
+) -> Result<bool> {
+    app_handle.state::<NotesStore>().pin(note_id)
+}
+
+/// Unpin a note.
+pub async fn unpin_note<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    note_id: &str,
+) -> Result<bool> {
+    app_handle.state::<NotesStore>().unpin(note_id)
+}
+
+/// Pin a note, or unpin a pinned one, returning whether it is now
+/// pinned.
+pub async fn toggle_pinned<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    note_id: &str,
+) -> Result<bool> {
+    app_handle.state::<NotesStore>().toggle_pinned(note_id)
+}
+
+/// Move a pinned note to a position among the pins.
+pub async fn move_pin<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    note_id: &str,
+    index: usize,
+) -> Result<bool> {
+    app_handle.state::<NotesStore>().move_pin(note_id, index)
+}
+
 /// Read a single note by id.
 pub async fn get_note<R: Runtime>(
     app_handle: &AppHandle<R>,
@@ -30,6 +165,24 @@ pub async fn get_note<R: Runtime>(
     app_handle.state::<NotesStore>().get(id)
 }
 
+/// Summarize a single note by id, leaving the content an editor's
+/// edits are based on alone.
+pub async fn get_note_summary<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    id: &str,
+) -> Result<NoteSummary> {
+    app_handle.state::<NotesStore>().summary(id)
+}
+
+/// Check a note's header for problems that keep fields from being
+/// read.
+pub async fn note_diagnostics<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    id: &str,
+) -> Result<Vec<NoteDiagnostic>> {
+    app_handle.state::<NotesStore>().diagnostics(id)
+}
+
 /// Write a note, creating it when the id is empty.
 pub async fn upsert_note<R: Runtime>(
     app_handle: &AppHandle<R>,
@@ -46,6 +199,15 @@ pub async fn delete_note<R: Runtime>(
     app_handle.state::<NotesStore>().delete(id)
 }
 
+/// Rewrite the file backing the note `id` as UTF-8 with `\n` line
+/// endings.
+pub async fn convert_note_to_utf8<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    id: &str,
+) -> Result<()> {
+    app_handle.state::<NotesStore>().convert_to_utf8(id)
+}
+
 /// The path of the file backing the note `id`.
 pub async fn note_path<R: Runtime>(
     app_handle: &AppHandle<R>,
@@ -59,6 +221,46 @@ pub async fn notes_dir<R: Runtime>(app_handle: &AppHandle<R>) -> PathBuf {
     app_handle.state::<NotesStore>().dir().to_path_buf()
 }
 
+/// List the vault's note templates, by name.
+pub async fn list_templates<R: Runtime>(
+    app_handle: &AppHandle<R>,
+) -> Result<Vec<NoteTemplate>> {
+    app_handle.state::<NotesStore>().templates()
+}
+
+/// The folder holding the vault's note templates, created if needed.
+pub async fn templates_dir<R: Runtime>(
+    app_handle: &AppHandle<R>,
+) -> Result<PathBuf> {
+    app_handle.state::<NotesStore>().templates_dir()
+}
+
+/// Create a note from a template, filling in its variables.
+pub async fn create_from_template<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    template: &str,
+    values: &HashMap<String, String>,
+) -> Result<Note> {
+    app_handle.state::<NotesStore>().create_from_template(template, values)
+}
+
+/// The daily note of a date, created if there is none yet.
+pub async fn daily_note<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    date: NaiveDate,
+) -> Result<Note> {
+    app_handle.state::<NotesStore>().daily_note(date)
+}
+
+/// The days between two dates, both included, that have a daily note.
+pub async fn list_daily_notes<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<DailyNote>> {
+    app_handle.state::<NotesStore>().daily_notes(from, to)
+}
+
 /// Generate a fresh id with the model's prefix, like `note_C7dKUnuR`.
 pub fn generate_model_id(model: ModelType) -> String {
     let id = generate_id();
@@ -0,0 +1,230 @@
+//! Reminders: a `remind:` time in a note's header.
+//!
+//! The time is local, written `2026-10-19 09:30` (a `T` may stand in
+//! for the space, and seconds may follow); a bare date reminds at
+//! [`DEFAULT_HOUR`]. An optional `repeat:` field makes the reminder
+//! recur `daily`, on `weekdays`, `weekly`, `monthly` or `yearly`.
+//!
+//! Snoozing writes a `snoozedUntil:` time next to it, which takes over
+//! until the reminder is dismissed. Dismissing drops both, or, for a
+//! recurring reminder, moves `remind:` on to its next occurrence.
+
+use chrono::{
+    Datelike, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Weekday,
+};
+use sticky_matter::{Document, Value};
+
+use crate::models::{Reminder, Repeat};
+
+/// The frontmatter field holding a note's reminder time.
+pub const REMIND: &str = "remind";
+
+/// The frontmatter field holding how a reminder recurs.
+pub const REPEAT: &str = "repeat";
+
+/// The frontmatter field holding the time a reminder is snoozed until.
+pub const SNOOZED_UNTIL: &str = "snoozedUntil";
+
+/// The hour a reminder given as a bare date fires at.
+pub const DEFAULT_HOUR: u32 = 9;
+
+/// How reminder times are written back.
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// The formats reminder times are read in, besides a bare date.
+pub(crate) const TIME_FORMATS: [&str; 4] = [
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S",
+];
+
+/// The reminder in a note's header, if it has a valid `remind:` time.
+/// An unknown `repeat:` value leaves the reminder one-off.
+pub fn header_reminder(doc: &Document) -> Option<Reminder> {
+    let text = |key| match doc.value(key) {
+        Some(Value::String(s)) => Some(s),
+        _ => None,
+    };
+    Some(Reminder {
+        at: parse_time(&text(REMIND)?)?,
+        repeat: text(REPEAT).and_then(|s| parse_repeat(&s)),
+        snoozed_until: text(SNOOZED_UNTIL).and_then(|s| parse_time(&s)),
+    })
+}
+
+/// Snooze the reminder in a header until `until`. Returns whether the
+/// header has a reminder to snooze.
+pub fn snooze(doc: &mut Document, until: NaiveDateTime) -> bool {
+    if header_reminder(doc).is_none() {
+        return false;
+    }
+    doc.set(SNOOZED_UNTIL, write_time(until));
+    true
+}
+
+/// Dismiss the reminder in a header as of `now`: a recurring one moves
+/// on to its first occurrence after `now`, a one-off one is removed.
+/// Returns whether the header had a reminder to dismiss.
+pub fn dismiss(doc: &mut Document, now: NaiveDateTime) -> bool {
+    let Some(reminder) = header_reminder(doc) else {
+        return false;
+    };
+    doc.remove(SNOOZED_UNTIL);
+    match reminder.repeat {
+        Some(repeat) => {
+            doc.set(REMIND, write_time(next(&reminder, repeat, now)))
+        }
+        None => {
+            doc.remove(REMIND);
+            doc.remove(REPEAT);
+        }
+    }
+    true
+}
+
+/// The first occurrence of a recurring reminder after `after`.
+pub fn next(
+    reminder: &Reminder,
+    repeat: Repeat,
+    after: NaiveDateTime,
+) -> NaiveDateTime {
+    let at = reminder.at;
+    if at > after {
+        return at;
+    }
+    match repeat {
+        Repeat::Daily => at + TimeDelta::days(periods(at, after, 1)),
+        Repeat::Weekly => at + TimeDelta::days(7 * periods(at, after, 7)),
+        Repeat::Weekdays => {
+            let mut next = at + TimeDelta::days(periods(at, after, 1));
+            while matches!(next.weekday(), Weekday::Sat | Weekday::Sun) {
+                next += TimeDelta::days(1);
+            }
+            next
+        }
+        Repeat::Monthly | Repeat::Yearly => {
+            let step = if repeat == Repeat::Monthly { 1 } else { 12 };
+            // Counted from `at` each time, so a reminder on the 31st
+            // comes back to it after shorter months.
+            (1..)
+                .filter_map(|n| at.checked_add_months(Months::new(n * step)))
+                .find(|next| *next > after)
+                .expect("A later month should exist")
+        }
+    }
+}
+
+/// How many periods of `days` days take `at` past `after`.
+fn periods(at: NaiveDateTime, after: NaiveDateTime, days: i64) -> i64 {
+    (after - at).num_days() / days + 1
+}
+
+fn parse_time(text: &str) -> Option<NaiveDateTime> {
+    let text = text.trim();
+    TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+        .or_else(|| {
+            let date = NaiveDate::parse_from_str(text, "%Y-%m-%d").ok()?;
+            let time = NaiveTime::from_hms_opt(DEFAULT_HOUR, 0, 0)?;
+            Some(date.and_time(time))
+        })
+}
+
+fn parse_repeat(text: &str) -> Option<Repeat> {
+    match text.trim().to_lowercase().as_str() {
+        "daily" => Some(Repeat::Daily),
+        "weekdays" => Some(Repeat::Weekdays),
+        "weekly" => Some(Repeat::Weekly),
+        "monthly" => Some(Repeat::Monthly),
+        "yearly" => Some(Repeat::Yearly),
+        _ => None,
+    }
+}
+
+fn write_time(time: NaiveDateTime) -> String {
+    time.format(TIME_FORMAT).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(s: &str) -> NaiveDateTime {
+        parse_time(s).unwrap()
+    }
+
+    #[test]
+    fn reads_reminders() {
+        let doc = Document::parse(
+            "---\nremind: 2026-10-19\nrepeat: Weekdays\n\
+             snoozedUntil: 2026-10-19T09:10\n---\n",
+        );
+        assert_eq!(
+            header_reminder(&doc),
+            Some(Reminder {
+                at: time("2026-10-19 09:00"),
+                repeat: Some(Repeat::Weekdays),
+                snoozed_until: Some(time("2026-10-19 09:10")),
+            })
+        );
+        let doc = Document::parse("---\nremind: soon\n---\n");
+        assert_eq!(header_reminder(&doc), None);
+    }
+
+    #[test]
+    fn finds_the_next_occurrence() {
+        let next = |at: &str, repeat, after: &str| {
+            let reminder = Reminder {
+                at: time(at),
+                repeat: Some(repeat),
+                snoozed_until: None,
+            };
+            super::next(&reminder, repeat, time(after)).to_string()
+        };
+        let at = "2026-10-16 09:00"; // A Friday.
+        assert_eq!(
+            next(at, Repeat::Daily, "2026-10-16 09:00"),
+            "2026-10-17 09:00:00"
+        );
+        assert_eq!(
+            next(at, Repeat::Weekdays, "2026-10-16 10:00"),
+            "2026-10-19 09:00:00"
+        );
+        assert_eq!(
+            next(at, Repeat::Weekly, "2026-10-30 08:00"),
+            "2026-10-30 09:00:00"
+        );
+        assert_eq!(
+            next("2026-01-31 09:00", Repeat::Monthly, "2026-02-28 10:00"),
+            "2026-03-31 09:00:00"
+        );
+        assert_eq!(
+            next(at, Repeat::Yearly, "2026-10-15 00:00"),
+            "2026-10-16 09:00:00"
+        );
+    }
+
+    #[test]
+    fn snoozes_and_dismisses() {
+        let mut doc = Document::parse("---\nremind: 2026-10-19 09:00\n---\n");
+        assert!(snooze(&mut doc, time("2026-10-19 09:10")));
+        assert_eq!(
+            header_reminder(&doc).unwrap().due(),
+            time("2026-10-19 09:10")
+        );
+        assert!(dismiss(&mut doc, time("2026-10-19 09:11")));
+        assert_eq!(doc.render(), "");
+        assert!(!dismiss(&mut doc, time("2026-10-19 09:11")));
+
+        let mut doc = Document::parse(
+            "---\nremind: 2026-10-19 09:00\nrepeat: daily\n---\n",
+        );
+        assert!(dismiss(&mut doc, time("2026-10-21 12:00")));
+        assert_eq!(
+            doc.render(),
+            "---\nremind: 2026-10-22 09:00\nrepeat: daily\n---\n"
+        );
+    }
+}
@@ -0,0 +1,420 @@
+//! Vault-level state kept next to the notes, in a hidden directory.
+//!
+//! The store's own bookkeeping lives in `.sticky/` inside the notes
+//! folder, so it travels with the vault through sync tools and
+//! backups. The directory is a dotfile: scans, the file watcher and
+//! most tools skip it.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use tempfile::NamedTempFile;
+
+use crate::error::{Error, Result};
+use crate::geometry::{Rect, Screen};
+
+/// The hidden directory holding vault-level state.
+pub const STATE_DIR: &str = ".sticky";
+
+/// The vault settings file, inside [`STATE_DIR`].
+const CONFIG_FILE: &str = "config.json";
+
+/// The sidecar index file, inside [`STATE_DIR`].
+const SIDECAR_FILE: &str = "index.json";
+
+/// The pinned notes file, inside [`STATE_DIR`].
+const PINS_FILE: &str = "pins.json";
+
+/// The note window frames file, inside [`STATE_DIR`].
+const FRAMES_FILE: &str = "frames.json";
+
+/// The last session's note windows file, inside [`STATE_DIR`].
+const SESSION_FILE: &str = "session.json";
+
+/// The named window layouts file, inside [`STATE_DIR`].
+const LAYOUTS_FILE: &str = "layouts.json";
+
+/// Per-vault store settings, read from `.sticky/config.json`.
+///
+/// Every setting is optional; a missing or unreadable file means the
+/// defaults.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct StoreConfig {
+    /// Never rewrite files created outside Sticky. Their identity and
+    /// timestamps live in the sidecar index instead of frontmatter.
+    pub non_invasive: bool,
+    /// The template new daily notes start from, by name; see
+    /// [`crate::daily`]. Without one they start with just their date.
+    pub daily_template: Option<String>,
+    /// Archive notes left unedited for this many days; see
+    /// [`crate::archive`]. Unset, notes are only archived by hand.
+    pub archive_after_days: Option<u32>,
+}
+
+impl StoreConfig {
+    /// Read the settings of the vault at `dir`.
+    pub fn load(dir: &Path) -> Self {
+        read_json(&state_path(dir, CONFIG_FILE)).unwrap_or_default()
+    }
+}
+
+/// Identity and timestamps of note files the store must not rewrite,
+/// kept in `.sticky/index.json`.
+///
+/// Entries are keyed by file name and, where the platform has them, by
+/// inode: an inode match follows a file through renames, a name match
+/// through editors that save by replacing the file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Sidecar {
+    entries: Vec<SidecarEntry>,
+}
+
+/// A note file tracked by the sidecar index.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SidecarEntry {
+    /// The file name inside the vault.
+    pub name: String,
+    /// The file's inode when it was last seen, if the platform has
+    /// inodes.
+    pub inode: Option<u64>,
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// The archived state set for the note, which its header can't
+    /// take; see [`crate::archive`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archived: Option<bool>,
+}
+
+impl Sidecar {
+    /// Read the sidecar index of the vault at `dir`.
+    pub fn load(dir: &Path) -> Self {
+        read_json(&state_path(dir, SIDECAR_FILE)).unwrap_or_default()
+    }
+
+    /// Persist the index crash-safely.
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        write_json(&state_path(dir, SIDECAR_FILE), self)
+    }
+
+    /// The entry for a file, matched by inode first and by name
+    /// otherwise.
+    pub fn find(
+        &self,
+        name: &str,
+        inode: Option<u64>,
+    ) -> Option<&SidecarEntry> {
+        let by_inode = inode.and_then(|inode| {
+            self.entries.iter().find(|e| e.inode == Some(inode))
+        });
+        by_inode.or_else(|| self.entries.iter().find(|e| e.name == name))
+    }
+
+    /// Add or replace the entry for `entry.id`, returning whether the
+    /// index changed.
+    pub fn insert(&mut self, entry: SidecarEntry) -> bool {
+        match self.entries.iter_mut().find(|e| e.id == entry.id) {
+            Some(existing) if *existing == entry => false,
+            Some(existing) => {
+                *existing = entry;
+                true
+            }
+            None => {
+                self.entries.push(entry);
+                true
+            }
+        }
+    }
+
+    /// Drop the entry for `id`, returning whether there was one.
+    pub fn remove(&mut self, id: &str) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|e| e.id != id);
+        self.entries.len() != before
+    }
+
+    /// Keep only the entries `keep` accepts, returning whether any
+    /// were dropped.
+    pub fn retain(&mut self, keep: impl FnMut(&SidecarEntry) -> bool) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(keep);
+        self.entries.len() != before
+    }
+}
+
+/// The ids of the pinned notes in their manual order, kept in
+/// `.sticky/pins.json`.
+///
+/// The order lives here rather than in the notes' headers, so moving
+/// a pin rewrites one small file instead of every note it passes.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Pins {
+    ids: Vec<String>,
+}
+
+impl Pins {
+    /// Read the pins of the vault at `dir`.
+    pub fn load(dir: &Path) -> Self {
+        read_json(&state_path(dir, PINS_FILE)).unwrap_or_default()
+    }
+
+    /// Persist the pins crash-safely.
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        write_json(&state_path(dir, PINS_FILE), self)
+    }
+
+    /// Where `id` sits among the pins, if it is pinned.
+    pub fn position(&self, id: &str) -> Option<usize> {
+        self.ids.iter().position(|pinned| pinned == id)
+    }
+
+    /// Pin `id` last, returning whether it wasn't pinned yet.
+    pub fn pin(&mut self, id: &str) -> bool {
+        if self.position(id).is_some() {
+            return false;
+        }
+        self.ids.push(id.to_string());
+        true
+    }
+
+    /// Unpin `id`, returning whether it was pinned.
+    pub fn unpin(&mut self, id: &str) -> bool {
+        let before = self.ids.len();
+        self.ids.retain(|pinned| pinned != id);
+        self.ids.len() != before
+    }
+
+    /// Move the pinned `id` to `index`, or last past the end. Returns
+    /// whether the order changed; an unpinned `id` is left alone.
+    pub fn move_to(&mut self, id: &str, index: usize) -> bool {
+        let Some(from) = self.position(id) else {
+            return false;
+        };
+        let id = self.ids.remove(from);
+        let to = index.min(self.ids.len());
+        self.ids.insert(to, id);
+        from != to
+    }
+}
+
+/// The last window frame of each note, keyed by note id, kept in
+/// `.sticky/frames.json`.
+///
+/// Note windows live in recycled `main_N` slots, so their geometry is
+/// remembered per note rather than per window: reopening a note puts
+/// its window back where it was.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Frames {
+    frames: BTreeMap<String, WindowFrame>,
+}
+
+/// Where a note's window was and how it was sized.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowFrame {
+    /// The window's outer position and inner size, in logical pixels.
+    #[serde(flatten)]
+    pub bounds: Rect,
+    /// Whether the window was resized by hand, which stops it from
+    /// following the height of the note's content.
+    pub manually_sized: bool,
+}
+
+impl Frames {
+    /// Read the window frames of the vault at `dir`.
+    pub fn load(dir: &Path) -> Self {
+        read_json(&state_path(dir, FRAMES_FILE)).unwrap_or_default()
+    }
+
+    /// Persist the frames crash-safely.
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        write_json(&state_path(dir, FRAMES_FILE), self)
+    }
+
+    /// The last frame of `id`'s window, if it had one.
+    pub fn get(&self, id: &str) -> Option<WindowFrame> {
+        self.frames.get(id).copied()
+    }
+
+    /// Remember `frame` for `id`, returning whether it differs from the
+    /// one remembered.
+    pub fn set(&mut self, id: &str, frame: WindowFrame) -> bool {
+        self.frames.insert(id.to_string(), frame) != Some(frame)
+    }
+
+    /// Forget the frame of `id`, returning whether there was one.
+    pub fn remove(&mut self, id: &str) -> bool {
+        self.frames.remove(id).is_some()
+    }
+}
+
+/// The note windows open when the app last quit, kept in
+/// `.sticky/session.json` and reopened on the next launch.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    /// The windows back to front: the last one was on top.
+    pub windows: Vec<SessionWindow>,
+}
+
+/// A note window of the last session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionWindow {
+    pub note_id: String,
+    pub frame: WindowFrame,
+    /// Whether the window was showing, rather than hidden through the
+    /// tray.
+    pub visible: bool,
+}
+
+impl Session {
+    /// Read the last session of the vault at `dir`.
+    pub fn load(dir: &Path) -> Self {
+        read_json(&state_path(dir, SESSION_FILE)).unwrap_or_default()
+    }
+
+    /// Persist the session crash-safely.
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        write_json(&state_path(dir, SESSION_FILE), self)
+    }
+}
+
+/// Named sets of note windows to put on screen together, kept in
+/// `.sticky/layouts.json` in the order they were first saved.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Layouts {
+    layouts: Vec<Layout>,
+}
+
+/// A named set of note windows and where they were.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Layout {
+    pub name: String,
+    /// The windows back to front.
+    pub windows: Vec<LayoutWindow>,
+}
+
+/// A note window of a layout.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LayoutWindow {
+    pub note_id: String,
+    pub frame: WindowFrame,
+    /// The monitor the window was on, as it was then; see
+    /// [`crate::geometry::relocate`].
+    pub screen: Screen,
+}
+
+impl Layouts {
+    /// Read the layouts of the vault at `dir`.
+    pub fn load(dir: &Path) -> Self {
+        read_json(&state_path(dir, LAYOUTS_FILE)).unwrap_or_default()
+    }
+
+    /// Persist the layouts crash-safely.
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        write_json(&state_path(dir, LAYOUTS_FILE), self)
+    }
+
+    /// The layouts' names, in order.
+    pub fn names(&self) -> Vec<String> {
+        self.layouts.iter().map(|layout| layout.name.clone()).collect()
+    }
+
+    /// The layout called `name`.
+    pub fn get(&self, name: &str) -> Option<&Layout> {
+        self.layouts.iter().find(|layout| layout.name == name)
+    }
+
+    /// Add `layout`, replacing the one of the same name in place.
+    pub fn insert(&mut self, layout: Layout) {
+        match self.layouts.iter_mut().find(|l| l.name == layout.name) {
+            Some(existing) => *existing = layout,
+            None => self.layouts.push(layout),
+        }
+    }
+
+    /// Drop the layout called `name`, returning whether there was one.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let before = self.layouts.len();
+        self.layouts.retain(|layout| layout.name != name);
+        self.layouts.len() != before
+    }
+
+    /// The first of "Layout 1", "Layout 2", ... not taken yet.
+    pub fn next_name(&self) -> String {
+        (1..)
+            .map(|n| format!("Layout {n}"))
+            .find(|name| self.get(name).is_none())
+            .expect("Layout names should not run out")
+    }
+}
+
+/// The inode of a file, where the platform has them.
+pub fn inode(meta: &fs::Metadata) -> Option<u64> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        Some(meta.ino())
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = meta;
+        None
+    }
+}
+
+/// The path of a state file of the vault at `dir`.
+pub fn state_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(STATE_DIR).join(name)
+}
+
+/// Read a JSON state file. Missing files are `None` silently; corrupt
+/// ones are `None` with a warning, so a bad hand edit never locks the
+/// user out of their notes.
+pub fn read_json<T: for<'de> Deserialize<'de>>(path: &Path) -> Option<T> {
+    let text = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&text)
+        .inspect_err(|e| warn!("Ignoring unreadable state file {path:?}: {e}"))
+        .ok()
+}
+
+/// Write a JSON state file crash-safely, creating the state directory
+/// if needed.
+pub fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let mut contents = serde_json::to_string_pretty(value)?;
+    contents.push('\n');
+    write_atomic(path, contents.as_bytes())
+}
+
+/// Write file contents crash-safely: temp file in the same directory,
+/// fsync, atomic rename.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let dir = path.parent().ok_or_else(|| {
+        Error::GenericError(format!("Path has no parent: {path:?}"))
+    })?;
+
+    let mut file = NamedTempFile::new_in(dir)?;
+    file.write_all(contents)?;
+    file.as_file().sync_all()?;
+    file.persist(path).map_err(|e| Error::from(e.error))?;
+    Ok(())
+}
@@ -1,21 +1,40 @@
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 use std::ffi::OsString;
-use std::fs;
+use std::fs::{self, File};
 use std::hash::{DefaultHasher, Hash, Hasher};
-use std::io::Write;
+use std::io::{self, BufRead, BufReader};
 use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 use std::time::SystemTime;
 
-use chrono::{DateTime, SecondsFormat, Utc};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, SecondsFormat, Utc};
 use log::warn;
-use sticky_matter::Document;
-use tempfile::NamedTempFile;
+use sticky_matter::{Document, Severity};
 
+use crate::archive::{self, header_archived, is_archived};
+use crate::colors::{self, header_color};
 use crate::constants::MAX_TITLE_LEN;
+use crate::daily::{self, header_date};
+use crate::encoding::{decode, encode, utf8_start, FileFormat};
 use crate::error::{Error, Result};
-use crate::models::{ModelType, Note, NoteSearchHit};
+use crate::expiry::{expires_at, EXPIRED_DIR, EXPIRES, EXPIRE_AFTER};
+use crate::links::{self, aliases, Resolver};
+use crate::models::{
+    Backlink, DailyNote, DiagnosticSeverity, ExpiredNote, LinkEdge, LinkGraph,
+    LinkNode, ModelType, Note, NoteColor, NoteDiagnostic, NoteSearchHit,
+    NoteSummary, NoteTemplate, StaleLinks, TagCount, Task, TaskFilter, TaskRef,
+    UnresolvedLink,
+};
 use crate::queries::generate_model_id;
+use crate::reminders::{self, header_reminder};
+use crate::state::{
+    inode, write_atomic, Frames, Pins, Sidecar, SidecarEntry, StoreConfig,
+    WindowFrame,
+};
+use crate::tags::{self, note_tags};
+use crate::tasks::{self, note_tasks};
+use crate::templates::{self, TEMPLATES_DIR};
 
 /// The longest filename slug derived from a note's first line.
 const MAX_SLUG_LEN: usize = 60;
@@ -37,6 +56,13 @@ const UPDATED_AT: &str = "updatedAt";
 /// A note's identity is the `id` in its frontmatter, never its path.
 /// Filenames follow the note's first line and are purely cosmetic, so
 /// external renames break nothing.
+///
+/// Files are read in whatever encoding and line endings they were
+/// written with and saved back the same way; see [`crate::encoding`].
+///
+/// In non-invasive mode (see [`StoreConfig`]), files created outside
+/// Sticky are never given a header: the sidecar index holds their
+/// identity instead, and they stay byte-identical until edited here.
 pub struct NotesStore {
     dir: PathBuf,
     index: Mutex<HashMap<String, PathBuf>>,
@@ -50,30 +76,90 @@ pub struct NotesStore {
     /// bytes on disk knows an external edit is about to be overwritten
     /// and saves it as a conflict copy first.
     bases: Mutex<HashMap<String, u64>>,
+    /// The title each note had before upserts changed it, per note id,
+    /// until [`Self::take_stale_links`] offers to update the links to
+    /// it. Typing a title saves many times; links only care about
+    /// where it started.
+    retitled: Mutex<HashMap<String, String>>,
     /// Parsed notes keyed by path, tagged with the file's mtime and
     /// size. A scan only reads and parses files whose tag changed, so
     /// listing and searching cost a `stat` per file, not a read.
-    cache: Mutex<HashMap<PathBuf, CachedNote>>,
+    cache: Mutex<HashMap<PathBuf, Cached<Parsed>>>,
+    /// Note summaries keyed by path, tagged like `cache`. Summaries
+    /// come from a file's header and first body line alone, so a
+    /// changed multi-megabyte note costs a few lines to relist.
+    heads: Mutex<HashMap<PathBuf, Cached<NoteSummary>>>,
+    /// Identities of files adopted without rewriting them; `Some` only
+    /// in non-invasive mode.
+    sidecar: Option<Mutex<Sidecar>>,
+    /// The template new daily notes start from, by name.
+    daily_template: Option<String>,
+    /// After how many days unedited notes are archived, if ever.
+    archive_after_days: Option<u32>,
+    /// The pinned notes, in their manual order.
+    pins: Mutex<Pins>,
+    /// The last window frame of each note.
+    frames: Mutex<Frames>,
+    /// Held while looking up or creating a daily note, so two callers
+    /// asking for the same day don't both create it.
+    daily: Mutex<()>,
+    /// Held across every read, change and write back of a note file,
+    /// so two edits through the store can't interleave and drop one.
+    edits: Mutex<()>,
+    /// The ids of the notes expired since the store was opened. Saving
+    /// one is refused, so an editor's late autosave can't bring it back
+    /// as a new file.
+    expired: Mutex<HashSet<String>>,
+}
+
+/// A fully read note and its summary, which needs header fields the
+/// note doesn't keep.
+#[derive(Clone)]
+struct Parsed {
+    note: Note,
+    summary: NoteSummary,
 }
 
-/// A parsed note plus the file identity it was read at.
+/// A value parsed from a file plus the file identity it was read at.
 #[derive(Clone)]
-struct CachedNote {
+struct Cached<T> {
     modified: SystemTime,
     len: u64,
-    note: Note,
+    value: T,
 }
 
 impl NotesStore {
-    /// Open the store, creating the directory if needed.
+    /// Open the store with the vault's own settings, creating the
+    /// directory if needed.
     pub fn open(dir: PathBuf) -> Result<Self> {
+        let config = StoreConfig::load(&dir);
+        Self::open_with(dir, config)
+    }
+
+    /// Open the store with explicit settings, creating the directory
+    /// if needed.
+    pub fn open_with(dir: PathBuf, config: StoreConfig) -> Result<Self> {
         fs::create_dir_all(&dir)?;
+        let sidecar =
+            config.non_invasive.then(|| Mutex::new(Sidecar::load(&dir)));
+        let pins = Mutex::new(Pins::load(&dir));
+        let frames = Mutex::new(Frames::load(&dir));
         let store = Self {
             dir,
             index: Mutex::new(HashMap::new()),
             writes: Mutex::new(HashMap::new()),
             bases: Mutex::new(HashMap::new()),
+            retitled: Mutex::new(HashMap::new()),
             cache: Mutex::new(HashMap::new()),
+            heads: Mutex::new(HashMap::new()),
+            sidecar,
+            daily_template: config.daily_template,
+            archive_after_days: config.archive_after_days,
+            pins,
+            frames,
+            daily: Mutex::new(()),
+            edits: Mutex::new(()),
+            expired: Mutex::new(HashSet::new()),
         };
         store.scan()?;
         Ok(store)
@@ -84,28 +170,164 @@ impl NotesStore {
         &self.dir
     }
 
-    /// Read every note from disk, newest first.
+    /// Read every note from disk but the archived ones: the pinned
+    /// ones first, in their manual order, then the rest newest first.
     pub fn list(&self) -> Result<Vec<Note>> {
+        let mut notes = self.list_all()?;
+        notes.retain(|note| !note.archived);
+        Ok(notes)
+    }
+
+    /// Read every note from disk, archived ones included, in the order
+    /// of [`Self::list`].
+    pub fn list_all(&self) -> Result<Vec<Note>> {
         let mut notes = self.scan()?;
+        let pins = self.pins.lock().unwrap();
+        for note in &mut notes {
+            note.pinned = pins.position(&note.id).is_some();
+        }
         notes.sort_by(|a, b| {
-            b.updated_at.cmp(&a.updated_at).then_with(|| a.id.cmp(&b.id))
+            listing_order(&pins, (&a.id, a.updated_at), (&b.id, b.updated_at))
         });
         Ok(notes)
     }
 
+    /// Summarize every note on disk but the archived ones, in the
+    /// order of [`Self::list`].
+    ///
+    /// Reads only the header and first body line of files changed
+    /// since they were last seen, so it stays fast on vaults with huge
+    /// notes where [`Self::list`] would read them whole.
+    pub fn summaries(&self) -> Result<Vec<NoteSummary>> {
+        let mut summaries = self.all_summaries()?;
+        summaries.retain(|summary| !summary.archived);
+        Ok(summaries)
+    }
+
+    /// Summarize every note on disk, archived ones included, in the
+    /// order of [`Self::list`].
+    pub fn all_summaries(&self) -> Result<Vec<NoteSummary>> {
+        let mut summaries = self.scan_summaries()?;
+        let pins = self.pins.lock().unwrap();
+        for summary in &mut summaries {
+            summary.pinned = pins.position(&summary.id).is_some();
+        }
+        summaries.sort_by(|a, b| {
+            listing_order(&pins, (&a.id, a.updated_at), (&b.id, b.updated_at))
+        });
+        Ok(summaries)
+    }
+
+    /// Pin a note, after the ones already pinned. Returns whether it
+    /// wasn't pinned yet.
+    pub fn pin(&self, id: &str) -> Result<bool> {
+        self.path(id)?;
+        self.edit_pins(|pins| pins.pin(id))
+    }
+
+    /// Unpin a note, returning whether it was pinned.
+    pub fn unpin(&self, id: &str) -> Result<bool> {
+        self.edit_pins(|pins| pins.unpin(id))
+    }
+
+    /// Pin a note, or unpin it if pinned, and return whether it is now
+    /// pinned. The pins are read and changed under one lock.
+    pub fn toggle_pinned(&self, id: &str) -> Result<bool> {
+        self.path(id)?;
+        let mut pinned = false;
+        self.edit_pins(|pins| {
+            pinned = !pins.unpin(id);
+            if pinned {
+                pins.pin(id);
+            }
+            true
+        })?;
+        Ok(pinned)
+    }
+
+    /// Move a pinned note to `index` among the pins, or last past the
+    /// end. Returns whether the order changed; unpinned notes are left
+    /// alone.
+    pub fn move_pin(&self, id: &str, index: usize) -> Result<bool> {
+        self.edit_pins(|pins| pins.move_to(id, index))
+    }
+
+    fn is_pinned(&self, id: &str) -> bool {
+        self.pins.lock().unwrap().position(id).is_some()
+    }
+
+    /// Apply `edit` to the pins and save them, if it reports a change.
+    fn edit_pins(&self, edit: impl FnOnce(&mut Pins) -> bool) -> Result<bool> {
+        let mut pins = self.pins.lock().unwrap();
+        if !edit(&mut pins) {
+            return Ok(false);
+        }
+        pins.save(&self.dir)?;
+        Ok(true)
+    }
+
+    /// The last frame of a note's window, if it was ever open.
+    pub fn window_frame(&self, id: &str) -> Option<WindowFrame> {
+        self.frames.lock().unwrap().get(id)
+    }
+
+    /// Remember the frame of a note's window, saving it if it changed.
+    pub fn set_window_frame(&self, id: &str, frame: WindowFrame) -> Result<()> {
+        self.path(id)?;
+        let mut frames = self.frames.lock().unwrap();
+        if frames.set(id, frame) {
+            frames.save(&self.dir)?;
+        }
+        Ok(())
+    }
+
     /// Search notes by title and body, best matches first.
     ///
     /// Every whitespace-separated term must appear somewhere in the
     /// note (case-insensitively); title hits weigh more than body
-    /// hits, and the newest-first order of [`Self::list`] breaks ties.
-    /// An empty query matches every note.
+    /// hits, and the order of [`Self::list`] breaks ties, so an empty
+    /// query lists pinned notes first.
+    /// A `#tag` term instead requires the tag or one nested in it,
+    /// and a `color:` term the color label (see [`crate::colors`]).
+    /// Archived notes are left out, unless an [`archive::SEARCH_TERM`]
+    /// asks for them alone. An empty query matches every note.
     pub fn search(&self, query: &str) -> Result<Vec<NoteSearchHit>> {
-        let notes = self.list()?;
-        let terms: Vec<String> =
-            query.split_whitespace().map(str::to_lowercase).collect();
+        let notes = self.list_all()?;
+        let (filters, terms): (Vec<String>, Vec<String>) =
+            query.split_whitespace().map(str::to_lowercase).partition(|term| {
+                term.starts_with('#')
+                    || term.starts_with(colors::SEARCH_PREFIX)
+                    || term == archive::SEARCH_TERM
+            });
+        let archived = filters.iter().any(|t| t == archive::SEARCH_TERM);
+        let tag_terms = filters.iter().filter(|t| t.starts_with('#'));
+        let wanted: Vec<String> =
+            tag_terms.filter_map(|t| tags::normalize(t)).collect();
+        // An unknown color matches no note, rather than being ignored.
+        let colors: Vec<Option<NoteColor>> = filters
+            .iter()
+            .filter_map(|t| t.strip_prefix(colors::SEARCH_PREFIX))
+            .map(colors::parse)
+            .collect();
 
         let mut hits: Vec<(u32, NoteSearchHit)> = Vec::new();
         for note in notes {
+            if note.archived != archived {
+                continue;
+            }
+            if colors
+                .iter()
+                .any(|color| color.is_none() || *color != note.color)
+            {
+                continue;
+            }
+            let tagged = wanted.iter().all(|wanted| {
+                note.tags.iter().any(|tag| tags::is_within(tag, wanted))
+            });
+            if !tagged {
+                continue;
+            }
+
             let title = note_title(&note.content);
             let Some(score) = match_score(&title, &note.content, &terms) else {
                 continue;
@@ -121,15 +343,393 @@ impl NotesStore {
         Ok(hits.into_iter().map(|(_, hit)| hit).collect())
     }
 
+    /// Every tag in use with the number of notes carrying it, most
+    /// used first.
+    pub fn tags(&self) -> Result<Vec<TagCount>> {
+        let mut counts: HashMap<String, u32> = HashMap::new();
+        for note in self.scan()? {
+            for tag in note.tags {
+                *counts.entry(tag).or_default() += 1;
+            }
+        }
+
+        let mut tags: Vec<TagCount> = counts
+            .into_iter()
+            .map(|(tag, count)| TagCount { tag, count })
+            .collect();
+        tags.sort_by(|a, b| b.count.cmp(&a.count).then(a.tag.cmp(&b.tag)));
+        Ok(tags)
+    }
+
+    /// Rename the tag `from` and the tags nested in it to `to` across
+    /// the vault, in headers and inline, returning the ids of the notes
+    /// changed.
+    ///
+    /// Files are rewritten in place without touching their timestamps
+    /// or names. An editor with unsaved changes to one of them gets a
+    /// conflict copy on its next save, as for any external edit.
+    pub fn rename_tag(&self, from: &str, to: &str) -> Result<Vec<String>> {
+        let invalid = |tag: &str| {
+            Error::GenericError(format!("Invalid tag name: {tag:?}"))
+        };
+        let from = tags::normalize(from).ok_or_else(|| invalid(from))?;
+        tags::normalize(to).ok_or_else(|| invalid(to))?;
+        let to = to.trim().trim_start_matches('#');
+
+        let mut renamed = Vec::new();
+        for note in self.scan()? {
+            if !note.tags.iter().any(|tag| tags::is_within(tag, &from)) {
+                continue;
+            }
+            let Some(path) = self.lookup(&note.id) else {
+                continue;
+            };
+
+            let _edit = self.edits.lock().unwrap();
+            let (text, format) = read_text(&path)?;
+            let mut doc = Document::parse(&text);
+            if tags::rename(&mut doc, &from, to) {
+                self.write(&path, &doc.render(), format)?;
+                renamed.push(note.id);
+            }
+        }
+        Ok(renamed)
+    }
+
+    /// The notes linking to the note `id`, newest first.
+    pub fn backlinks(&self, id: &str) -> Result<Vec<Backlink>> {
+        let notes = self.list_all()?;
+        let resolver = resolver(&notes);
+
+        let mut backlinks = Vec::new();
+        for note in notes.iter().filter(|note| note.id != id) {
+            let link = links::links(&note.content)
+                .into_iter()
+                .find(|link| resolver.resolve(&link.target) == Some(id));
+            if let Some(link) = link {
+                backlinks.push(Backlink {
+                    id: note.id.clone(),
+                    title: note_title(&note.content),
+                    snippet: line_around(&note.content, link.range.start),
+                });
+            }
+        }
+        Ok(backlinks)
+    }
+
+    /// Every note and the wiki links between them.
+    pub fn link_graph(&self) -> Result<LinkGraph> {
+        let notes = self.list_all()?;
+        let resolver = resolver(&notes);
+
+        let mut graph =
+            LinkGraph { nodes: vec![], edges: vec![], unresolved: vec![] };
+        for note in &notes {
+            graph.nodes.push(LinkNode {
+                id: note.id.clone(),
+                title: note_title(&note.content),
+            });
+
+            for link in links::links(&note.content) {
+                match resolver.resolve(&link.target) {
+                    Some(to) if to == note.id => {}
+                    Some(to) => {
+                        let edge =
+                            LinkEdge { from: note.id.clone(), to: to.into() };
+                        if !graph.edges.contains(&edge) {
+                            graph.edges.push(edge);
+                        }
+                    }
+                    None => {
+                        let target = link.target;
+                        let known = graph.unresolved.iter().any(|u| {
+                            u.from == note.id
+                                && links::same_name(&u.target, &target)
+                        });
+                        if !known {
+                            let from = note.id.clone();
+                            graph
+                                .unresolved
+                                .push(UnresolvedLink { from, target });
+                        }
+                    }
+                }
+            }
+        }
+        Ok(graph)
+    }
+
+    /// The links left pointing at the note `id`'s old title since its
+    /// title changed, if there are any and they can be updated.
+    ///
+    /// Each title change is offered once: the old title is forgotten
+    /// whatever the answer, so asking again returns `None` until the
+    /// title changes again.
+    pub fn take_stale_links(&self, id: &str) -> Result<Option<StaleLinks>> {
+        let Some(old_title) = self.retitled.lock().unwrap().remove(id) else {
+            return Ok(None);
+        };
+        let notes = self.list_all()?;
+        let Some(note) = notes.iter().find(|note| note.id == id) else {
+            return Ok(None);
+        };
+        let new_title = note_title(&note.content);
+
+        // Links still resolving, to an alias of this note or to
+        // another note now holding the title, are left alone.
+        let resolver = resolver(&notes);
+        if links::same_name(&old_title, &new_title)
+            || !links::is_linkable(&new_title)
+            || resolver.resolve(&old_title).is_some()
+        {
+            return Ok(None);
+        }
+
+        let note_ids: Vec<String> = notes
+            .iter()
+            .filter(|note| {
+                links::links(&note.content)
+                    .iter()
+                    .any(|link| links::same_name(&link.target, &old_title))
+            })
+            .map(|note| note.id.clone())
+            .collect();
+        Ok((!note_ids.is_empty()).then_some(StaleLinks {
+            old_title,
+            new_title,
+            note_ids,
+        }))
+    }
+
+    /// Point the wiki links to the title `from` at `to` across the
+    /// vault, keeping their headings and labels, and return the ids of
+    /// the notes changed.
+    ///
+    /// Like [`Self::rename_tag`], files are rewritten in place without
+    /// touching their timestamps or names.
+    pub fn rewrite_links(&self, from: &str, to: &str) -> Result<Vec<String>> {
+        if !links::is_linkable(to) {
+            return Err(Error::GenericError(format!(
+                "Not a valid link target: {to:?}"
+            )));
+        }
+
+        let mut rewritten = Vec::new();
+        for note in self.scan()? {
+            let stale = links::links(&note.content)
+                .iter()
+                .any(|link| links::same_name(&link.target, from));
+            let Some(path) = self.lookup(&note.id).filter(|_| stale) else {
+                continue;
+            };
+
+            let _edit = self.edits.lock().unwrap();
+            let (text, format) = read_text(&path)?;
+            let mut doc = Document::parse(&text);
+            if let Some(body) = links::retarget(doc.body(), from, to) {
+                doc.set_body(body);
+                self.write(&path, &doc.render(), format)?;
+                rewritten.push(note.id);
+            }
+        }
+        Ok(rewritten)
+    }
+
+    /// Every task in the vault that `filter` lets through: notes
+    /// newest first, each note's tasks in body order.
+    ///
+    /// Tasks come from the notes a scan has cached, so listing them
+    /// again only rereads the notes changed since.
+    pub fn tasks(&self, filter: &TaskFilter) -> Result<Vec<Task>> {
+        let mut tasks = Vec::new();
+        for note in self.list()? {
+            let title = note_title(&note.content);
+            tasks.extend(
+                note_tasks(&note.id, &title, &note.content).into_iter().filter(
+                    |task| {
+                        filter.done.is_none_or(|done| task.done == done)
+                            && filter.due_by.is_none_or(|by| {
+                                task.due.is_some_and(|due| due <= by)
+                            })
+                    },
+                ),
+            );
+        }
+        Ok(tasks)
+    }
+
+    /// Tick or untick one task in the note `note_id`'s file, returning
+    /// the task as it now is.
+    ///
+    /// Only that checkbox changes; the file is otherwise written back
+    /// as read, without touching its timestamps or name. The task is
+    /// found again in the file as it is now (see [`tasks::toggle`]),
+    /// and saves and other edits through the store wait until it is
+    /// written back.
+    pub fn toggle_task(&self, note_id: &str, task: &TaskRef) -> Result<Task> {
+        let _edit = self.edits.lock().unwrap();
+        let path = self.path(note_id)?;
+        let (text, format) = read_text(&path)?;
+        let mut doc = Document::parse(&text);
+        let Some((body, line)) = tasks::toggle(doc.body(), task) else {
+            return Err(Error::GenericError(format!(
+                "Task not found in note {note_id}: {:?}",
+                task.source
+            )));
+        };
+        doc.set_body(body);
+        self.write(&path, &doc.render(), format)?;
+
+        let body = doc.body();
+        let title = note_title(body);
+        let toggled = note_tasks(note_id, &title, body)
+            .into_iter()
+            .find(|task| task.task_ref.line as usize == line);
+        toggled.ok_or_else(|| {
+            Error::GenericError("Toggled task vanished".to_string())
+        })
+    }
+
+    /// Snooze the reminder of the note `note_id` until `until`, local
+    /// time. Returns whether the note has a reminder to snooze.
+    ///
+    /// Like [`Self::rename_tag`], this rewrites the file's header in
+    /// place, leaving its timestamps and name alone.
+    pub fn snooze_reminder(
+        &self,
+        note_id: &str,
+        until: NaiveDateTime,
+    ) -> Result<bool> {
+        self.edit_header(note_id, |doc| reminders::snooze(doc, until))
+    }
+
+    /// Dismiss the reminder of the note `note_id` as of `now`, local
+    /// time; see [`reminders::dismiss`]. Returns whether the note had
+    /// a reminder to dismiss.
+    pub fn dismiss_reminder(
+        &self,
+        note_id: &str,
+        now: NaiveDateTime,
+    ) -> Result<bool> {
+        self.edit_header(note_id, |doc| reminders::dismiss(doc, now))
+    }
+
+    /// Archive or unarchive the note `note_id`; see [`crate::archive`].
+    /// Returns whether its state changed.
+    ///
+    /// Like [`Self::rename_tag`], this rewrites the file's header in
+    /// place, so archiving doesn't count as an edit. Files the sidecar
+    /// index tracks keep the state there instead.
+    pub fn set_archived(&self, note_id: &str, archived: bool) -> Result<bool> {
+        self.edit_archived(note_id, |_| Some(archived))
+    }
+
+    /// Archive the note `note_id`, or unarchive it if archived, and
+    /// return whether it is now archived.
+    ///
+    /// The state is read and flipped under one lock, so a concurrent
+    /// sweep or window can't make it write a stale state.
+    pub fn toggle_archived(&self, note_id: &str) -> Result<bool> {
+        let mut archived = false;
+        self.edit_archived(note_id, |state| {
+            archived = state != Some(true);
+            Some(archived)
+        })?;
+        Ok(archived)
+    }
+
+    /// Archive the notes left unedited for the vault's
+    /// `archiveAfterDays` as of `now`, returning their ids. Does
+    /// nothing without that setting.
+    pub fn archive_idle(&self, now: DateTime<Utc>) -> Result<Vec<String>> {
+        let Some(days) = self.archive_after_days else {
+            return Ok(Vec::new());
+        };
+        let mut archived = Vec::new();
+        for summary in self.all_summaries()? {
+            let idle = |archived| {
+                archive::is_idle(archived, summary.updated_at, now, days)
+            };
+            if summary.archived || !idle(None) {
+                continue;
+            }
+            // A note unarchived by hand is left alone too, which only
+            // its stored state tells.
+            let archive = |state| idle(state).then_some(true);
+            if self.edit_archived(&summary.id, archive)? {
+                archived.push(summary.id);
+            }
+        }
+        Ok(archived)
+    }
+
+    /// Apply `archive` to a note's archived state, `None` when it has
+    /// none, and store the state it returns, if any. Returns whether
+    /// the state changed.
+    ///
+    /// Files the sidecar index tracks keep the state there, as their
+    /// headers are never touched; others keep it in their header.
+    fn edit_archived(
+        &self,
+        note_id: &str,
+        archive: impl FnOnce(Option<bool>) -> Option<bool>,
+    ) -> Result<bool> {
+        let path = self.path(note_id)?;
+        {
+            let _edit = self.edits.lock().unwrap();
+            let tracked = self.tracked(&path).filter(|e| e.id == note_id);
+            if let Some(entry) = tracked {
+                let doc = read_head(&path)?;
+                let state = entry.archived.or_else(|| header_archived(&doc));
+                let Some(archived) = archive(state) else {
+                    return Ok(false);
+                };
+                if state == Some(archived) {
+                    return Ok(false);
+                }
+                let archived = Some(archived);
+                self.track_at(&path, SidecarEntry { archived, ..entry })?;
+                // The file didn't change, so its cached reads are stale.
+                self.cache.lock().unwrap().remove(&path);
+                self.heads.lock().unwrap().remove(&path);
+                return Ok(true);
+            }
+        }
+
+        self.edit_header(note_id, |doc| match archive(header_archived(doc)) {
+            Some(archived) => archive::set_archived(doc, archived),
+            None => false,
+        })
+    }
+
+    /// Apply `edit` to a note's header and write it back in place, if
+    /// `edit` reports a change. Saves and other edits through the store
+    /// wait until it is written back.
+    fn edit_header(
+        &self,
+        note_id: &str,
+        edit: impl FnOnce(&mut Document) -> bool,
+    ) -> Result<bool> {
+        let _edit = self.edits.lock().unwrap();
+        let path = self.path(note_id)?;
+        let (text, format) = read_text(&path)?;
+        let mut doc = Document::parse(&text);
+        if !edit(&mut doc) {
+            return Ok(false);
+        }
+        self.write(&path, &doc.render(), format)?;
+        Ok(true)
+    }
+
     /// Read a single note by id.
     pub fn get(&self, id: &str) -> Result<Note> {
-        let note = match self.lookup(id).and_then(|p| read_note(&p, id)) {
+        let note = match self.lookup(id).and_then(|p| self.read_note(&p, id)) {
             Some(note) => note,
             None => {
                 // The file may have moved or changed under us; rescan.
                 self.scan()?;
                 self.lookup(id)
-                    .and_then(|p| read_note(&p, id))
+                    .and_then(|p| self.read_note(&p, id))
                     .ok_or_else(|| Error::ModelNotFound(id.to_string()))?
             }
         };
@@ -143,6 +743,45 @@ impl NotesStore {
         Ok(note)
     }
 
+    /// Summarize a single note by id.
+    ///
+    /// Unlike [`Self::get`], this doesn't serve content to edit, so an
+    /// editor's next save still spots external edits made before it.
+    pub fn summary(&self, id: &str) -> Result<NoteSummary> {
+        let read = |path: PathBuf| {
+            self.adopt_summary(&path).ok().filter(|summary| summary.id == id)
+        };
+        let mut summary = match self.lookup(id).and_then(read) {
+            Some(summary) => summary,
+            None => {
+                self.scan_summaries()?;
+                self.lookup(id)
+                    .and_then(read)
+                    .ok_or_else(|| Error::ModelNotFound(id.to_string()))?
+            }
+        };
+        summary.pinned = self.is_pinned(id);
+        Ok(summary)
+    }
+
+    /// Check the header of the note `id`'s file for problems that keep
+    /// fields from being read, like duplicate keys or unclosed quotes.
+    pub fn diagnostics(&self, id: &str) -> Result<Vec<NoteDiagnostic>> {
+        let (text, _) = read_text(&self.path(id)?)?;
+        let diagnostics = Document::diagnostics(&text)
+            .into_iter()
+            .map(|d| NoteDiagnostic {
+                line: d.line as u32,
+                severity: match d.severity {
+                    Severity::Warning => DiagnosticSeverity::Warning,
+                    Severity::Error => DiagnosticSeverity::Error,
+                },
+                message: d.message,
+            })
+            .collect();
+        Ok(diagnostics)
+    }
+
     /// The file backing the note `id`.
     pub fn path(&self, id: &str) -> Result<PathBuf> {
         match self.lookup(id).filter(|p| p.is_file()) {
@@ -158,9 +797,192 @@ impl NotesStore {
     }
 
     /// Write a note to disk, creating it when the id is new or empty.
+    /// A note expired meanwhile (see [`Self::expire`]) is an error.
     ///
     /// Returns the persisted note with backend-owned id and timestamps.
     pub fn upsert(&self, note: Note) -> Result<Note> {
+        self.save(note, Document::new())
+    }
+
+    /// The folder holding the vault's note templates, created if
+    /// needed.
+    pub fn templates_dir(&self) -> Result<PathBuf> {
+        let dir = self.dir.join(TEMPLATES_DIR);
+        fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+
+    /// The vault's note templates, by name. A vault without a templates
+    /// folder has none.
+    pub fn templates(&self) -> Result<Vec<NoteTemplate>> {
+        let entries = match fs::read_dir(self.dir.join(TEMPLATES_DIR)) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                return Ok(Vec::new())
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut found = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            if !is_note_file(&path) {
+                continue;
+            }
+            match read_text(&path) {
+                Ok((text, _)) => found.push(NoteTemplate {
+                    name: name.to_string(),
+                    prompts: templates::prompts(&text),
+                }),
+                Err(e) => warn!("Skipping unreadable template {path:?}: {e}"),
+            }
+        }
+        found.sort_by_key(|t| t.name.to_lowercase());
+        Ok(found)
+    }
+
+    /// Create a note from the template `template`, filling in its
+    /// variables (see [`crate::templates`]) from `values`.
+    ///
+    /// The template's header is carried into the note, except for the
+    /// fields the store owns; the note gets its own id and timestamps.
+    /// `title` defaults to the template's name, and anything else left
+    /// out of `values` to nothing.
+    pub fn create_from_template(
+        &self,
+        template: &str,
+        values: &HashMap<String, String>,
+    ) -> Result<Note> {
+        let mut values = values.clone();
+        values.entry("title".into()).or_insert_with(|| template.into());
+        let now = Local::now().naive_local();
+        let doc = self.expand_template(template, values, now)?;
+        let note = Note::new(doc.body().to_string());
+        self.save(note, doc)
+    }
+
+    /// The daily note of `date`, created if there is none yet.
+    ///
+    /// With several notes claiming the same day, the oldest wins. A
+    /// new one starts from the vault's daily template, its variables
+    /// filled in as of `date` and its title set to the date; a missing
+    /// template falls back to a note holding just the date.
+    pub fn daily_note(&self, date: NaiveDate) -> Result<Note> {
+        let _daily = self.daily.lock().unwrap();
+        let existing = self
+            .all_summaries()?
+            .into_iter()
+            .filter(|summary| summary.date == Some(date))
+            .min_by(|a, b| {
+                a.created_at.cmp(&b.created_at).then_with(|| a.id.cmp(&b.id))
+            });
+        if let Some(summary) = existing {
+            return self.get(&summary.id);
+        }
+
+        let now = date.and_time(Local::now().time());
+        let values = HashMap::from([("title".into(), daily::title(date))]);
+        let mut doc = match &self.daily_template {
+            Some(name) => {
+                self.expand_template(name, values, now).unwrap_or_else(|e| {
+                    warn!("Daily note template {name:?} unusable: {e}");
+                    daily::seed(date)
+                })
+            }
+            None => daily::seed(date),
+        };
+        doc.set(daily::DATE, daily::title(date));
+        let note = Note::new(doc.body().to_string());
+        self.save(note, doc)
+    }
+
+    /// The days between `from` and `to`, both included, that have a
+    /// daily note, in order.
+    pub fn daily_notes(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<DailyNote>> {
+        let mut days: HashMap<NaiveDate, NoteSummary> = HashMap::new();
+        for summary in self.all_summaries()? {
+            let Some(date) = summary.date.filter(|d| (from..=to).contains(d))
+            else {
+                continue;
+            };
+            let day = days.entry(date).or_insert_with(|| summary.clone());
+            if (summary.created_at, &summary.id) < (day.created_at, &day.id) {
+                *day = summary;
+            }
+        }
+
+        let mut notes: Vec<DailyNote> = days
+            .into_iter()
+            .map(|(date, summary)| DailyNote { date, note_id: summary.id })
+            .collect();
+        notes.sort_by_key(|note| note.date);
+        Ok(notes)
+    }
+
+    /// The template `template` with its variables filled in as of
+    /// `now`, without the header fields the store owns. Variables left
+    /// out of `values` are filled in with nothing.
+    ///
+    /// The header is filled in field by field, so a value holding a
+    /// newline or `---` stays within its field instead of adding to or
+    /// ending the header.
+    fn expand_template(
+        &self,
+        template: &str,
+        mut values: HashMap<String, String>,
+        now: NaiveDateTime,
+    ) -> Result<Document> {
+        let not_found =
+            || Error::GenericError(format!("No template named {template:?}"));
+        if template.is_empty() || template.contains(['/', '\\']) {
+            return Err(not_found());
+        }
+        let path = self.dir.join(TEMPLATES_DIR).join(format!("{template}.md"));
+        if !is_note_file(&path) {
+            return Err(not_found());
+        }
+
+        let (text, _) = read_text(&path)?;
+        values.entry("clipboard".into()).or_default();
+        for prompt in templates::prompts(&text) {
+            values.entry(prompt).or_default();
+        }
+
+        let mut doc = Document::parse(&text);
+        for key in [ID, CREATED_AT, UPDATED_AT] {
+            doc.remove(key);
+        }
+        let keys: Vec<String> = doc.keys().map(str::to_string).collect();
+        for key in keys {
+            if let Some(value) = doc.value(&key) {
+                doc.set_value(
+                    &key,
+                    templates::expand_value(&value, &values, now),
+                );
+            }
+        }
+        let body = templates::expand(doc.body(), &values, now);
+        doc.set_body(body);
+        Ok(doc)
+    }
+
+    /// [`Self::upsert`], starting a new note's file from `seed` rather
+    /// than from an empty document. Existing notes ignore `seed`.
+    fn save(&self, note: Note, seed: Document) -> Result<Note> {
+        let _edit = self.edits.lock().unwrap();
+        if self.expired.lock().unwrap().contains(&note.id) {
+            return Err(Error::GenericError(format!(
+                "Note {} has expired",
+                note.id
+            )));
+        }
         let id = match note.id.as_str() {
             "" => generate_model_id(ModelType::TypeNote),
             _ => note.id.clone(),
@@ -174,14 +996,27 @@ impl NotesStore {
             }
         };
 
-        let mut doc = current
+        let (mut doc, format) =
+            current.as_deref().and_then(|p| read_text(p).ok()).map_or_else(
+                || (seed, FileFormat::default()),
+                |(text, format)| (Document::parse(&text), format),
+            );
+
+        // A file the sidecar index tracks keeps its identity there; its
+        // header is never touched.
+        let tracked = current
             .as_deref()
-            .and_then(|p| fs::read_to_string(p).ok())
-            .map_or_else(Document::new, |text| Document::parse(&text));
+            .filter(|_| file_id(&doc).is_none())
+            .and_then(|p| self.tracked(p))
+            .filter(|entry| entry.id == id);
 
         let now = truncate(Utc::now());
         let (created_at, updated_at) = if current.is_some() {
-            (read_time(&doc, CREATED_AT).unwrap_or(now), now)
+            let created_at = match &tracked {
+                Some(entry) => Some(entry.created_at),
+                None => read_time(&doc, CREATED_AT),
+            };
+            (created_at.unwrap_or(now), now)
         } else {
             (
                 truncate(timestamp_for_upsert(note.created_at)),
@@ -201,22 +1036,42 @@ impl NotesStore {
             }
         }
 
-        doc.set(ID, id.as_str());
-        doc.set(CREATED_AT, write_time(created_at));
-        doc.set(UPDATED_AT, write_time(updated_at));
+        if tracked.is_none() {
+            doc.set(ID, id.as_str());
+            doc.set(CREATED_AT, write_time(created_at));
+            doc.set(UPDATED_AT, write_time(updated_at));
+        }
+        if current.is_some() {
+            self.remember_title(&id, note_title(doc.body()), note_title(body));
+        }
         doc.set_body(body);
 
-        let path = self.place(&id, doc.body(), current)?;
-        self.write(&path, &doc.render())?;
+        // Tracked files keep their names too: they may be addressed by
+        // path elsewhere, like a static site's sources.
+        let path = match (&tracked, current) {
+            (Some(_), Some(current)) => current,
+            (_, current) => self.place(&id, doc.body(), current)?,
+        };
+        self.write(&path, &doc.render(), format)?;
+        let archived = note_archived(&doc, tracked.as_ref());
+        if let Some(entry) = tracked {
+            self.track_at(&path, SidecarEntry { updated_at, ..entry })?;
+        }
         self.index.lock().unwrap().insert(id.clone(), path);
         self.bases.lock().unwrap().insert(id.clone(), body_hash(doc.body()));
 
         Ok(Note {
             model: "note".to_string(),
+            pinned: self.is_pinned(&id),
             id,
             created_at,
             updated_at,
             content: doc.body().to_string(),
+            tags: note_tags(&doc),
+            aliases: aliases(&doc),
+            expires_at: expires_at(&doc, updated_at),
+            archived,
+            color: header_color(&doc),
         })
     }
 
@@ -230,8 +1085,58 @@ impl NotesStore {
             fs::remove_file(&path)?;
             self.record_write(&path, None);
         }
-        self.bases.lock().unwrap().remove(id);
-        Ok(())
+        self.forget(id)
+    }
+
+    /// Move the notes expired by `now` to the vault's `expired` folder
+    /// (see [`crate::expiry`]), returning them. A name already taken
+    /// there gets a numeric suffix, so no expired note is overwritten.
+    ///
+    /// The moved files lose their expiry fields, so a note moved back
+    /// stays. Until it is, it can't be saved again; see
+    /// [`Self::upsert`].
+    pub fn expire(&self, now: DateTime<Utc>) -> Result<Vec<ExpiredNote>> {
+        let due: Vec<NoteSummary> = self
+            .all_summaries()?
+            .into_iter()
+            .filter(|note| note.expires_at.is_some_and(|at| at <= now))
+            .collect();
+        if due.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let dir = self.dir.join(EXPIRED_DIR);
+        fs::create_dir_all(&dir)?;
+        let _edit = self.edits.lock().unwrap();
+        let mut expired = Vec::new();
+        for note in due {
+            let Some(path) = self.lookup(&note.id) else {
+                continue;
+            };
+            // A save since the summaries were read may have put the
+            // expiry off or dropped it.
+            let still_due = self.adopt_summary(&path).is_ok_and(|current| {
+                current.id == note.id
+                    && current.expires_at.is_some_and(|at| at <= now)
+            });
+            if !still_due {
+                continue;
+            }
+            let to = unused_path(&dir, &path);
+            if let Err(e) = fs::rename(&path, &to) {
+                warn!("Could not move expired note {path:?}: {e}");
+                continue;
+            }
+            if let Err(e) = strip_expiry(&to) {
+                warn!("Could not clear the expiry of {to:?}: {e}");
+            }
+            self.index.lock().unwrap().remove(&note.id);
+            self.record_write(&path, None);
+            self.expired.lock().unwrap().insert(note.id.clone());
+            self.forget(&note.id)?;
+            expired.push(ExpiredNote { id: note.id, title: note.title });
+        }
+        Ok(expired)
     }
 
     /// Whether the state of `path` on disk is this store's own doing:
@@ -246,21 +1151,144 @@ impl NotesStore {
             return false;
         };
 
-        match fs::read_to_string(path) {
-            Ok(contents) => last == Some(body_hash(&contents)),
+        match fs::read(path) {
+            Ok(contents) => last == Some(file_hash(&contents)),
             Err(_) => last.is_none(),
         }
     }
 
+    /// Rewrite a note's file as UTF-8 with `\n` line endings, the
+    /// format Sticky creates notes in.
+    ///
+    /// Saves otherwise keep a file's original encoding and line
+    /// endings; this is the explicit conversion. Files already in the
+    /// default format are left alone.
+    pub fn convert_to_utf8(&self, id: &str) -> Result<()> {
+        let _edit = self.edits.lock().unwrap();
+        let path = self.path(id)?;
+        let (text, format) = read_text(&path)?;
+        if !format.is_default() {
+            self.write(&path, &text, FileFormat::default())?;
+        }
+        Ok(())
+    }
+
+    /// Drop what the store keeps about a note whose file is gone.
+    fn forget(&self, id: &str) -> Result<()> {
+        self.bases.lock().unwrap().remove(id);
+        self.retitled.lock().unwrap().remove(id);
+        self.unpin(id)?;
+
+        let mut frames = self.frames.lock().unwrap();
+        if frames.remove(id) {
+            frames.save(&self.dir)?;
+        }
+
+        if let Some(sidecar) = &self.sidecar {
+            let mut sidecar = sidecar.lock().unwrap();
+            if sidecar.remove(id) {
+                sidecar.save(&self.dir)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Remember a note's title before an upsert changes it, unless an
+    /// earlier title is already remembered. Changing it back forgets
+    /// it.
+    fn remember_title(&self, id: &str, old: String, new: String) {
+        if old == new {
+            return;
+        }
+        let mut retitled = self.retitled.lock().unwrap();
+        let first = retitled.entry(id.to_string()).or_insert(old);
+        if *first == new {
+            retitled.remove(id);
+        }
+    }
+
     /// Rebuild the index from disk and return all readable notes.
     ///
     /// Files created by hand without an id are adopted: they get an id
-    /// and timestamps written back, becoming regular notes.
+    /// and timestamps written back, becoming regular notes. In
+    /// non-invasive mode the sidecar index records them instead.
     fn scan(&self) -> Result<Vec<Note>> {
-        let mut notes = Vec::new();
-        let mut index = HashMap::new();
-        let mut cache = HashMap::new();
         let old_cache = self.cache.lock().unwrap().clone();
+        let mut cache = HashMap::new();
+
+        let notes = self.scan_with(
+            |path| {
+                let entry = match cached(&old_cache, path) {
+                    Some(entry) => entry,
+                    None => {
+                        let parsed = self.adopt_note(path)?;
+                        // Tagged after any adoption write-back, so the
+                        // tag describes the bytes the note was parsed
+                        // from.
+                        match cache_entry(path, parsed.clone()) {
+                            Some(entry) => entry,
+                            // A note whose file can't be tagged (stat
+                            // failure) still lists; it just isn't
+                            // cached for the next scan.
+                            None => return Ok(parsed.note),
+                        }
+                    }
+                };
+                cache.insert(path.to_path_buf(), entry.clone());
+                Ok(entry.value.note)
+            },
+            |note| &note.id,
+        )?;
+
+        *self.cache.lock().unwrap() = cache;
+        Ok(notes)
+    }
+
+    /// Rebuild the index from disk and summarize all readable notes,
+    /// adopting files like [`Self::scan`] does.
+    ///
+    /// Notes the full cache already holds are summarized from there.
+    fn scan_summaries(&self) -> Result<Vec<NoteSummary>> {
+        // Borrowed rather than cloned: the full cache holds whole
+        // bodies.
+        let notes = self.cache.lock().unwrap();
+        let old_heads = self.heads.lock().unwrap().clone();
+        let mut heads = HashMap::new();
+
+        let summaries = self.scan_with(
+            |path| {
+                if let Some(entry) = cached(&notes, path) {
+                    return Ok(entry.value.summary);
+                }
+                let entry = match cached(&old_heads, path) {
+                    Some(entry) => entry,
+                    None => {
+                        let summary = self.adopt_summary(path)?;
+                        match cache_entry(path, summary.clone()) {
+                            Some(entry) => entry,
+                            None => return Ok(summary),
+                        }
+                    }
+                };
+                heads.insert(path.to_path_buf(), entry.clone());
+                Ok(entry.value)
+            },
+            |summary| &summary.id,
+        )?;
+
+        *self.heads.lock().unwrap() = heads;
+        Ok(summaries)
+    }
+
+    /// Load every note file with `load` and rebuild the index from the
+    /// ids of what it returns. Files that fail to load are skipped.
+    fn scan_with<T>(
+        &self,
+        mut load: impl FnMut(&Path) -> Result<T>,
+        id: impl Fn(&T) -> &String,
+    ) -> Result<Vec<T>> {
+        let mut loaded = Vec::new();
+        let mut index = HashMap::new();
 
         for entry in fs::read_dir(&self.dir)? {
             let path = entry?.path();
@@ -268,34 +1296,38 @@ impl NotesStore {
                 continue;
             }
 
-            // A note whose file can't be tagged (stat failure) still
-            // lists; it just isn't cached for the next scan.
-            let (note, entry) = match cached(&old_cache, &path) {
-                Some(entry) => (entry.note.clone(), Some(entry)),
-                None => match self.adopt_note(&path) {
-                    // Tagged after any adoption write-back, so the tag
-                    // describes the bytes the note was parsed from.
-                    Ok(note) => (note.clone(), cache_entry(&path, note)),
-                    Err(e) => {
-                        warn!("Skipping unreadable note {path:?}: {e}");
-                        continue;
-                    }
-                },
+            let value = match load(&path) {
+                Ok(value) => value,
+                Err(e) => {
+                    warn!("Skipping unreadable note {path:?}: {e}");
+                    continue;
+                }
             };
 
-            if let Some(entry) = entry {
-                cache.insert(path.clone(), entry);
+            if index.insert(id(&value).clone(), path.clone()).is_some() {
+                warn!("Duplicate note id {} at {path:?}", id(&value));
             }
+            loaded.push(value);
+        }
 
-            if index.insert(note.id.clone(), path.clone()).is_some() {
-                warn!("Duplicate note id {} at {path:?}", note.id);
+        // Forget tracked files that are gone for good: neither their id
+        // turned up in this scan nor does a file of their name exist.
+        if let Some(sidecar) = &self.sidecar {
+            let mut sidecar = sidecar.lock().unwrap();
+            let pruned = sidecar.retain(|entry| {
+                index.contains_key(&entry.id)
+                    || self.dir.join(&entry.name).exists()
+            });
+            if pruned {
+                sidecar.save(&self.dir)?;
             }
-            notes.push(note);
         }
 
+        // An expired note moved back into the vault is a note again.
+        self.expired.lock().unwrap().retain(|id| !index.contains_key(id));
+
         *self.index.lock().unwrap() = index;
-        *self.cache.lock().unwrap() = cache;
-        Ok(notes)
+        Ok(loaded)
     }
 
     fn lookup(&self, id: &str) -> Option<PathBuf> {
@@ -358,12 +1390,49 @@ impl NotesStore {
         }
     }
 
-    /// Load a note file, assigning an id and timestamps (and writing
-    /// them back) when the file lacks them.
-    fn adopt_note(&self, path: &Path) -> Result<Note> {
-        let text = fs::read_to_string(path)?;
+    /// Load a note file and its summary, assigning an id and
+    /// timestamps (and writing them back) when the file lacks them.
+    ///
+    /// In non-invasive mode nothing is written back: a file without an
+    /// id is tracked by the sidecar index instead, and missing
+    /// timestamps fall back to the file's own.
+    ///
+    /// Both come back unpinned: the scan caches them by file, and pins
+    /// change without the file, so listings mark them instead.
+    fn adopt_note(&self, path: &Path) -> Result<Parsed> {
+        let (text, format) = read_text(path)?;
+        for diagnostic in Document::diagnostics(&text) {
+            warn!("Note header problem in {path:?}, {diagnostic}");
+        }
         let mut doc = Document::parse(&text);
         let meta = fs::metadata(path)?;
+        let parsed =
+            |doc: &Document, id: String, created_at, updated_at| Parsed {
+                note: Note {
+                    model: "note".to_string(),
+                    id: id.clone(),
+                    created_at,
+                    updated_at,
+                    content: doc.body().to_string(),
+                    tags: note_tags(doc),
+                    aliases: aliases(doc),
+                    expires_at: expires_at(doc, updated_at),
+                    archived: is_archived(doc),
+                    color: header_color(doc),
+                    pinned: false,
+                },
+                summary: summarize(doc, id, created_at, updated_at),
+            };
+
+        if self.sidecar.is_some() && file_id(&doc).is_none() {
+            let entry = self.track(path, &meta)?;
+            let archived = note_archived(&doc, Some(&entry));
+            let mut parsed =
+                parsed(&doc, entry.id, entry.created_at, entry.updated_at);
+            parsed.note.archived = archived;
+            parsed.summary.archived = archived;
+            return Ok(parsed);
+        }
 
         let id = file_id(&doc).map(str::to_string);
         let created = read_time(&doc, CREATED_AT);
@@ -378,22 +1447,140 @@ impl NotesStore {
             .or_else(|| meta.modified().ok().map(DateTime::from))
             .map_or_else(|| truncate(Utc::now()), truncate);
 
-        if !complete {
+        if !complete && self.sidecar.is_none() {
             doc.set(ID, id.as_str());
             doc.set(CREATED_AT, write_time(created_at));
             doc.set(UPDATED_AT, write_time(updated_at));
-            self.write(path, &doc.render())?;
+            self.write(path, &doc.render(), format)?;
         }
 
-        Ok(Note {
+        Ok(parsed(&doc, id, created_at, updated_at))
+    }
+
+    /// Summarize a note file from its head.
+    ///
+    /// A file whose header lacks an id or timestamps is adopted
+    /// through [`Self::adopt_note`], which reads it whole.
+    fn adopt_summary(&self, path: &Path) -> Result<NoteSummary> {
+        let doc = read_head(path)?;
+        if self.sidecar.is_some() && file_id(&doc).is_none() {
+            let entry = self.track(path, &fs::metadata(path)?)?;
+            let archived = note_archived(&doc, Some(&entry));
+            return Ok(NoteSummary {
+                archived,
+                ..summarize(&doc, entry.id, entry.created_at, entry.updated_at)
+            });
+        }
+
+        let created = read_time(&doc, CREATED_AT);
+        let updated = read_time(&doc, UPDATED_AT);
+        match (file_id(&doc), created, updated) {
+            (Some(id), Some(created_at), Some(updated_at)) => {
+                Ok(summarize(&doc, id.to_string(), created_at, updated_at))
+            }
+            _ => self.adopt_note(path).map(|parsed| parsed.summary),
+        }
+    }
+
+    /// Read a note file, returning it only when it carries the wanted
+    /// id, in its header or through the sidecar index.
+    fn read_note(&self, path: &Path, id: &str) -> Option<Note> {
+        let (text, _) = read_text(path).ok()?;
+        let doc = Document::parse(&text);
+
+        let tracked = match file_id(&doc) {
+            Some(file_id) if file_id == id => None,
+            Some(_) => return None,
+            None => Some(self.tracked(path).filter(|e| e.id == id)?),
+        };
+        let (created_at, updated_at) = match &tracked {
+            Some(entry) => (Some(entry.created_at), Some(entry.updated_at)),
+            None => (read_time(&doc, CREATED_AT), read_time(&doc, UPDATED_AT)),
+        };
+
+        let now = Utc::now();
+        let updated_at = updated_at.unwrap_or(now);
+        Some(Note {
             model: "note".to_string(),
-            id,
-            created_at,
+            id: id.to_string(),
+            created_at: created_at.unwrap_or(now),
             updated_at,
             content: doc.body().to_string(),
+            tags: note_tags(&doc),
+            aliases: aliases(&doc),
+            expires_at: expires_at(&doc, updated_at),
+            archived: note_archived(&doc, tracked.as_ref()),
+            color: header_color(&doc),
+            pinned: self.is_pinned(id),
         })
     }
 
+    /// The sidecar entry of a file, if the store tracks it there.
+    fn tracked(&self, path: &Path) -> Option<SidecarEntry> {
+        let sidecar = self.sidecar.as_ref()?.lock().unwrap();
+        let name = path.file_name()?.to_str()?;
+        let inode = fs::metadata(path).ok().as_ref().and_then(inode);
+        sidecar.find(name, inode).cloned()
+    }
+
+    /// Track a file without an id in the sidecar index, giving it an
+    /// id on first sight.
+    ///
+    /// A file edited externally since it was last seen counts as
+    /// updated at its modification time.
+    fn track(&self, path: &Path, meta: &fs::Metadata) -> Result<SidecarEntry> {
+        let modified = meta.modified().ok().map(DateTime::from).map(truncate);
+        let entry = match self.tracked(path) {
+            Some(entry) => SidecarEntry {
+                updated_at: modified
+                    .map_or(entry.updated_at, |m| m.max(entry.updated_at)),
+                ..entry
+            },
+            None => SidecarEntry {
+                name: String::new(),
+                inode: None,
+                id: generate_model_id(ModelType::TypeNote),
+                created_at: meta
+                    .created()
+                    .ok()
+                    .map(DateTime::from)
+                    .map_or_else(|| truncate(Utc::now()), truncate),
+                updated_at: modified.unwrap_or_else(|| truncate(Utc::now())),
+                archived: None,
+            },
+        };
+
+        self.track_at(path, entry)
+    }
+
+    /// Record `entry` as describing the file at `path`, as it is on
+    /// disk now.
+    fn track_at(
+        &self,
+        path: &Path,
+        entry: SidecarEntry,
+    ) -> Result<SidecarEntry> {
+        let Some(sidecar) = &self.sidecar else {
+            return Ok(entry);
+        };
+
+        let entry = SidecarEntry {
+            name: path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default()
+                .to_string(),
+            inode: fs::metadata(path).ok().as_ref().and_then(inode),
+            ..entry
+        };
+
+        let mut sidecar = sidecar.lock().unwrap();
+        if sidecar.insert(entry.clone()) {
+            sidecar.save(&self.dir)?;
+        }
+        Ok(entry)
+    }
+
     /// Save externally edited content that is about to be overwritten
     /// into a sibling file.
     ///
@@ -412,25 +1599,23 @@ impl NotesStore {
 
         let mut contents = body.trim_end_matches('\n').to_string();
         contents.push('\n');
-        self.write(&self.dir.join(name), &contents)
+        self.write(&self.dir.join(name), &contents, FileFormat::default())
     }
 
-    /// Write file contents crash-safely: temp file in the same
-    /// directory, fsync, atomic rename.
+    /// Write file contents crash-safely, encoded in `format`: temp
+    /// file in the same directory, fsync, atomic rename.
     ///
     /// The write is remembered so the file watcher can recognize it
     /// as the store's own.
-    fn write(&self, path: &Path, contents: &str) -> Result<()> {
-        let dir = path.parent().ok_or_else(|| {
-            Error::GenericError(format!("Note path has no parent: {path:?}"))
-        })?;
-
-        let mut file = NamedTempFile::new_in(dir)?;
-        file.write_all(contents.as_bytes())?;
-        file.as_file().sync_all()?;
-        file.persist(path).map_err(|e| Error::from(e.error))?;
-
-        self.record_write(path, Some(body_hash(contents)));
+    fn write(
+        &self,
+        path: &Path,
+        contents: &str,
+        format: FileFormat,
+    ) -> Result<()> {
+        let bytes = encode(contents, format);
+        write_atomic(path, &bytes)?;
+        self.record_write(path, Some(file_hash(&bytes)));
         Ok(())
     }
 
@@ -441,12 +1626,25 @@ impl NotesStore {
     }
 }
 
-/// The cached note for `path`, if the file still has the mtime and
+/// Drop the expiry fields of a note file moved aside by
+/// [`NotesStore::expire`].
+fn strip_expiry(path: &Path) -> Result<()> {
+    let (text, format) = read_text(path)?;
+    let mut doc = Document::parse(&text);
+    let fixed = doc.remove(EXPIRES).is_some();
+    let idle = doc.remove(EXPIRE_AFTER).is_some();
+    if fixed || idle {
+        write_atomic(path, &encode(&doc.render(), format))?;
+    }
+    Ok(())
+}
+
+/// The cached value for `path`, if the file still has the mtime and
 /// size it was parsed at.
-fn cached(
-    cache: &HashMap<PathBuf, CachedNote>,
+fn cached<T: Clone>(
+    cache: &HashMap<PathBuf, Cached<T>>,
     path: &Path,
-) -> Option<CachedNote> {
+) -> Option<Cached<T>> {
     let entry = cache.get(path)?;
     let meta = fs::metadata(path).ok()?;
     let fresh = meta
@@ -455,11 +1653,57 @@ fn cached(
     fresh.then(|| entry.clone())
 }
 
-/// Tag a parsed note with its file's current identity for the cache.
-fn cache_entry(path: &Path, note: Note) -> Option<CachedNote> {
-    let meta = fs::metadata(path).ok()?;
-    let modified = meta.modified().ok()?;
-    Some(CachedNote { modified, len: meta.len(), note })
+/// Tag a parsed value with its file's current identity for the cache.
+fn cache_entry<T>(path: &Path, value: T) -> Option<Cached<T>> {
+    let meta = fs::metadata(path).ok()?;
+    let modified = meta.modified().ok()?;
+    Some(Cached { modified, len: meta.len(), value })
+}
+
+/// Whether a note is archived: by the state the sidecar index keeps for
+/// a tracked file, else by its header.
+fn note_archived(doc: &Document, tracked: Option<&SidecarEntry>) -> bool {
+    tracked.and_then(|entry| entry.archived).unwrap_or_else(|| is_archived(doc))
+}
+
+/// The summary of a note file read into `doc`, whole or just its head.
+/// It comes back unpinned, like [`NotesStore::adopt_note`]'s notes.
+fn summarize(
+    doc: &Document,
+    id: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+) -> NoteSummary {
+    NoteSummary {
+        id,
+        title: note_title(doc.body()),
+        created_at,
+        updated_at,
+        date: header_date(doc),
+        reminder: header_reminder(doc),
+        expires_at: expires_at(doc, updated_at),
+        archived: is_archived(doc),
+        color: header_color(doc),
+        pinned: false,
+    }
+}
+
+/// A resolver for links between `notes`. Shared titles resolve to the
+/// first note holding them, so pass them newest first.
+fn resolver(notes: &[Note]) -> Resolver {
+    let mut resolver = Resolver::default();
+    for note in notes {
+        resolver.add(&note.id, &note_title(&note.content), &note.aliases);
+    }
+    resolver
+}
+
+/// The line of `body` holding the byte offset `at`, windowed around it
+/// like a search snippet.
+fn line_around(body: &str, at: usize) -> String {
+    let start = body[..at].rfind('\n').map_or(0, |i| i + 1);
+    let end = body[at..].find('\n').map_or(body.len(), |i| at + i);
+    excerpt(body[start..end].trim_end(), at - start)
 }
 
 /// The display title of a note: its first non-empty line with block
@@ -589,22 +1833,6 @@ fn timestamp_for_upsert(dt: DateTime<Utc>) -> DateTime<Utc> {
     }
 }
 
-/// Read a note file, returning it only when it carries the wanted id.
-fn read_note(path: &Path, id: &str) -> Option<Note> {
-    let text = fs::read_to_string(path).ok()?;
-    let doc = Document::parse(&text);
-    (file_id(&doc) == Some(id)).then(|| {
-        let now = Utc::now();
-        Note {
-            model: "note".to_string(),
-            id: id.to_string(),
-            created_at: read_time(&doc, CREATED_AT).unwrap_or(now),
-            updated_at: read_time(&doc, UPDATED_AT).unwrap_or(now),
-            content: doc.body().to_string(),
-        }
-    })
-}
-
 /// The id a note file carries, if any.
 fn file_id(doc: &Document) -> Option<&str> {
     doc.get(ID).filter(|id| !id.is_empty())
@@ -631,13 +1859,80 @@ fn write_time(dt: DateTime<Utc>) -> String {
     dt.to_rfc3339_opts(SecondsFormat::Micros, true)
 }
 
-/// The identity of a note body for base and self-write comparisons.
+/// Read a note file's text, decoded from whatever format it is in.
+fn read_text(path: &Path) -> Result<(String, FileFormat)> {
+    Ok(decode(&fs::read(path)?))
+}
+
+/// Read a note file's header and first body line, leaving the rest of
+/// the file unread.
+///
+/// Files that turn out not to be UTF-8 are decoded whole instead, like
+/// [`read_text`] does.
+fn read_head(path: &Path) -> Result<Document> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let Some(start) = utf8_start(reader.fill_buf()?) else {
+        return Ok(Document::parse(&read_text(path)?.0));
+    };
+    reader.consume(start);
+
+    match Document::parse_head(reader) {
+        Ok(doc) => Ok(doc),
+        Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+            Ok(Document::parse(&read_text(path)?.0))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// The identity of a note body for base comparisons.
 fn body_hash(text: &str) -> u64 {
     let mut hasher = DefaultHasher::new();
     text.hash(&mut hasher);
     hasher.finish()
 }
 
+/// The identity of file contents for self-write comparisons.
+fn file_hash(bytes: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// A path in `dir` for the file at `path`: its own name, or the first
+/// free one with a numeric suffix.
+fn unused_path(dir: &Path, path: &Path) -> PathBuf {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("note");
+    let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("md");
+    let mut n = 1;
+    loop {
+        let name = match n {
+            1 => format!("{stem}.{ext}"),
+            n => format!("{stem}-{n}.{ext}"),
+        };
+        let to = dir.join(name);
+        if !to.exists() {
+            return to;
+        }
+        n += 1;
+    }
+}
+
+/// The order of listings: pinned notes first, in their manual order,
+/// then the rest newest first. Notes are given as id and update time.
+fn listing_order(
+    pins: &Pins,
+    a: (&str, DateTime<Utc>),
+    b: (&str, DateTime<Utc>),
+) -> Ordering {
+    match (pins.position(a.0), pins.position(b.0)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)),
+    }
+}
+
 /// A visible `.md` file; temp files are dotfiles and get skipped.
 fn is_note_file(path: &Path) -> bool {
     path.is_file() && is_note_name(path)
@@ -714,7 +2009,11 @@ fn strip_line_markers(line: &str) -> &str {
 
 #[cfg(test)]
 mod tests {
+    use chrono::TimeDelta;
+    use sticky_matter::Value;
+
     use super::*;
+    use crate::geometry::Rect;
 
     fn store() -> (tempfile::TempDir, NotesStore) {
         let dir = tempfile::tempdir().unwrap();
@@ -722,6 +2021,14 @@ mod tests {
         (dir, store)
     }
 
+    fn non_invasive_store() -> (tempfile::TempDir, NotesStore) {
+        let dir = tempfile::tempdir().unwrap();
+        let config = StoreConfig { non_invasive: true, ..Default::default() };
+        let store =
+            NotesStore::open_with(dir.path().join("notes"), config).unwrap();
+        (dir, store)
+    }
+
     fn upsert(store: &NotesStore, id: &str, content: &str) -> Note {
         store
             .upsert(Note {
@@ -820,6 +2127,156 @@ mod tests {
         assert!(text.starts_with("---\nid: note_"));
     }
 
+    #[test]
+    fn adopts_toml_frontmatter_in_place() {
+        let (_dir, store) = store();
+        let path = store.dir().join("from-hugo.md");
+        fs::write(&path, "+++\ntitle = \"Post\"\n+++\n\n# From Hugo\n")
+            .unwrap();
+
+        let listed = store.list().unwrap();
+        assert_eq!(listed[0].content, "# From Hugo");
+
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(text.starts_with("+++\ntitle = \"Post\"\nid = \"note_"));
+        assert!(!text.contains("---"));
+    }
+
+    #[test]
+    fn reports_header_problems() {
+        let (_dir, store) = store();
+        let note = upsert(&store, "", "Body");
+        let path = store.path(&note.id).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        let text = text.replacen("---\n", "---\ntitle: \"open\n", 1);
+        fs::write(&path, text).unwrap();
+
+        let diagnostics = store.diagnostics(&note.id).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, 2);
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Error);
+    }
+
+    #[test]
+    fn non_invasive_mode_leaves_external_files_untouched() {
+        let (_dir, store) = non_invasive_store();
+        let path = store.dir().join("post.md");
+        let text = "---\ntitle: Hello\n---\n\n# From a site\n";
+        fs::write(&path, text).unwrap();
+
+        let id = store.list().unwrap()[0].id.clone();
+        assert!(id.starts_with("note_"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), text);
+
+        // The id survives a rescan, a reopen, and a rename.
+        assert_eq!(store.list().unwrap()[0].id, id);
+        let reopened = NotesStore::open_with(
+            store.dir().to_path_buf(),
+            StoreConfig { non_invasive: true, ..Default::default() },
+        )
+        .unwrap();
+        assert_eq!(reopened.list().unwrap()[0].id, id);
+        fs::rename(&path, store.dir().join("moved.md")).unwrap();
+        assert_eq!(store.get(&id).unwrap().content, "# From a site");
+    }
+
+    #[test]
+    fn non_invasive_edits_keep_the_header_and_the_name() {
+        let (_dir, store) = non_invasive_store();
+        let path = store.dir().join("post.md");
+        fs::write(&path, "---\ntitle: Hello\n---\n\n# Old\n").unwrap();
+        let id = store.list().unwrap()[0].id.clone();
+
+        let saved = upsert(&store, &id, "# New");
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "---\ntitle: Hello\n---\n\n# New\n"
+        );
+        assert_eq!(store.get(&id).unwrap().updated_at, saved.updated_at);
+
+        store.delete(&id).unwrap();
+        assert!(!path.exists());
+        assert!(store.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn non_invasive_archiving_leaves_headers_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = StoreConfig {
+            non_invasive: true,
+            archive_after_days: Some(30),
+            ..Default::default()
+        };
+        let store =
+            NotesStore::open_with(dir.path().join("notes"), config).unwrap();
+        let text = "---\ntitle: Hello\n---\n\n# From a site\n";
+        let post = store.dir().join("post.md");
+        let draft = store.dir().join("draft.md");
+        fs::write(&post, text).unwrap();
+        fs::write(&draft, text).unwrap();
+        let summaries = store.summaries().unwrap();
+        let id = |path: &Path| store.tracked(path).unwrap().id;
+        let (post_id, draft_id) = (id(&post), id(&draft));
+
+        assert!(store.set_archived(&post_id, true).unwrap());
+        assert!(!store.set_archived(&post_id, true).unwrap());
+        assert!(store.get(&post_id).unwrap().archived);
+        let listed: Vec<String> =
+            store.summaries().unwrap().into_iter().map(|s| s.id).collect();
+        assert_eq!(listed, vec![draft_id.clone()]);
+
+        // Unarchived by hand, it stays out of automatic archiving.
+        assert!(store.set_archived(&post_id, false).unwrap());
+        let later = summaries[0].updated_at + TimeDelta::days(31);
+        assert_eq!(store.archive_idle(later).unwrap(), vec![draft_id.clone()]);
+        assert!(store.get(&draft_id).unwrap().archived);
+        assert!(!store.get(&post_id).unwrap().archived);
+
+        assert_eq!(fs::read_to_string(&post).unwrap(), text);
+        assert_eq!(fs::read_to_string(&draft).unwrap(), text);
+    }
+
+    #[test]
+    fn reads_and_saves_legacy_encodings_in_place() {
+        let (_dir, store) = store();
+        let path = store.dir().join("latin.md");
+        fs::write(&path, b"# Caf\xE9\r\n\r\nna\xEFve\r\n").unwrap();
+
+        let note = store.list().unwrap().remove(0);
+        assert_eq!(note.content, "# Café\n\nnaïve");
+        assert_eq!(store.search("naïve").unwrap().len(), 1);
+
+        upsert(&store, &note.id, "# Café\n\nnaïve résumé");
+        let path = store.path(&note.id).unwrap();
+        let bytes = fs::read(&path).unwrap();
+        assert!(bytes.starts_with(b"---\r\nid: note_"));
+        assert!(bytes
+            .ends_with(b"\r\n\r\n# Caf\xE9\r\n\r\nna\xEFve r\xE9sum\xE9\r\n"));
+        assert!(store.is_own_write(&path));
+
+        store.convert_to_utf8(&note.id).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(text.ends_with("\n\n# Café\n\nnaïve résumé\n"));
+        assert!(!text.contains('\r'));
+    }
+
+    #[test]
+    fn reads_frontmatter_behind_a_byte_order_mark() {
+        let (_dir, store) = store();
+        let path = store.dir().join("bom.md");
+        fs::write(
+            &path,
+            "\u{FEFF}---\nid: note_bom\ncreatedAt: 2026-03-11 22:56:44\n\
+             updatedAt: 2026-03-11 23:23:34\n---\n\nhi\n",
+        )
+        .unwrap();
+
+        assert_eq!(store.get("note_bom").unwrap().content, "hi");
+        upsert(&store, "note_bom", "hi there");
+        let bytes = fs::read(store.path("note_bom").unwrap()).unwrap();
+        assert!(bytes.starts_with("\u{FEFF}---\nid: note_bom\n".as_bytes()));
+    }
+
     #[test]
     fn reads_legacy_sqlite_timestamps() {
         let (_dir, store) = store();
@@ -876,6 +2333,24 @@ mod tests {
         assert_eq!(store.get(&note.id).unwrap().content, "# Plan\n\nuser edit");
     }
 
+    #[test]
+    fn reads_outside_the_editor_keep_external_edits_conflicting() {
+        let (_dir, store) = store();
+        let note = upsert(&store, "", "# Plan\n\noriginal");
+        let path = store.lookup(&note.id).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        fs::write(&path, text.replace("original", "agent edit")).unwrap();
+
+        // A window retitling itself after the edit, say.
+        assert_eq!(store.summary(&note.id).unwrap().title, "Plan");
+        store.all_summaries().unwrap();
+        store.list().unwrap();
+
+        upsert(&store, &note.id, "# Plan\n\nuser edit");
+        let notes = store.list().unwrap();
+        assert!(notes.iter().any(|n| n.content.contains("agent edit")));
+    }
+
     #[test]
     fn overwrites_silently_when_disk_matches_the_base() {
         let (_dir, store) = store();
@@ -944,6 +2419,551 @@ mod tests {
         assert_eq!(hits[0].title, "Newer");
     }
 
+    #[test]
+    fn lists_and_filters_by_tags() {
+        let (_dir, store) = store();
+        let sticky = upsert(&store, "", "Ship it #work/sticky #urgent");
+        let report = upsert(&store, "", "Quarterly report #Work");
+        upsert(&store, "", "Groceries #home `#work`");
+
+        assert_eq!(sticky.tags, ["work/sticky", "urgent"]);
+        let tags: Vec<(String, u32)> = store
+            .tags()
+            .unwrap()
+            .into_iter()
+            .map(|t| (t.tag, t.count))
+            .collect();
+        assert_eq!(
+            tags,
+            [
+                ("home".into(), 1),
+                ("urgent".into(), 1),
+                ("work".into(), 1),
+                ("work/sticky".into(), 1),
+            ]
+        );
+
+        let ids = |query: &str| -> Vec<String> {
+            let hits = store.search(query).unwrap();
+            hits.into_iter().map(|hit| hit.note.id).collect()
+        };
+        assert_eq!(ids("#work"), [report.id.as_str(), sticky.id.as_str()]);
+        assert_eq!(ids("#work ship"), [sticky.id.as_str()]);
+        assert_eq!(ids("#work/sticky #urgent"), [sticky.id.as_str()]);
+        assert!(ids("#nope").is_empty());
+    }
+
+    #[test]
+    fn renames_tags_across_the_vault() {
+        let (_dir, store) = store();
+        let inline = upsert(&store, "", "Plan #work/sticky and #workshop");
+        let untouched = upsert(&store, "", "Nothing tagged here");
+
+        let path = store.dir().join("tagged.md");
+        fs::write(&path, "---\ntags: [Work, home]\n---\n\nFrom a tool\n")
+            .unwrap();
+        let tagged = store.list().unwrap();
+        let tagged = tagged.iter().find(|n| n.content == "From a tool");
+        let tagged = tagged.unwrap().clone();
+
+        let mut renamed = store.rename_tag("#work", "job").unwrap();
+        renamed.sort();
+        let mut expected = vec![inline.id.clone(), tagged.id.clone()];
+        expected.sort();
+        assert_eq!(renamed, expected);
+
+        let note = store.get(&inline.id).unwrap();
+        assert_eq!(note.content, "Plan #job/sticky and #workshop");
+        assert_eq!(note.updated_at, inline.updated_at);
+        assert!(fs::read_to_string(&path)
+            .unwrap()
+            .contains("tags: [job, home]"));
+        assert_eq!(store.get(&untouched.id).unwrap(), untouched);
+
+        assert!(store.rename_tag("job", "two words").is_err());
+    }
+
+    #[test]
+    fn finds_backlinks_and_the_link_graph() {
+        let (_dir, store) = store();
+        let target = upsert(&store, "", "# Groceries\n\n- milk");
+        fs::write(
+            store.dir().join("travel.md"),
+            "---\naliases: [Trip]\n---\n\n# Travel plans\n",
+        )
+        .unwrap();
+        let travel = store.list().unwrap();
+        let travel = travel.iter().find(|n| n.content == "# Travel plans");
+        let travel = travel.unwrap().clone();
+        let by_title = upsert(
+            &store,
+            "",
+            "Monday\n\n  Buy [[groceries#Dairy|food]] today",
+        );
+        let id_links = format!("[[{}]], [[Trip]], [[Nowhere]]", target.id);
+        let by_id = upsert(&store, "", &format!("Links\n\n{id_links}"));
+
+        let mut backlinks = store.backlinks(&target.id).unwrap();
+        backlinks.sort_by(|a, b| a.title.cmp(&b.title));
+        let brief: Vec<(&str, &str)> = backlinks
+            .iter()
+            .map(|b| (b.id.as_str(), b.snippet.as_str()))
+            .collect();
+        assert_eq!(
+            brief,
+            [
+                (by_id.id.as_str(), id_links.as_str()),
+                (by_title.id.as_str(), "Buy [[groceries#Dairy|food]] today"),
+            ]
+        );
+
+        let graph = store.link_graph().unwrap();
+        assert_eq!(graph.nodes.len(), 4);
+        let edge = |from: &Note, to: &Note| LinkEdge {
+            from: from.id.clone(),
+            to: to.id.clone(),
+        };
+        let mut edges = graph.edges.clone();
+        edges.sort_by(|a, b| (&a.from, &a.to).cmp(&(&b.from, &b.to)));
+        let mut expected = vec![
+            edge(&by_title, &target),
+            edge(&by_id, &target),
+            edge(&by_id, &travel),
+        ];
+        expected.sort_by(|a, b| (&a.from, &a.to).cmp(&(&b.from, &b.to)));
+        assert_eq!(edges, expected);
+        assert_eq!(
+            graph.unresolved,
+            [UnresolvedLink { from: by_id.id, target: "Nowhere".into() }]
+        );
+    }
+
+    #[test]
+    fn offers_to_rewrite_links_to_an_old_title() {
+        let (_dir, store) = store();
+        let note = upsert(&store, "", "# Groceries");
+        let linking =
+            upsert(&store, "", "Plan\n\n[[Groceries]], [[groceries|list]]");
+        upsert(&store, &note.id, "# Grocer");
+        upsert(&store, &note.id, "# Shopping list");
+
+        let stale = store.take_stale_links(&note.id).unwrap().unwrap();
+        assert_eq!(stale.old_title, "Groceries");
+        assert_eq!(stale.new_title, "Shopping list");
+        assert_eq!(stale.note_ids, [linking.id.as_str()]);
+        assert_eq!(store.take_stale_links(&note.id).unwrap(), None);
+
+        let rewritten =
+            store.rewrite_links(&stale.old_title, &stale.new_title).unwrap();
+        assert_eq!(rewritten, [linking.id.as_str()]);
+        let linking = store.get(&linking.id).unwrap();
+        assert_eq!(
+            linking.content,
+            "Plan\n\n[[Shopping list]], [[Shopping list|list]]"
+        );
+        assert_eq!(store.backlinks(&note.id).unwrap().len(), 1);
+
+        // Changing the title back and forth offers nothing.
+        upsert(&store, &note.id, "# Other");
+        upsert(&store, &note.id, "# Shopping list");
+        assert_eq!(store.take_stale_links(&note.id).unwrap(), None);
+    }
+
+    #[test]
+    fn lists_and_toggles_tasks() {
+        let (_dir, store) = store();
+        let trip = upsert(
+            &store,
+            "",
+            "Trip\n\n- [ ] Pack @due(2026-11-01)\n  - [x] Socks\n- [ ] Book",
+        );
+        upsert(&store, "", "Chores\n\n- [x] Dishes\n```\n- [ ] code\n```");
+
+        let texts = |filter: TaskFilter| {
+            let tasks = store.tasks(&filter).unwrap();
+            let mut texts: Vec<String> =
+                tasks.into_iter().map(|task| task.text).collect();
+            texts.sort();
+            texts
+        };
+        assert_eq!(texts(TaskFilter::default()).len(), 4);
+        assert_eq!(
+            texts(TaskFilter { done: Some(false), ..Default::default() }),
+            ["Book", "Pack"]
+        );
+        assert_eq!(
+            texts(TaskFilter { done: Some(true), ..Default::default() }),
+            ["Dishes", "Socks"]
+        );
+        let due_by = "2026-11-30".parse().ok();
+        assert_eq!(
+            texts(TaskFilter { due_by, ..Default::default() }),
+            ["Pack"]
+        );
+
+        // The note gains a line above the task after it was listed.
+        let tasks = store.tasks(&TaskFilter::default()).unwrap();
+        let book = tasks.iter().find(|task| task.text == "Book").unwrap();
+        let path = store.path(&trip.id).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        fs::write(&path, text.replace("Trip\n", "Trip\nSunday\n")).unwrap();
+
+        let toggled = store.toggle_task(&trip.id, &book.task_ref).unwrap();
+        assert!(toggled.done);
+        assert_eq!(toggled.task_ref.line, 5);
+        let note = store.get(&trip.id).unwrap();
+        assert!(note.content.ends_with("  - [x] Socks\n- [x] Book"));
+        assert_eq!(note.updated_at, trip.updated_at);
+
+        // A reference read before the toggle no longer matches.
+        assert!(store.toggle_task(&trip.id, &book.task_ref).is_err());
+        let untoggled = store.toggle_task(&trip.id, &toggled.task_ref);
+        assert!(!untoggled.unwrap().done);
+    }
+
+    #[test]
+    fn concurrent_task_toggles_all_land() {
+        let (_dir, store) = store();
+        let lines: Vec<String> =
+            (0..8).map(|n| format!("- [ ] Task {n}")).collect();
+        let note = upsert(&store, "", &format!("Chores\n{}", lines.join("\n")));
+        let tasks = store.tasks(&TaskFilter::default()).unwrap();
+
+        std::thread::scope(|scope| {
+            for task in &tasks {
+                let store = &store;
+                let id = &note.id;
+                scope.spawn(move || {
+                    store.toggle_task(id, &task.task_ref).unwrap()
+                });
+            }
+        });
+        let done = store.tasks(&TaskFilter::default()).unwrap();
+        assert!(done.iter().all(|task| task.done), "{done:?}");
+    }
+
+    #[test]
+    fn creates_notes_from_templates() {
+        let (_dir, store) = store();
+        assert!(store.templates().unwrap().is_empty());
+
+        let dir = store.templates_dir().unwrap();
+        fs::write(
+            dir.join("Standup.md"),
+            "---\nid: note_template\ntags: [standup]\n\
+             attendees: {{Attendees}}\n---\n\n\
+             # {{title}} {{date:%Y}}\n\n{{clipboard}}\n",
+        )
+        .unwrap();
+        fs::write(dir.join("notes.txt"), "Not a template").unwrap();
+        assert_eq!(
+            store.templates().unwrap(),
+            [NoteTemplate {
+                name: "Standup".into(),
+                prompts: vec!["Attendees".into()],
+            }]
+        );
+        assert!(store.list().unwrap().is_empty(), "templates aren't notes");
+
+        let values = HashMap::from([("Attendees".into(), "Ana".into())]);
+        let note = store.create_from_template("Standup", &values).unwrap();
+        let year = Local::now().format("%Y");
+        assert_eq!(note.content, format!("# Standup {year}"));
+        assert_eq!(note.tags, ["standup"]);
+        let text = fs::read_to_string(store.path(&note.id).unwrap()).unwrap();
+        assert!(text.contains("attendees: Ana\n"));
+        assert!(!text.contains("note_template"));
+
+        assert!(store.create_from_template("../Standup", &values).is_err());
+        assert!(store.create_from_template("Missing", &values).is_err());
+    }
+
+    #[test]
+    fn template_values_stay_in_their_header_field() {
+        let (_dir, store) = store();
+        let dir = store.templates_dir().unwrap();
+        fs::write(
+            dir.join("Clip.md"),
+            "---\nsource: {{clipboard}}\ntags: [clip, \"{{Topic}}\"]\n---\n\n\
+             # {{title}}\n\n{{clipboard}}\n",
+        )
+        .unwrap();
+
+        let pasted = "x\nid: note_forged\nexpires: 1m\n---\nbody";
+        let values = HashMap::from([
+            ("clipboard".into(), pasted.into()),
+            ("Topic".into(), "work".into()),
+        ]);
+        let note = store.create_from_template("Clip", &values).unwrap();
+        assert_ne!(note.id, "note_forged");
+        assert_eq!(note.expires_at, None);
+        assert_eq!(note.tags, ["clip", "work"]);
+        assert_eq!(note.content, format!("# Clip\n\n{pasted}"));
+
+        let text = fs::read_to_string(store.path(&note.id).unwrap()).unwrap();
+        let doc = Document::parse(&text);
+        assert_eq!(doc.value("source"), Some(Value::String(pasted.into())));
+    }
+
+    #[test]
+    fn finds_or_creates_daily_notes() {
+        let (_dir, store) = store();
+        let day = |s: &str| s.parse::<NaiveDate>().unwrap();
+
+        let plain = store.daily_note(day("2026-10-19")).unwrap();
+        assert_eq!(plain.content, "# 2026-10-19");
+        assert_eq!(store.daily_note(day("2026-10-19")).unwrap().id, plain.id);
+
+        // A note claims a day by its header, whatever its name.
+        let claimed = upsert(&store, "", "Monday");
+        let path = store.path(&claimed.id).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        fs::write(&path, text.replace("---\n\n", "date: 2026-10-20\n---\n\n"))
+            .unwrap();
+        assert_eq!(store.daily_note(day("2026-10-20")).unwrap().id, claimed.id);
+
+        let config = StoreConfig {
+            daily_template: Some("Daily".into()),
+            ..Default::default()
+        };
+        let store = NotesStore::open_with(store.dir().into(), config).unwrap();
+        fs::write(
+            store.templates_dir().unwrap().join("Daily.md"),
+            "---\ntags: [journal]\n---\n# {{date:%A}} {{title}}\n",
+        )
+        .unwrap();
+        let templated = store.daily_note(day("2026-10-21")).unwrap();
+        assert_eq!(templated.content, "# Wednesday 2026-10-21");
+        assert_eq!(templated.tags, ["journal"]);
+
+        let days: Vec<(String, String)> = store
+            .daily_notes(day("2026-10-20"), day("2026-10-31"))
+            .unwrap()
+            .into_iter()
+            .map(|daily| (daily.date.to_string(), daily.note_id))
+            .collect();
+        assert_eq!(
+            days,
+            [
+                ("2026-10-20".to_string(), claimed.id),
+                ("2026-10-21".to_string(), templated.id),
+            ]
+        );
+    }
+
+    #[test]
+    fn snoozes_and_dismisses_reminders() {
+        let (_dir, store) = store();
+        let note = upsert(&store, "", "Call the bank");
+        let path = store.path(&note.id).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        let header = "remind: 2026-10-19 09:00\nrepeat: daily\n---\n\n";
+        fs::write(&path, text.replace("---\n\n", header)).unwrap();
+
+        let time = |s: &str| s.parse::<NaiveDateTime>().unwrap();
+        let reminder = || store.summaries().unwrap()[0].reminder.clone();
+        assert_eq!(reminder().unwrap().due(), time("2026-10-19T09:00:00"));
+
+        let until = time("2026-10-19T09:10:00");
+        assert!(store.snooze_reminder(&note.id, until).unwrap());
+        assert_eq!(reminder().unwrap().due(), until);
+
+        let now = time("2026-10-19T09:30:00");
+        assert!(store.dismiss_reminder(&note.id, now).unwrap());
+        let next = reminder().unwrap();
+        assert_eq!((next.due(), next.snoozed_until), (next.at, None));
+        assert_eq!(next.at, time("2026-10-20T09:00:00"));
+
+        let plain = upsert(&store, "", "No reminder");
+        assert!(!store.dismiss_reminder(&plain.id, now).unwrap());
+        assert_eq!(store.get(&note.id).unwrap().content, "Call the bank");
+    }
+
+    #[test]
+    fn moves_expired_notes_aside() {
+        let (_dir, store) = store();
+        let note = upsert(&store, "", "555-0123");
+        let kept = upsert(&store, "", "Keep me");
+        let path = store.path(&note.id).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        fs::write(&path, text.replace("---\n\n", "expireAfter: 2h\n---\n\n"))
+            .unwrap();
+
+        let expires_at = store.get(&note.id).unwrap().expires_at.unwrap();
+        assert_eq!(expires_at, note.updated_at + TimeDelta::hours(2));
+        assert!(store
+            .expire(expires_at - TimeDelta::seconds(1))
+            .unwrap()
+            .is_empty());
+
+        let expired = store.expire(expires_at).unwrap();
+        assert_eq!(
+            expired,
+            [ExpiredNote { id: note.id.clone(), title: "555-0123".into() }]
+        );
+        assert!(!path.exists());
+        let aside = store.dir().join(EXPIRED_DIR).join("555-0123.md");
+        let text = fs::read_to_string(&aside).unwrap();
+        assert!(text.contains("555-0123") && !text.contains("expireAfter"));
+        let ids: Vec<String> =
+            store.list().unwrap().into_iter().map(|n| n.id).collect();
+        assert_eq!(ids, [kept.id]);
+
+        // An editor still open on it saves once more.
+        assert!(store
+            .upsert(Note { content: "555-0123!".into(), ..note.clone() })
+            .is_err());
+        assert_eq!(store.list().unwrap().len(), 1);
+
+        // Moved back, it is a note again, and stays one.
+        fs::rename(&aside, &path).unwrap();
+        assert_eq!(store.list().unwrap().len(), 2);
+        assert!(store
+            .expire(expires_at + TimeDelta::days(1))
+            .unwrap()
+            .is_empty());
+        let restored = store.get(&note.id).unwrap();
+        assert_eq!(restored.expires_at, None);
+        let saved = store
+            .upsert(Note { content: "555-0123!".into(), ..restored })
+            .unwrap();
+        assert_eq!(store.get(&note.id).unwrap().content, saved.content);
+    }
+
+    #[test]
+    fn archives_notes_out_of_default_listings() {
+        let (_dir, store) = store();
+        let note = upsert(&store, "", "Old plans");
+        let kept = upsert(&store, "", "Current plans");
+
+        assert!(store.set_archived(&note.id, true).unwrap());
+        assert!(!store.set_archived(&note.id, true).unwrap());
+        let archived = store.get(&note.id).unwrap();
+        assert!(archived.archived);
+        assert_eq!(archived.updated_at, note.updated_at);
+        assert!(store.summary(&note.id).unwrap().archived);
+        assert!(!store.toggle_archived(&note.id).unwrap());
+        assert!(!store.summary(&note.id).unwrap().archived);
+        assert!(store.toggle_archived(&note.id).unwrap());
+
+        let ids = |notes: Vec<Note>| -> Vec<String> {
+            notes.into_iter().map(|n| n.id).collect()
+        };
+        assert_eq!(ids(store.list().unwrap()), vec![kept.id.clone()]);
+        assert_eq!(store.summaries().unwrap().len(), 1);
+        assert_eq!(store.list_all().unwrap().len(), 2);
+        let hits = |query| -> Vec<String> {
+            let hits = store.search(query).unwrap();
+            ids(hits.into_iter().map(|hit| hit.note).collect())
+        };
+        assert_eq!(hits("plans"), vec![kept.id.clone()]);
+        assert_eq!(hits("plans is:archived"), vec![note.id.clone()]);
+
+        // Unarchived notes are kept out of automatic archiving.
+        let config =
+            StoreConfig { archive_after_days: Some(30), ..Default::default() };
+        let store = NotesStore::open_with(store.dir().into(), config).unwrap();
+        assert!(store.set_archived(&note.id, false).unwrap());
+        let later = kept.updated_at + TimeDelta::days(30);
+        assert_eq!(store.archive_idle(later).unwrap(), vec![kept.id.clone()]);
+        assert_eq!(ids(store.list().unwrap()), [note.id]);
+        assert!(store.archive_idle(later).unwrap().is_empty());
+    }
+
+    #[test]
+    fn lists_pinned_notes_first_in_manual_order() {
+        let (_dir, store) = store();
+        let first = upsert(&store, "", "First");
+        let second = upsert(&store, "", "Second");
+        let third = upsert(&store, "", "Third");
+        let ids = |store: &NotesStore| -> Vec<String> {
+            store.list().unwrap().into_iter().map(|n| n.id).collect()
+        };
+        assert_eq!(
+            ids(&store),
+            [third.id.as_str(), second.id.as_str(), first.id.as_str()]
+        );
+
+        assert!(store.pin(&first.id).unwrap());
+        assert!(store.pin(&second.id).unwrap());
+        assert!(!store.pin(&second.id).unwrap());
+        assert!(store.pin("note_missing").is_err());
+        assert_eq!(
+            ids(&store),
+            [first.id.as_str(), second.id.as_str(), third.id.as_str()]
+        );
+        assert!(store.get(&first.id).unwrap().pinned);
+        let summaries = store.summaries().unwrap();
+        assert!(summaries[0].pinned && !summaries[2].pinned);
+
+        assert!(store.move_pin(&second.id, 0).unwrap());
+        assert!(!store.move_pin(&third.id, 0).unwrap());
+        let hits: Vec<String> = store
+            .search("")
+            .unwrap()
+            .into_iter()
+            .map(|hit| hit.note.id)
+            .collect();
+        assert_eq!(
+            hits,
+            [second.id.as_str(), first.id.as_str(), third.id.as_str()]
+        );
+
+        // The order is kept in the vault, and deleting a note unpins it.
+        store.delete(&second.id).unwrap();
+        let reopened = NotesStore::open(store.dir().into()).unwrap();
+        assert_eq!(ids(&reopened), [first.id.as_str(), third.id.as_str()]);
+        assert!(reopened.unpin(&first.id).unwrap());
+        assert!(reopened.toggle_pinned(&first.id).unwrap());
+        assert!(!reopened.toggle_pinned(&first.id).unwrap());
+        assert_eq!(ids(&reopened), [third.id.as_str(), first.id.as_str()]);
+    }
+
+    #[test]
+    fn remembers_window_frames_per_note() {
+        let (_dir, store) = store();
+        let note = upsert(&store, "", "Groceries");
+        assert_eq!(store.window_frame(&note.id), None);
+
+        let frame = WindowFrame {
+            bounds: Rect { x: 40.0, y: 60.0, width: 420.0, height: 300.0 },
+            manually_sized: true,
+        };
+        store.set_window_frame(&note.id, frame).unwrap();
+        assert!(store.set_window_frame("note_missing", frame).is_err());
+
+        // Frames are kept in the vault, and deleting a note drops its.
+        let reopened = NotesStore::open(store.dir().into()).unwrap();
+        assert_eq!(reopened.window_frame(&note.id), Some(frame));
+        reopened.delete(&note.id).unwrap();
+        let reopened = NotesStore::open(store.dir().into()).unwrap();
+        assert_eq!(reopened.window_frame(&note.id), None);
+    }
+
+    #[test]
+    fn filters_search_by_color_label() {
+        let (_dir, store) = store();
+        let urgent = upsert(&store, "", "Call the plumber");
+        upsert(&store, "", "Call mom");
+        let path = store.path(&urgent.id).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        fs::write(&path, text.replace("---\n\n", "color: Red\n---\n\n"))
+            .unwrap();
+
+        assert_eq!(store.get(&urgent.id).unwrap().color, Some(NoteColor::Red));
+        let summaries = store.summaries().unwrap();
+        let summary = summaries.iter().find(|s| s.id == urgent.id).unwrap();
+        assert_eq!(summary.color, Some(NoteColor::Red));
+        let hits = |query| -> Vec<String> {
+            let hits = store.search(query).unwrap();
+            hits.into_iter().map(|hit| hit.note.id).collect()
+        };
+        assert_eq!(hits("call").len(), 2);
+        assert_eq!(hits("call color:red"), vec![urgent.id.clone()]);
+        assert!(hits("color:blue").is_empty());
+        assert!(hits("color:teal").is_empty());
+    }
+
     #[test]
     fn titles_drop_markdown_syntax() {
         assert_eq!(
@@ -1002,4 +3022,56 @@ mod tests {
         let path = store.lookup(&note.id).unwrap();
         assert_eq!(path.file_name().unwrap(), "untitled.md");
     }
+
+    #[test]
+    fn summaries_match_full_listing() {
+        let (_dir, store) = store();
+        upsert(&store, "", "# First\n\nbody");
+        let dir = store.dir().to_path_buf();
+        fs::write(dir.join("hand.md"), "\n\nWritten *by hand*\n").unwrap();
+        fs::write(dir.join("legacy.md"), b"caf\xE9 notes\n").unwrap();
+        fs::write(dir.join("wide.md"), b"\xFF\xFEh\0i\0\n\0").unwrap();
+        let heavy = format!(
+            "---\nid: note_heavy\ncreatedAt: 2024-01-01T00:00:00Z\n\
+             updatedAt: 2024-01-02T00:00:00Z\ntags: [logs]\n---\n\n\
+             Pasted log\n{}#inline\n",
+            "line\n".repeat(200_000)
+        );
+        fs::write(dir.join("log.md"), heavy).unwrap();
+
+        // Fresh files go through the head-only path; the full cache
+        // knows none of them yet. Once the full listing has read them,
+        // summaries come from there.
+        let summaries = store.summaries().unwrap();
+        let listed: Vec<String> =
+            store.list().unwrap().into_iter().map(|n| n.id).collect();
+        assert_eq!(store.summaries().unwrap(), summaries);
+        let ids: Vec<String> = summaries.iter().map(|s| s.id.clone()).collect();
+        assert_eq!(ids, listed);
+
+        let titles: Vec<&str> =
+            summaries.iter().map(|s| s.title.as_str()).collect();
+        for title in ["First", "Written by hand", "café notes", "hi"] {
+            assert!(titles.contains(&title), "{title} in {titles:?}");
+        }
+        assert_eq!(store.get("note_heavy").unwrap().tags, ["logs", "inline"]);
+    }
+
+    #[test]
+    fn summaries_adopt_and_track_like_a_full_scan() {
+        let (_dir, store) = store();
+        let path = store.dir().join("hand.md");
+        fs::write(&path, "Plain\n").unwrap();
+        let id = store.summaries().unwrap()[0].id.clone();
+        assert!(fs::read_to_string(&path).unwrap().contains(&id));
+        assert_eq!(store.get(&id).unwrap().content, "Plain");
+
+        let (_dir, store) = non_invasive_store();
+        let path = store.dir().join("hand.md");
+        fs::write(&path, "Plain\n").unwrap();
+        let id = store.summaries().unwrap()[0].id.clone();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "Plain\n");
+        assert_eq!(store.summaries().unwrap()[0].id, id);
+        assert_eq!(store.list().unwrap()[0].id, id);
+    }
 }
@@ -0,0 +1,57 @@
+use std::thread;
+use std::time::Duration;
+
+use chrono::Utc;
+use log::{error, warn};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::store::NotesStore;
+use crate::watcher::NOTES_CHANGED;
+
+/// The event announcing notes that expired (see [`crate::expiry`]).
+/// The payload is the list of [`crate::models::ExpiredNote`]s.
+pub const NOTES_EXPIRED: &str = "notes:expired";
+
+/// How often the sweeper looks for expired and idle notes.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Start moving expired notes out of the vault, and archiving idle ones
+/// (see [`crate::archive`]), in the background. Call once at setup,
+/// after the store is managed.
+///
+/// Each sweep that expires notes emits [`NOTES_EXPIRED`] with them,
+/// for the app to close their windows; any sweep that changes notes
+/// then emits [`NOTES_CHANGED`]. The first sweep waits a full
+/// interval, so the app is listening by then.
+pub fn start<R: Runtime>(app_handle: &AppHandle<R>) {
+    let handle = app_handle.clone();
+    thread::spawn(move || loop {
+        thread::sleep(SWEEP_INTERVAL);
+        sweep(&handle);
+    });
+}
+
+fn sweep<R: Runtime>(app_handle: &AppHandle<R>) {
+    let store = app_handle.state::<NotesStore>();
+    let now = Utc::now();
+    let archived = store.archive_idle(now).unwrap_or_else(|e| {
+        warn!("Failed to archive idle notes: {e}");
+        Vec::new()
+    });
+    let expired = store.expire(now).unwrap_or_else(|e| {
+        warn!("Failed to expire notes: {e}");
+        Vec::new()
+    });
+    if archived.is_empty() && expired.is_empty() {
+        return;
+    }
+
+    if !expired.is_empty() {
+        if let Err(e) = app_handle.emit(NOTES_EXPIRED, &expired) {
+            error!("Failed to emit {NOTES_EXPIRED}: {e}");
+        }
+    }
+    if let Err(e) = app_handle.emit(NOTES_CHANGED, None::<String>) {
+        error!("Failed to emit {NOTES_CHANGED}: {e}");
+    }
+}
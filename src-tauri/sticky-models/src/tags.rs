@@ -0,0 +1,269 @@
+//! Note tags: the `tags` frontmatter field plus inline `#hashtags`.
+//!
+//! Tags compare case-insensitively and are reported lowercase, without
+//! the `#`. They nest with `/`: `#work/sticky` is within `work`, and
+//! renaming `work` renames it too. Hashtags inside code spans and
+//! fenced code blocks don't count, nor do ones made only of digits,
+//! which are more likely issue numbers.
+
+use std::ops::Range;
+
+use sticky_matter::{Document, Value};
+
+use crate::markdown::prose;
+
+/// The frontmatter field holding a note's tags.
+const TAGS: &str = "tags";
+
+/// Every tag of a note, header tags first, without duplicates.
+pub fn note_tags(doc: &Document) -> Vec<String> {
+    let mut tags = header_tags(doc);
+    for tag in inline_tags(doc.body()) {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// The tags in a note's `tags` field: a list, or a string of tags
+/// separated by commas or spaces.
+pub fn header_tags(doc: &Document) -> Vec<String> {
+    let names: Vec<String> = match doc.value(TAGS) {
+        Some(Value::List(items)) => items
+            .iter()
+            .filter_map(|item| item.as_str().map(str::to_string))
+            .collect(),
+        Some(Value::String(s)) => {
+            tokens(&s).map(|(_, token)| token.to_string()).collect()
+        }
+        _ => Vec::new(),
+    };
+
+    let mut tags = Vec::new();
+    for tag in names.iter().filter_map(|name| normalize(name)) {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// The inline hashtags of a markdown body, in order of first use.
+pub fn inline_tags(body: &str) -> Vec<String> {
+    let mut tags = Vec::new();
+    for range in hashtags(body) {
+        let tag = body[range].to_lowercase();
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// A tag as given by a user, lowercase and without its `#`, or `None`
+/// if it isn't a valid tag.
+pub fn normalize(tag: &str) -> Option<String> {
+    let tag = tag.trim();
+    let tag = tag.strip_prefix('#').unwrap_or(tag);
+    let valid = !tag.is_empty()
+        && tag.chars().all(is_tag_char)
+        && !tag.chars().all(|c| c.is_ascii_digit());
+    valid.then(|| tag.to_lowercase())
+}
+
+/// Whether `tag` is `within` itself or one of its nested tags.
+pub fn is_within(tag: &str, within: &str) -> bool {
+    tag.strip_prefix(within)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Rename the tag `from` (a normalized tag) and its nested tags to
+/// `to` in a note's header and body. Returns whether anything changed.
+///
+/// Header lists keep their style and the other items their text;
+/// duplicates the rename creates are dropped.
+pub fn rename(doc: &mut Document, from: &str, to: &str) -> bool {
+    let renamed = |tag: &str| {
+        let bare = tag.strip_prefix('#').unwrap_or(tag);
+        let hash = &tag[..tag.len() - bare.len()];
+        nested_part(bare, from).map(|rest| format!("{hash}{to}{rest}"))
+    };
+
+    let mut changed = false;
+    match doc.value(TAGS) {
+        Some(Value::List(items)) => {
+            let mut seen = Vec::new();
+            let mut renamed_items = Vec::new();
+            for item in items {
+                let item = match &item {
+                    Value::String(s) => match renamed(s) {
+                        Some(new) => {
+                            changed = true;
+                            Value::String(new)
+                        }
+                        None => item,
+                    },
+                    _ => item,
+                };
+                let key = item.as_str().and_then(normalize);
+                if key.is_none() || !seen.contains(&key) {
+                    seen.push(key);
+                    renamed_items.push(item);
+                }
+            }
+            if changed {
+                doc.set_value(TAGS, Value::List(renamed_items));
+            }
+        }
+        Some(Value::String(s)) => {
+            let mut text = s.clone();
+            for (range, token) in
+                tokens(&s).collect::<Vec<_>>().into_iter().rev()
+            {
+                if let Some(new) = renamed(token) {
+                    text.replace_range(range, &new);
+                }
+            }
+            if text != s {
+                doc.set(TAGS, text);
+                changed = true;
+            }
+        }
+        _ => {}
+    }
+
+    let body = doc.body();
+    let mut text = body.to_string();
+    for range in hashtags(body).into_iter().rev() {
+        if let Some(new) = renamed(&body[range.clone()]) {
+            text.replace_range(range, &new);
+        }
+    }
+    if text != body {
+        doc.set_body(text);
+        changed = true;
+    }
+    changed
+}
+
+/// What follows `within` (a normalized tag) in `tag`, if `tag` is it
+/// or nested in it: empty or starting with `/`.
+///
+/// Compares char by char, as lowercasing can change a tag's length in
+/// bytes: the Kelvin sign, U+212A, lowercases to a plain `k`.
+fn nested_part<'t>(tag: &'t str, within: &str) -> Option<&'t str> {
+    let mut wanted = within.chars().peekable();
+    let mut rest = "";
+    for (i, c) in tag.char_indices() {
+        if wanted.peek().is_none() {
+            rest = &tag[i..];
+            break;
+        }
+        if !c.to_lowercase().all(|lower| wanted.next() == Some(lower)) {
+            return None;
+        }
+    }
+    let whole = wanted.next().is_none();
+    (whole && (rest.is_empty() || rest.starts_with('/'))).then_some(rest)
+}
+
+/// The tags in a `tags` string, with their byte ranges.
+fn tokens(text: &str) -> impl Iterator<Item = (Range<usize>, &str)> {
+    let mut pos = 0;
+    text.split(|c: char| c == ',' || c.is_ascii_whitespace()).filter_map(
+        move |token| {
+            let start = pos;
+            pos += token.len() + 1;
+            (!token.is_empty()).then_some((start..start + token.len(), token))
+        },
+    )
+}
+
+/// The byte ranges of inline hashtags, without their `#`, skipping
+/// code.
+fn hashtags(body: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    for part in prose(body) {
+        let text = &body[part.clone()];
+        let mut prev: Option<char> = None;
+        for (i, c) in text.char_indices() {
+            let starts_tag = c == '#'
+                && !prev.is_some_and(|p| {
+                    p.is_alphanumeric() || matches!(p, '#' | '&' | '/' | '_')
+                });
+            if starts_tag {
+                let rest = &text[i + 1..];
+                let len =
+                    rest.len() - rest.trim_start_matches(is_tag_char).len();
+                let tag = rest[..len].trim_end_matches(['/', '-']);
+                if normalize(tag).is_some() {
+                    let start = part.start + i + 1;
+                    ranges.push(start..start + tag.len());
+                }
+            }
+            prev = Some(c);
+        }
+    }
+    ranges
+}
+
+fn is_tag_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '/')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_inline_tags_outside_code() {
+        let body = "#Todo and #work/sticky, not C# or a#b or #123.\n\
+                    `#code` ``#more ` code`` x#y #end-\n\
+                    ```\n#fenced\n```\n\
+                    # Heading #last";
+        assert_eq!(inline_tags(body), ["todo", "work/sticky", "end", "last"]);
+    }
+
+    #[test]
+    fn reads_header_lists_and_strings() {
+        let doc = Document::parse("---\ntags: [A, '#b', 2024, a]\n---\n#c\n");
+        assert_eq!(note_tags(&doc), ["a", "b", "c"]);
+
+        let doc = Document::parse("---\ntags: x, y z\n---\n");
+        assert_eq!(header_tags(&doc), ["x", "y", "z"]);
+    }
+
+    #[test]
+    fn renames_nested_tags_everywhere() {
+        let mut doc = Document::parse(
+            "---\ntags:\n  - Work # main\n  - home\n  - job\n---\n\n\
+             #work/sticky and #workshop `#work`\n",
+        );
+        assert!(rename(&mut doc, "work", "job"));
+        assert_eq!(
+            doc.render(),
+            "---\ntags:\n  - job\n  - home\n---\n\n\
+             #job/sticky and #workshop `#work`\n"
+        );
+        assert!(!rename(&mut doc, "work", "job"));
+
+        let mut doc = Document::parse("---\ntags: a, b\n---\n");
+        assert!(rename(&mut doc, "a", "c"));
+        assert_eq!(doc.get("tags"), Some("c, b"));
+
+        // The Kelvin sign is three bytes, its lowercase `k` one.
+        let mut doc = Document::parse("#\u{212A}/x and #\u{212A}ey\n");
+        assert!(rename(&mut doc, "k", "j"));
+        assert_eq!(doc.body(), "#j/x and #\u{212A}ey");
+    }
+
+    #[test]
+    fn normalizes_user_input() {
+        assert_eq!(normalize(" #Work "), Some("work".to_string()));
+        assert_eq!(normalize("two words"), None);
+        assert_eq!(normalize("42"), None);
+        assert!(is_within("work/sticky", "work"));
+        assert!(!is_within("workshop", "work"));
+    }
+}
@@ -0,0 +1,299 @@
+//! Checklist tasks: `- [ ]` and `- [x]` list items in note bodies.
+//!
+//! Any list marker works (`-`, `*`, `+`, `1.`, `1)`). Tasks nest by
+//! indentation, like the lists holding them, and may carry a due date
+//! as `@due(2026-10-19)`. Items inside fenced code blocks aren't tasks.
+
+use chrono::NaiveDate;
+
+use crate::markdown::unfenced_lines;
+use crate::models::{Task, TaskRef};
+
+/// How many columns a tab indents by.
+const TAB_WIDTH: usize = 4;
+
+/// A task line as found in a body.
+#[derive(Debug)]
+struct Item<'a> {
+    /// The 0-based line of the body.
+    line: usize,
+    /// The byte offset of the line in the body.
+    offset: usize,
+    source: &'a str,
+    indent: usize,
+    /// The byte offset of the checkbox's `[` in the line.
+    checkbox: usize,
+    done: bool,
+    text: &'a str,
+}
+
+/// A line of a body that matters to tasks.
+#[derive(Debug)]
+enum Line<'a> {
+    Task(Item<'a>),
+    /// A list item without a checkbox, at this indentation.
+    Item(usize),
+    /// A line ending the lists above it, like a paragraph or heading.
+    Break,
+}
+
+/// The tasks of a note, in body order.
+pub fn note_tasks(note_id: &str, note_title: &str, body: &str) -> Vec<Task> {
+    let mut tasks: Vec<Task> = Vec::new();
+    // The indentation of each open ancestor list item, and its line
+    // if it is a task.
+    let mut open: Vec<(usize, Option<usize>)> = Vec::new();
+
+    for line in lines(body) {
+        let (indent, item) = match line {
+            Line::Task(item) => (item.indent, Some(item)),
+            Line::Item(indent) => (indent, None),
+            Line::Break => {
+                open.clear();
+                continue;
+            }
+        };
+        while open.last().is_some_and(|&(open, _)| open >= indent) {
+            open.pop();
+        }
+        let Some(item) = item else {
+            open.push((indent, None));
+            continue;
+        };
+
+        let (text, due) = due_date(item.text);
+        let ancestors: Vec<usize> =
+            open.iter().filter_map(|&(_, line)| line).collect();
+        tasks.push(Task {
+            note_id: note_id.to_string(),
+            note_title: note_title.to_string(),
+            task_ref: TaskRef {
+                line: item.line as u32,
+                source: item.source.to_string(),
+            },
+            text,
+            done: item.done,
+            due,
+            depth: ancestors.len() as u32,
+            parent_line: ancestors.last().map(|&line| line as u32),
+        });
+        open.push((indent, Some(item.line)));
+    }
+    tasks
+}
+
+/// Tick or untick the task `task` points at, returning the new body
+/// and the task's line, or `None` if the task can't be found.
+///
+/// The task is looked for on its line first. If that line changed, a
+/// single other task line with the same source is taken to be it,
+/// moved by edits above it; with none or several, nothing is toggled.
+pub fn toggle(body: &str, task: &TaskRef) -> Option<(String, usize)> {
+    let items: Vec<Item> = lines(body)
+        .into_iter()
+        .filter_map(|line| match line {
+            Line::Task(item) => Some(item),
+            _ => None,
+        })
+        .collect();
+    let item = items
+        .iter()
+        .find(|item| {
+            item.line == task.line as usize && item.source == task.source
+        })
+        .or_else(|| {
+            let mut same =
+                items.iter().filter(|item| item.source == task.source);
+            same.next().filter(|_| same.next().is_none())
+        })?;
+
+    let at = item.offset + item.checkbox + 1;
+    let mark = if item.done { " " } else { "x" };
+    let mut text = body.to_string();
+    text.replace_range(at..at + 1, mark);
+    Some((text, item.line))
+}
+
+/// The lines of a body that matter to tasks. Blank lines and
+/// indented continuation lines are left out.
+fn lines(body: &str) -> Vec<Line<'_>> {
+    let mut out = Vec::new();
+    let mut line = 0;
+    let mut pos = 0;
+
+    for (offset, source) in unfenced_lines(body) {
+        line += body[pos..offset].matches('\n').count();
+        pos = offset;
+
+        let rest = source.trim_start_matches([' ', '\t']);
+        if let Some(item) = parse_item(line, offset, source) {
+            out.push(Line::Task(item));
+        } else if list_marker(rest).is_some() {
+            out.push(Line::Item(indent(source)));
+        } else if !rest.is_empty() && rest.len() == source.len() {
+            out.push(Line::Break);
+        }
+    }
+    out
+}
+
+fn parse_item(line: usize, offset: usize, source: &str) -> Option<Item<'_>> {
+    let rest = source.trim_start_matches([' ', '\t']);
+    let marker = list_marker(rest)?;
+    let after = &rest[marker..];
+    let checkbox = source.len() - after.len();
+    let done = match after.get(..3)? {
+        "[ ]" => false,
+        "[x]" | "[X]" => true,
+        _ => return None,
+    };
+    let text = &after[3..];
+    if !(text.is_empty() || text.starts_with([' ', '\t'])) {
+        return None;
+    }
+
+    Some(Item {
+        line,
+        offset,
+        source,
+        indent: indent(source),
+        checkbox,
+        done,
+        text: text.trim(),
+    })
+}
+
+/// The indentation of a line, in columns.
+fn indent(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| matches!(c, ' ' | '\t'))
+        .map(|c| if c == '\t' { TAB_WIDTH } else { 1 })
+        .sum()
+}
+
+/// The length of a list marker starting `text`, with the whitespace
+/// after it.
+fn list_marker(text: &str) -> Option<usize> {
+    let digits = text.len()
+        - text.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let len = match text[digits..].chars().next()? {
+        '-' | '*' | '+' if digits == 0 => 1,
+        '.' | ')' if (1..=9).contains(&digits) => digits + 1,
+        _ => return None,
+    };
+    let rest = &text[len..];
+    let space = rest.len() - rest.trim_start_matches([' ', '\t']).len();
+    (space > 0).then_some(len + space)
+}
+
+/// Split an `@due(…)` date off a task's text. A date that doesn't
+/// parse as `YYYY-MM-DD` stays in the text.
+fn due_date(text: &str) -> (String, Option<NaiveDate>) {
+    let Some(start) = text.find("@due(") else {
+        return (text.to_string(), None);
+    };
+    let Some(len) = text[start..].find(')') else {
+        return (text.to_string(), None);
+    };
+    let date = &text[start + "@due(".len()..start + len];
+    match NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d") {
+        Ok(due) => {
+            let before = text[..start].trim_end();
+            let after = text[start + len + 1..].trim_start();
+            let text = match (before.is_empty(), after.is_empty()) {
+                (false, false) => format!("{before} {after}"),
+                _ => format!("{before}{after}"),
+            };
+            (text, Some(due))
+        }
+        Err(_) => (text.to_string(), None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brief(body: &str) -> Vec<(u32, &str, bool, u32, Option<u32>)> {
+        let tasks = note_tasks("note_a", "Title", body);
+        let mut out = Vec::new();
+        for task in &tasks {
+            let text = body.lines().nth(task.task_ref.line as usize).unwrap();
+            out.push((
+                task.task_ref.line,
+                text.trim(),
+                task.done,
+                task.depth,
+                task.parent_line,
+            ));
+        }
+        out
+    }
+
+    #[test]
+    fn finds_nested_tasks_outside_code() {
+        let body = "# Plan\n\
+                    - [ ] Pack\n\
+                    \x20 - [x] Socks\n\
+                    \x20 - Clothes\n\
+                    \x20   * [ ] Shirts\n\
+                    \n\
+                    1. [X] Book\n\
+                    -[ ] no space\n\
+                    - [ ]no space\n\
+                    ```\n- [ ] fenced\n```\n\
+                    Done.\n\
+                    \x20 - [ ] after a paragraph\n";
+        assert_eq!(
+            brief(body),
+            [
+                (1, "- [ ] Pack", false, 0, None),
+                (2, "- [x] Socks", true, 1, Some(1)),
+                (4, "* [ ] Shirts", false, 1, Some(1)),
+                (6, "1. [X] Book", true, 0, None),
+                (13, "- [ ] after a paragraph", false, 0, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_due_dates() {
+        let tasks = note_tasks(
+            "note_a",
+            "Title",
+            "- [ ] Pay rent @due(2026-11-01) online\n\
+             - [ ] @due(2026-11-02)\n\
+             - [ ] Later @due(soon)",
+        );
+        let due: Vec<(&str, Option<String>)> = tasks
+            .iter()
+            .map(|t| (t.text.as_str(), t.due.map(|d| d.to_string())))
+            .collect();
+        assert_eq!(
+            due,
+            [
+                ("Pay rent online", Some("2026-11-01".into())),
+                ("", Some("2026-11-02".into())),
+                ("Later @due(soon)", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn toggles_the_task_even_if_it_moved() {
+        let body = "- [ ] One\n- [x] Two\n";
+        let two = note_tasks("n", "t", body)[1].task_ref.clone();
+        let (body, line) = toggle(body, &two).unwrap();
+        assert_eq!((body.as_str(), line), ("- [ ] One\n- [ ] Two\n", 1));
+
+        // Edited elsewhere: a line inserted above the task.
+        let one = note_tasks("n", "t", &body)[0].task_ref.clone();
+        let moved = format!("Intro\n{body}");
+        let (body, line) = toggle(&moved, &one).unwrap();
+        assert_eq!((body.as_str(), line), ("Intro\n- [x] One\n- [ ] Two\n", 1));
+
+        // The task itself changed, or is ambiguous: nothing to toggle.
+        assert_eq!(toggle("- [ ] One!\n", &one), None);
+        assert_eq!(toggle("x\n- [ ] One\n- [ ] One\n", &one), None);
+    }
+}
@@ -0,0 +1,153 @@
+//! Note templates: markdown files in the vault's `templates` folder.
+//!
+//! A template's `{{variables}}` are filled in when a note is created
+//! from it, in its header as well as its body:
+//!
+//! - `{{date}}` and `{{time}}`: the current local date (`2026-10-19`)
+//!   and time (`14:05`). A `strftime` format may follow a colon, as in
+//!   `{{date:%A, %B %d}}`.
+//! - `{{title}}`: the title asked for, or the template's name.
+//! - `{{clipboard}}`: the text on the clipboard.
+//! - Any other name is a prompt: the user is asked for its value.
+//!
+//! A variable given no value, or an invalid format, stays as written.
+
+use std::collections::HashMap;
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::NaiveDateTime;
+use sticky_matter::Value;
+
+/// The folder of the vault holding the templates.
+pub const TEMPLATES_DIR: &str = "templates";
+
+/// The variables filled in without asking.
+const BUILT_INS: [&str; 4] = ["date", "time", "title", "clipboard"];
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const TIME_FORMAT: &str = "%H:%M";
+
+/// The prompts of a template, in order of first use.
+pub fn prompts(text: &str) -> Vec<String> {
+    let mut prompts: Vec<String> = Vec::new();
+    for (_, name) in variables(text) {
+        let (key, _) = split(name);
+        if !BUILT_INS.contains(&key) && !prompts.iter().any(|p| p == key) {
+            prompts.push(key.to_string());
+        }
+    }
+    prompts
+}
+
+/// Fill in a template's variables. `values` holds the title, the
+/// clipboard and the prompts' answers, by name; `now` is the local
+/// time the note is created at.
+pub fn expand(
+    text: &str,
+    values: &HashMap<String, String>,
+    now: NaiveDateTime,
+) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut pos = 0;
+    for (range, name) in variables(text) {
+        out.push_str(&text[pos..range.start]);
+        let (key, format) = split(name);
+        let value = match (key, format) {
+            ("date", format) => time(now, format.unwrap_or(DATE_FORMAT)),
+            ("time", format) => time(now, format.unwrap_or(TIME_FORMAT)),
+            (key, _) => values.get(key).cloned(),
+        };
+        match value {
+            Some(value) => out.push_str(&value),
+            None => out.push_str(&text[range.clone()]),
+        }
+        pos = range.end;
+    }
+    out.push_str(&text[pos..]);
+    out
+}
+
+/// [`expand`] for a header field's value: fills in the variables of
+/// each string in it, keeping lists and mappings as they are.
+pub fn expand_value(
+    value: &Value,
+    values: &HashMap<String, String>,
+    now: NaiveDateTime,
+) -> Value {
+    match value {
+        Value::String(text) => Value::String(expand(text, values, now)),
+        Value::List(items) => Value::List(
+            items.iter().map(|item| expand_value(item, values, now)).collect(),
+        ),
+        Value::Map(entries) => Value::Map(
+            entries
+                .iter()
+                .map(|(key, v)| (key.clone(), expand_value(v, values, now)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// The `{{…}}` variables of a text, with their byte ranges and trimmed
+/// contents.
+fn variables(text: &str) -> Vec<(std::ops::Range<usize>, &str)> {
+    let mut found = Vec::new();
+    let mut pos = 0;
+    while let Some(open) = text[pos..].find("{{").map(|i| pos + i) {
+        let Some(len) = text[open + 2..].find("}}") else {
+            break;
+        };
+        let end = open + 2 + len + 2;
+        let name = text[open + 2..end - 2].trim();
+        if name.is_empty() || name.contains(['{', '\n']) {
+            pos = open + 2;
+            continue;
+        }
+        found.push((open..end, name));
+        pos = end;
+    }
+    found
+}
+
+/// A variable's name and the format after its colon, if any.
+fn split(name: &str) -> (&str, Option<&str>) {
+    match name.split_once(':') {
+        Some((key, format)) => (key.trim(), Some(format.trim())),
+        None => (name, None),
+    }
+}
+
+/// `now` formatted with a `strftime` format, or `None` if the format
+/// is invalid.
+fn time(now: NaiveDateTime, format: &str) -> Option<String> {
+    let items: Vec<Item> = StrftimeItems::new(format).collect();
+    if items.iter().any(|item| matches!(item, Item::Error)) {
+        return None;
+    }
+    Some(now.format_with_items(items.into_iter()).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_built_ins_and_prompts() {
+        let now = "2026-10-19T14:05:00".parse().unwrap();
+        let values = HashMap::from([
+            ("title".to_string(), "Standup".to_string()),
+            ("Attendees".to_string(), "Ana, Bo".to_string()),
+        ]);
+        let text = "# {{title}} {{ date }}\n{{date:%A}} at {{time}}\n\
+                    With {{Attendees}}. {{clipboard}}{{Notes}}\n\
+                    {{date:%Q}} {{}} {{ open";
+        assert_eq!(
+            expand(text, &values, now),
+            "# Standup 2026-10-19\nMonday at 14:05\n\
+             With Ana, Bo. {{clipboard}}{{Notes}}\n\
+             {{date:%Q}} {{}} {{ open"
+        );
+        assert_eq!(prompts(text), ["Attendees", "Notes"]);
+    }
+}
@@ -1,6 +1,6 @@
 import { Autocomplete } from '@base-ui/react/autocomplete';
 import type { NoteSearchHit } from '@sticky/models';
-import { Trash2Icon } from 'lucide-react';
+import { ArchiveIcon, HourglassIcon, PinIcon, Trash2Icon } from 'lucide-react';
 import { getRelativeTime } from '~/lib/date';
 import { escapeRegExp } from '~/lib/string';
 import { Button } from './ui/button';
@@ -47,7 +47,11 @@ export function SearchNoteItem(props: SearchNoteItemProps) {
       className="text-muted-foreground data-[highlighted]:bg-muted data-[highlighted]:text-foreground group relative flex w-full items-center justify-between gap-2 rounded-md p-2 text-left"
     >
       <div className="flex min-w-0 flex-col gap-1">
-        <Text size="2" className="w-full truncate font-medium">
+        <Text
+          size="2"
+          className="flex w-full items-center gap-1 truncate font-medium"
+        >
+          {hit.note.pinned && <PinIcon className="h-3 w-3 shrink-0" />}
           <Highlighted text={hit.title} terms={terms} />
         </Text>
 
@@ -63,6 +67,20 @@ export function SearchNoteItem(props: SearchNoteItemProps) {
           <Text className="text-faint text-[13px]">
             Updated {getRelativeTime(hit.note.updatedAt)}
           </Text>
+
+          {hit.note.expiresAt && (
+            <Text className="text-faint flex items-center gap-1 text-[13px]">
+              <HourglassIcon className="h-3 w-3" />
+              Expires {getRelativeTime(hit.note.expiresAt)}
+            </Text>
+          )}
+
+          {hit.note.archived && (
+            <Text className="text-faint flex items-center gap-1 text-[13px]">
+              <ArchiveIcon className="h-3 w-3" />
+              Archived
+            </Text>
+          )}
         </div>
       </div>
 
@@ -1,5 +1,6 @@
 import { useHotkey } from '@tanstack/react-hotkeys';
 import { useQueryClient } from '@tanstack/react-query';
+import { invoke } from '@tauri-apps/api/core';
 import { EditorContent, useEditor } from '@tiptap/react';
 import { useCallback, useEffect, useRef, useState } from 'react';
 import { Divider } from '~/components/divider';
@@ -77,8 +78,17 @@ export function SkeletonEditor(props: SkeletonEditorProps) {
   const title = useNoteTitle(editor);
 
   const queryClient = useQueryClient();
-  useOnFocusChanged(() => {
+  useOnFocusChanged((focused) => {
     queryClient.invalidateQueries(listNotesOptions());
+    if (focused || !currentNoteId) {
+      return;
+    }
+
+    // A retitled note offers to update the links to its old title once
+    // the user moves on, not on every save while the title is typed.
+    flush().then(() =>
+      invoke('cmd_offer_link_rewrite', { noteId: currentNoteId })
+    );
   });
 
   useNoteSync(editor, currentNoteId, isDirtyRef);
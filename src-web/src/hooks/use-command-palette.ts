@@ -4,7 +4,10 @@ import { getCurrentWindow } from '@tauri-apps/api/window';
 import { useEffect, useRef } from 'react';
 import type { CommandContext } from '~/lib/commands';
 
-type CommandActions = Record<string, () => void | Promise<void>>;
+type CommandActions = Record<
+  string,
+  (argument?: string) => void | Promise<void>
+>;
 
 export function useCommandPalette(
   getContext: () => CommandContext,
@@ -39,7 +42,11 @@ export function useCommandPalette(
     const unlisten = getCurrentWindow().listen<string>(
       'command:action',
       (event) => {
-        actionsRef.current[event.payload]?.();
+        // Some commands carry an argument after a colon, like the
+        // template name of `new-from-template:Standup`.
+        const [id, ...rest] = event.payload.split(':');
+        const argument = rest.length > 0 ? rest.join(':') : undefined;
+        actionsRef.current[id]?.(argument);
       }
     );
 
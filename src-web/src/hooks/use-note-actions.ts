@@ -1,4 +1,9 @@
-import { MIN_WINDOW_HEIGHT, MIN_WINDOW_WIDTH, type Note } from '@sticky/models';
+import {
+  MIN_WINDOW_HEIGHT,
+  MIN_WINDOW_WIDTH,
+  type Note,
+  type NoteDiagnostic,
+} from '@sticky/models';
 import { useQueryClient } from '@tanstack/react-query';
 import { useNavigate } from '@tanstack/react-router';
 import { invoke } from '@tauri-apps/api/core';
@@ -122,6 +127,56 @@ export function useNoteActions(options: NoteActionsOptions) {
     navigate({ to: '/new', replace: true });
   }, [noteId, navigate, isDirtyRef]);
 
+  const toggleArchived = useCallback(async () => {
+    if (!noteId) {
+      return;
+    }
+
+    await flush();
+    const archived = await invoke<boolean>('cmd_toggle_archived', { noteId });
+    queryClient.invalidateQueries(listNotesOptions());
+    // An archived note is set aside; its window goes with it.
+    if (archived) {
+      await getCurrentWindow().close();
+    } else {
+      await invoke('cmd_show_toast', { message: 'Unarchived note' });
+    }
+  }, [noteId, flush, queryClient]);
+
+  const togglePinned = useCallback(async () => {
+    if (!noteId) {
+      return;
+    }
+
+    const pinned = await invoke<boolean>('cmd_toggle_pinned', { noteId });
+    queryClient.invalidateQueries(listNotesOptions());
+    await invoke('cmd_show_toast', {
+      message: pinned ? 'Pinned note' : 'Unpinned note',
+    });
+  }, [noteId, queryClient]);
+
+  // Explains why header fields aren't read; the toast has room for the
+  // first problem, which is the one to fix first anyway.
+  const checkHeader = useCallback(async () => {
+    if (!noteId) {
+      return;
+    }
+
+    await flush();
+    const diagnostics = await invoke<NoteDiagnostic[]>(
+      'cmd_note_diagnostics',
+      { id: noteId }
+    );
+    const [first] = diagnostics;
+    const more =
+      diagnostics.length > 1 ? ` (+${diagnostics.length - 1} more)` : '';
+    await invoke('cmd_show_toast', {
+      message: first
+        ? `Line ${first.line}: ${first.message}${more}`
+        : 'No header problems',
+    });
+  }, [noteId, flush]);
+
   useCommandPalette(
     () => ({
       noteId,
@@ -131,12 +186,23 @@ export function useNoteActions(options: NoteActionsOptions) {
       'new-note': createNote,
       'new-note-here': createNoteHere,
       'duplicate-note': () => openNewNote(editor.getMarkdown()),
+      'toggle-pinned': togglePinned,
+      'toggle-archived': toggleArchived,
+      'open-today-note': () => invoke('cmd_open_daily_note', { offset: 0 }),
+      'open-yesterday-note': () =>
+        invoke('cmd_open_daily_note', { offset: -1 }),
+      'open-tomorrow-note': () => invoke('cmd_open_daily_note', { offset: 1 }),
       'browse-notes': browseNotes,
       'find-in-note': openFind,
       'fit-window': () => {
         fitWindow();
       },
       'toggle-auto-size': toggleAutoSize,
+      'arrange-windows': (arrangement) => {
+        if (arrangement) {
+          invoke('cmd_arrange_windows', { arrangement });
+        }
+      },
       'copy-markdown': () =>
         copyToClipboard(() => editor.getMarkdown(), 'Copied as Markdown'),
       'copy-path': () => {
@@ -152,7 +218,40 @@ export function useNoteActions(options: NoteActionsOptions) {
           invoke('cmd_reveal_note', { noteId });
         }
       },
+      'convert-to-utf8': async () => {
+        if (noteId) {
+          await invoke('cmd_convert_note_to_utf8', { noteId });
+          await invoke('cmd_show_toast', { message: 'Converted to UTF-8' });
+        }
+      },
+      'check-header': checkHeader,
       'open-notes-folder': () => invoke('cmd_open_notes_dir'),
+      'open-templates-folder': () => invoke('cmd_open_templates_dir'),
+      'new-from-template': (name) => {
+        if (name) {
+          invoke('cmd_new_main_window', {
+            url: `/new?template=${encodeURIComponent(name)}`,
+            size: [MIN_WINDOW_WIDTH, MIN_WINDOW_HEIGHT],
+          });
+        }
+      },
+      'apply-layout': (name) => {
+        if (name) {
+          invoke('cmd_apply_layout', { name });
+        }
+      },
+      'save-layout': async (name) => {
+        if (name) {
+          await invoke('cmd_save_layout', { name });
+          await invoke('cmd_show_toast', { message: `Saved ${name}` });
+        }
+      },
+      'delete-layout': async (name) => {
+        if (name) {
+          await invoke('cmd_delete_layout', { name });
+          await invoke('cmd_show_toast', { message: `Deleted ${name}` });
+        }
+      },
       'delete-note': deleteNote,
     }
   );
@@ -183,11 +282,26 @@ export function useNoteActions(options: NoteActionsOptions) {
       }
     );
 
+    // The note's file was deleted outside the app. A window with edits
+    // still to save keeps them: saving brings the file back.
+    const unlistenGone = currentWindow.listen('note:deleted', async () => {
+      if (!isDirtyRef.current) {
+        await currentWindow.close();
+        return;
+      }
+
+      await flush();
+      await invoke('cmd_show_toast', {
+        message: 'Note deleted elsewhere; kept your edits',
+      });
+    });
+
     return () => {
       unlistenSelected.then((fn) => fn());
       unlistenDeleted.then((fn) => fn());
+      unlistenGone.then((fn) => fn());
     };
-  }, [navigate, flush]);
+  }, [navigate, flush, isDirtyRef]);
 
   return { createNote, browseNotes };
 }
@@ -4,6 +4,7 @@ import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 import type { Editor } from '@tiptap/react';
 import { useEffect, type RefObject } from 'react';
+import { applyNoteColor } from '~/lib/note-color';
 import { listNotesOptions } from '~/queries/notes';
 
 export function useNoteSync(
@@ -28,7 +29,12 @@ export function useNoteSync(
       const note = await invoke<Note>('cmd_get_note', {
         id: noteId,
       }).catch(() => null);
-      if (!note || isDirtyRef.current) {
+      if (!note) {
+        return;
+      }
+
+      applyNoteColor(note.color);
+      if (isDirtyRef.current) {
         return;
       }
 
@@ -1,9 +1,11 @@
 import { getCurrentWindow } from '@tauri-apps/api/window';
 import { useEffect } from 'react';
 
-export function useOnFocusChanged(cb: () => void) {
+export function useOnFocusChanged(cb: (focused: boolean) => void) {
   useEffect(() => {
-    const unlisten = getCurrentWindow().onFocusChanged(cb);
+    const unlisten = getCurrentWindow().onFocusChanged(({ payload }) =>
+      cb(payload)
+    );
     return () => {
       unlisten.then((fn) => fn());
     };
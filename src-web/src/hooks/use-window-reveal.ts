@@ -4,10 +4,21 @@ import { useEffect } from 'react';
 
 const MAIN_WINDOW_PREFIX = 'main_';
 
+declare global {
+  interface Window {
+    // Set natively for windows restored hidden from the last session;
+    // the tray shows them again.
+    __STICKY_KEEP_HIDDEN__?: boolean;
+  }
+}
+
 export function useWindowReveal(editor: Editor) {
   useEffect(() => {
     const currentWindow = getCurrentWindow();
-    if (!currentWindow.label.startsWith(MAIN_WINDOW_PREFIX)) {
+    if (
+      !currentWindow.label.startsWith(MAIN_WINDOW_PREFIX) ||
+      window.__STICKY_KEEP_HIDDEN__
+    ) {
       return;
     }
 
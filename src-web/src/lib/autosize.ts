@@ -1,5 +1,8 @@
+import { invoke } from '@tauri-apps/api/core';
 import { getCurrentWindow } from '@tauri-apps/api/window';
 
+// Keep the key in sync with MANUALLY_RESIZED_KEY in
+// src-tauri/src/window/main.rs, which seeds it for new windows.
 const IS_MANUALLY_RESIZED_KEY = '__is_manually_resized__';
 
 const currentWindow = getCurrentWindow();
@@ -10,6 +13,10 @@ export function setIsManuallyResized(isManuallyResized: boolean) {
     `${IS_MANUALLY_RESIZED_KEY}_${currentWindowLabel}`,
     String(isManuallyResized)
   );
+  // Remembered with the note's window frame, natively.
+  invoke('cmd_set_manually_sized', {
+    manuallySized: isManuallyResized,
+  }).catch(() => {});
 }
 
 export function getIsManuallyResized() {
@@ -1,14 +1,30 @@
+import type { NoteTemplate } from '@sticky/models';
 import {
+  ArchiveIcon,
+  CalendarArrowDownIcon,
+  CalendarArrowUpIcon,
+  CalendarIcon,
   ChevronsDownUpIcon,
   ClipboardCopyIcon,
   ClipboardTypeIcon,
   CopyPlusIcon,
   FilePlusIcon,
+  FileStackIcon,
+  FileTypeIcon,
+  FileWarningIcon,
   FolderIcon,
   FolderOpenIcon,
+  FolderPenIcon,
+  Layers2Icon,
   LayersIcon,
+  LayoutDashboardIcon,
+  LayoutGridIcon,
+  PanelRightIcon,
+  PinIcon,
   PlusIcon,
+  SaveIcon,
   SearchIcon,
+  ShrinkIcon,
   SquareDashedIcon,
   Trash2Icon,
   type LucideIcon,
@@ -17,6 +33,9 @@ import {
 export type CommandContext = {
   noteId?: string;
   autoSize: boolean;
+  // Looked up by the native side when it opens the palette.
+  archived?: boolean;
+  pinned?: boolean;
 };
 
 export type Command = {
@@ -61,6 +80,34 @@ export const commandGroups: CommandGroup[] = [
         icon: CopyPlusIcon,
         isAvailable: requiresNote,
       },
+      {
+        id: 'toggle-pinned',
+        label: (context) => (context.pinned ? 'Unpin Note' : 'Pin Note'),
+        icon: PinIcon,
+        isAvailable: requiresNote,
+      },
+      {
+        id: 'toggle-archived',
+        label: (context) =>
+          context.archived ? 'Unarchive Note' : 'Archive Note',
+        icon: ArchiveIcon,
+        isAvailable: requiresNote,
+      },
+      {
+        id: 'open-today-note',
+        label: "Open Today's Note",
+        icon: CalendarIcon,
+      },
+      {
+        id: 'open-yesterday-note',
+        label: "Open Yesterday's Note",
+        icon: CalendarArrowDownIcon,
+      },
+      {
+        id: 'open-tomorrow-note',
+        label: "Open Tomorrow's Note",
+        icon: CalendarArrowUpIcon,
+      },
       {
         id: 'browse-notes',
         label: 'Browse Notes',
@@ -97,11 +144,28 @@ export const commandGroups: CommandGroup[] = [
         icon: FolderOpenIcon,
         isAvailable: requiresNote,
       },
+      {
+        id: 'convert-to-utf8',
+        label: 'Convert File to UTF-8',
+        icon: FileTypeIcon,
+        isAvailable: requiresNote,
+      },
+      {
+        id: 'check-header',
+        label: 'Check Header',
+        icon: FileWarningIcon,
+        isAvailable: requiresNote,
+      },
       {
         id: 'open-notes-folder',
         label: 'Open Notes Folder',
         icon: FolderIcon,
       },
+      {
+        id: 'open-templates-folder',
+        label: 'Open Templates Folder',
+        icon: FolderPenIcon,
+      },
     ],
   },
   {
@@ -121,6 +185,28 @@ export const commandGroups: CommandGroup[] = [
             : 'Enable Window Auto-Sizing',
         icon: SquareDashedIcon,
       },
+      // The arrangement after the colon is `Arrangement` in
+      // sticky-models' geometry.
+      {
+        id: 'arrange-windows:cascade',
+        label: 'Cascade Windows',
+        icon: Layers2Icon,
+      },
+      {
+        id: 'arrange-windows:tile',
+        label: 'Tile Windows',
+        icon: LayoutGridIcon,
+      },
+      {
+        id: 'arrange-windows:stack-right',
+        label: 'Stack Windows Along Right Edge',
+        icon: PanelRightIcon,
+      },
+      {
+        id: 'arrange-windows:gather',
+        label: 'Gather Windows from All Monitors',
+        icon: ShrinkIcon,
+      },
     ],
   },
   {
@@ -143,11 +229,62 @@ export function commandLabel(command: Command, context: CommandContext) {
     : command.label(context);
 }
 
+// One command per template file; the id carries the template's name
+// after the colon, which `useCommandPalette` passes to the handler.
+export function templateCommandGroup(templates: NoteTemplate[]): CommandGroup {
+  return {
+    id: 'templates',
+    title: 'Templates',
+    children: templates.map((template) => ({
+      id: `new-from-template:${template.name}`,
+      label: `New from Template: ${template.name}`,
+      icon: FileStackIcon,
+    })),
+  };
+}
+
+// Applying and deleting the saved window layouts, by name after the
+// colon; typing a name offers saving the windows on screen under it.
+export function layoutCommandGroup(
+  layouts: string[],
+  search: string
+): CommandGroup {
+  const name = search.trim();
+  const saveCommands: Command[] = name
+    ? [
+        {
+          id: `save-layout:${name}`,
+          label: `Save Layout as "${name}"`,
+          icon: SaveIcon,
+        },
+      ]
+    : [];
+
+  return {
+    id: 'layouts',
+    title: 'Layouts',
+    children: [
+      ...layouts.map((layout) => ({
+        id: `apply-layout:${layout}`,
+        label: `Apply Layout: ${layout}`,
+        icon: LayoutDashboardIcon,
+      })),
+      ...saveCommands,
+      ...layouts.map((layout) => ({
+        id: `delete-layout:${layout}`,
+        label: `Delete Layout: ${layout}`,
+        icon: Trash2Icon,
+      })),
+    ],
+  };
+}
+
 export function filterCommandGroups(
   context: CommandContext,
-  search: string
+  search: string,
+  extraGroups: CommandGroup[] = []
 ): CommandGroup[] {
-  return commandGroups
+  return [...commandGroups, ...extraGroups]
     .map((group) => ({
       ...group,
       children: group.children.filter((command) => {
@@ -0,0 +1,13 @@
+import type { NoteColor } from '@sticky/models';
+
+// Tints the window's own background with the note's color label; the
+// `data-color` rules in global.css hold the palette. The native side
+// tints the window behind the webview to match.
+export function applyNoteColor(color: NoteColor | null) {
+  const root = document.documentElement;
+  if (color) {
+    root.dataset.color = color;
+  } else {
+    delete root.dataset.color;
+  }
+}
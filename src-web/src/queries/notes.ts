@@ -1,4 +1,4 @@
-import type { Note, NoteSearchHit } from '@sticky/models';
+import type { Note, NoteSearchHit, NoteTemplate } from '@sticky/models';
 import { keepPreviousData, queryOptions } from '@tanstack/react-query';
 import { invoke } from '@tauri-apps/api/core';
 
@@ -11,6 +11,24 @@ export function listNotesOptions() {
   });
 }
 
+export function listTemplatesOptions() {
+  return queryOptions({
+    queryKey: ['templates'],
+    queryFn: () => {
+      return invoke<NoteTemplate[]>('cmd_list_templates', {});
+    },
+  });
+}
+
+export function listLayoutsOptions() {
+  return queryOptions({
+    queryKey: ['layouts'],
+    queryFn: () => {
+      return invoke<string[]>('cmd_list_layouts', {});
+    },
+  });
+}
+
 // The key starts with 'notes' so the existing list invalidations
 // (saves, deletes, external file edits) refresh search results too.
 export function searchNotesOptions(query: string) {
@@ -1,7 +1,9 @@
 import type { Note } from '@sticky/models';
 import { createFileRoute } from '@tanstack/react-router';
 import { invoke } from '@tauri-apps/api/core';
+import { useEffect } from 'react';
 import { SkeletonEditor } from '~/components/skeleton-editor';
+import { applyNoteColor } from '~/lib/note-color';
 
 export const Route = createFileRoute('/$noteId')({
   component: RouteComponent,
@@ -20,5 +22,10 @@ function RouteComponent() {
   const { noteId } = Route.useParams();
   const { note } = Route.useLoaderData();
 
+  useEffect(() => {
+    applyNoteColor(note.color);
+    return () => applyNoteColor(null);
+  }, [note.color]);
+
   return <SkeletonEditor key={noteId} noteId={noteId} content={note.content} />;
 }
@@ -1,6 +1,7 @@
 import { Autocomplete } from '@base-ui/react/autocomplete';
 import { COMMAND_WINDOW_HEIGHT } from '@sticky/models';
 import { useHotkey } from '@tanstack/react-hotkeys';
+import { useQuery, useQueryClient } from '@tanstack/react-query';
 import { createFileRoute } from '@tanstack/react-router';
 import { emitTo } from '@tauri-apps/api/event';
 import { WebviewWindow } from '@tauri-apps/api/webviewWindow';
@@ -19,10 +20,13 @@ import { Text } from '~/components/ui/text';
 import {
   commandLabel,
   filterCommandGroups,
+  layoutCommandGroup,
+  templateCommandGroup,
   type Command,
   type CommandContext,
   type CommandGroup,
 } from '~/lib/commands';
+import { listLayoutsOptions, listTemplatesOptions } from '~/queries/notes';
 
 // A group shaped for Base UI, which recognizes grouped items by an
 // `items` key on each entry.
@@ -41,12 +45,15 @@ export const Route = createFileRoute('/commands')({
     parent: typeof search.parent === 'string' ? search.parent : '',
     noteId: typeof search.noteId === 'string' ? search.noteId : undefined,
     autoSize: search.autoSize !== false && search.autoSize !== 'false',
+    archived: search.archived === true || search.archived === 'true',
+    pinned: search.pinned === true || search.pinned === 'true',
     prewarm: search.prewarm === true,
   }),
 });
 
 function CommandsPage() {
-  const { parent, noteId, autoSize, prewarm } = Route.useSearch();
+  const { parent, noteId, autoSize, archived, pinned, prewarm } =
+    Route.useSearch();
 
   const [search, setSearch] = useState('');
   // The panel outlives its parent's navigations and state changes, so
@@ -54,14 +61,24 @@ function CommandsPage() {
   const [context, setContext] = useState<CommandContext>({
     noteId,
     autoSize,
+    archived,
+    pinned,
   });
 
+  const queryClient = useQueryClient();
+  const { data: templates } = useQuery(listTemplatesOptions());
+  const { data: layouts } = useQuery(listLayoutsOptions());
+
   const commandGroups = useMemo<CommandGroupItems[]>(() => {
-    return filterCommandGroups(context, search).map((group) => ({
+    const extraGroups = [
+      ...(templates?.length ? [templateCommandGroup(templates)] : []),
+      layoutCommandGroup(layouts ?? [], search),
+    ];
+    return filterCommandGroups(context, search, extraGroups).map((group) => ({
       ...group,
       items: group.children,
     }));
-  }, [context, search]);
+  }, [context, search, templates, layouts]);
 
   // Closing is unified with focus: giving the parent window focus back
   // makes the panel lose it, and the native side hides it on blur.
@@ -96,13 +113,17 @@ function CommandsPage() {
         setSearch('');
         setContext(event.payload);
         inputRef.current?.focus();
+        // Templates are plain files, added and removed outside the app;
+        // layouts change from the tray too.
+        queryClient.invalidateQueries(listTemplatesOptions());
+        queryClient.invalidateQueries(listLayoutsOptions());
       }
     );
 
     return () => {
       unlisten.then((fn) => fn());
     };
-  }, []);
+  }, [queryClient]);
 
   useHotkey('Escape', dismiss);
   useHotkey('Mod+K', dismiss);
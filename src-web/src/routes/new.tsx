@@ -1,17 +1,45 @@
-import type { Note } from '@sticky/models';
-import { createFileRoute, redirect } from '@tanstack/react-router';
+import type { Note, NoteTemplate } from '@sticky/models';
+import { createFileRoute, redirect, useNavigate } from '@tanstack/react-router';
 import { invoke } from '@tauri-apps/api/core';
+import { getCurrentWindow } from '@tauri-apps/api/window';
+import { useLayoutEffect, useRef, useState } from 'react';
 import { SkeletonEditor } from '~/components/skeleton-editor';
+import { Button } from '~/components/ui/button';
+import { Input } from '~/components/ui/input';
+import { Text } from '~/components/ui/text';
+
+type SearchParams = {
+  template?: string;
+};
 
 export const Route = createFileRoute('/new')({
   component: RouteComponent,
-  beforeLoad: async () => {
-    const note = await invoke<Note>('cmd_upsert_note', {
-      note: {
-        model: 'note',
-        content: '',
-      },
-    });
+  validateSearch: (search: Record<string, unknown>): SearchParams => ({
+    template:
+      typeof search.template === 'string' ? search.template : undefined,
+  }),
+  beforeLoad: async ({ search }) => {
+    let note: Note | undefined;
+    if (search.template) {
+      const templates = await invoke<NoteTemplate[]>('cmd_list_templates');
+      const template = templates.find((t) => t.name === search.template);
+      // A template with prompts is filled in by the form below first.
+      if (template && template.prompts.length > 0) {
+        return { template };
+      }
+
+      note = await invoke<Note>('cmd_create_from_template', {
+        template: search.template,
+        values: {},
+      });
+    } else {
+      note = await invoke<Note>('cmd_upsert_note', {
+        note: {
+          model: 'note',
+          content: '',
+        },
+      });
+    }
 
     if (!note) {
       return;
@@ -27,5 +55,111 @@ export const Route = createFileRoute('/new')({
 });
 
 function RouteComponent() {
+  const context = Route.useRouteContext();
+  const template = 'template' in context ? context.template : undefined;
+
+  if (template) {
+    return <TemplateForm template={template} />;
+  }
+
   return <SkeletonEditor />;
 }
+
+type TemplateFormProps = {
+  template: NoteTemplate;
+};
+
+function TemplateForm(props: TemplateFormProps) {
+  const { template } = props;
+
+  const navigate = useNavigate();
+  const [values, setValues] = useState<Record<string, string>>({});
+  const [isCreating, setIsCreating] = useState(false);
+
+  const firstInputRef = useRef<HTMLInputElement>(null);
+  const isShownRef = useRef(false);
+
+  const handleSubmit = async (e: React.FormEvent<HTMLFormElement>) => {
+    e.preventDefault();
+    if (isCreating) {
+      return;
+    }
+
+    setIsCreating(true);
+    try {
+      const note = await invoke<Note>('cmd_create_from_template', {
+        template: template.name,
+        values,
+      });
+      navigate({
+        to: '/$noteId',
+        params: {
+          noteId: note.id,
+        },
+        replace: true,
+      });
+    } catch (error) {
+      setIsCreating(false);
+      await invoke('cmd_show_toast', { message: String(error) });
+    }
+  };
+
+  // Note windows are created invisible and revealed by the editor;
+  // there is no editor yet, so reveal the form here.
+  useLayoutEffect(() => {
+    if (isShownRef.current) {
+      return;
+    }
+
+    isShownRef.current = true;
+    const reveal = async () => {
+      const currentWindow = getCurrentWindow();
+      await currentWindow.show();
+      await currentWindow.setFocus();
+      // The autofocus attribute fired while the window was still
+      // hidden, which WebKit ignores; focus for real now.
+      firstInputRef.current?.focus();
+    };
+
+    reveal();
+  }, []);
+
+  return (
+    <main className="bg-background caret-accent h-screen overflow-y-auto">
+      <form
+        className="flex flex-col gap-3 p-4"
+        data-tauri-drag-region
+        onSubmit={handleSubmit}
+      >
+        <Text size="2" className="font-medium">
+          {template.name}
+        </Text>
+
+        {template.prompts.map((prompt, index) => (
+          <label key={prompt} className="flex flex-col gap-1">
+            <Text size="1" className="text-muted-foreground">
+              {prompt}
+            </Text>
+            <Input
+              ref={index === 0 ? firstInputRef : undefined}
+              value={values[prompt] ?? ''}
+              onChange={(e) =>
+                setValues((current) => ({
+                  ...current,
+                  [prompt]: e.target.value,
+                }))
+              }
+              autoFocus={index === 0}
+              spellCheck={false}
+              autoComplete="off"
+            />
+          </label>
+        ))}
+
+        <Button type="submit" disabled={isCreating} className="self-end">
+          Create Note
+        </Button>
+      </form>
+    </main>
+  );
+}